@@ -0,0 +1,82 @@
+//! Generate the table of asset files that `lib.rs` builds into the binary. These are the files in
+//! this directory (the default asset pack), unless the `RUSTY_ENGINE_ASSETS` environment variable
+//! names a different directory to embed instead, like a game's own `assets/` directory.
+
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+};
+
+/// Files in the top of this directory which belong to the crate, not the asset pack
+const CRATE_FILES: &[&str] = &[
+    "Cargo.toml",
+    "Cargo.toml.orig",
+    "Cargo.lock",
+    "build.rs",
+    "lib.rs",
+    "target",
+    ".cargo_vcs_info.json",
+];
+
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-env-changed=RUSTY_ENGINE_ASSETS");
+    let assets_dir = match env::var_os("RUSTY_ENGINE_ASSETS") {
+        Some(assets_dir) => PathBuf::from(assets_dir),
+        None => PathBuf::from(env::var("CARGO_MANIFEST_DIR").unwrap()),
+    };
+    println!("cargo:rerun-if-changed={}", assets_dir.to_string_lossy());
+
+    let mut files = Vec::new();
+    collect_files(&assets_dir, &assets_dir, &mut files);
+    files.sort();
+
+    let mut table = String::from("&[\n");
+    for file in files {
+        // Asset paths always use forward slashes, whatever the platform
+        let relative_path = file
+            .strip_prefix(&assets_dir)
+            .unwrap()
+            .iter()
+            .map(|component| component.to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+        table.push_str(&format!(
+            "    ({:?}, include_bytes!({:?})),\n",
+            relative_path,
+            file.to_string_lossy()
+        ));
+    }
+    table.push(']');
+
+    let out_dir = PathBuf::from(env::var("OUT_DIR").unwrap());
+    fs::write(out_dir.join("files.rs"), table).unwrap();
+}
+
+/// Find all the asset files in a directory and its subdirectories. The license READMEs aren't
+/// needed at runtime, so they are left out, as are the files of this crate.
+fn collect_files(assets_dir: &Path, dir: &Path, files: &mut Vec<PathBuf>) {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) => panic!(
+            "could not read the assets directory {}: {}",
+            dir.to_string_lossy(),
+            e
+        ),
+    };
+    for entry in entries {
+        let path = entry.unwrap().path();
+        if dir == assets_dir
+            && path
+                .file_name()
+                .map_or(false, |name| CRATE_FILES.iter().any(|file| name == *file))
+        {
+            continue;
+        }
+        if path.is_dir() {
+            collect_files(assets_dir, &path, files);
+        } else if path.extension().map_or(true, |extension| extension != "md") {
+            files.push(path);
+        }
+    }
+}
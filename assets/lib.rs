@@ -0,0 +1,8 @@
+//! The default asset pack for [Rusty Engine](https://github.com/CleanCut/rusty_engine): the sprites,
+//! sounds, music, and fonts that the presets use. You don't need to use this crate directly.
+//! Enable the `embedded_assets` feature of `rusty_engine` to build these files into your game, so
+//! that it runs without an `assets/` directory next to it.
+
+/// Every file in the asset pack, by path relative to this directory (with forward slashes). This is
+/// generated by `build.rs`.
+pub static FILES: &[(&str, &[u8])] = include!(concat!(env!("OUT_DIR"), "/files.rs"));
@@ -0,0 +1,92 @@
+//! To run this code, clone the rusty_engine repository and run the command:
+//!
+//!     cargo run --release --example audio_settings
+
+//! This is an example of an options menu for sound: each audio bus has a volume and can be muted,
+//! and the settings can be saved.
+
+use rusty_engine::prelude::*;
+
+const BUSES: [&str; 4] = [MASTER_BUS, MUSIC_BUS, SFX_BUS, "ui"];
+
+struct GameState {
+    selected: usize,
+}
+
+fn main() {
+    let mut game = Game::new();
+
+    let instructions = game.add_text(
+        "instructions",
+        "Up/Down: choose a bus   Left/Right: volume   M: mute\nSpace: sound effect   Enter: ui sound   S: print the settings",
+    );
+    instructions.translation.y = 250.0;
+    instructions.font_size = 24.0;
+
+    for (i, _) in BUSES.iter().enumerate() {
+        let text = game.add_text(format!("bus {}", i), "");
+        text.translation = Vec2::new(0.0, 100.0 - 60.0 * i as f32);
+        text.font = "font/FiraMono-Medium.ttf".to_string();
+    }
+
+    // The "ui" bus is one of our own
+    game.audio_manager.set_bus_volume("ui", 0.8);
+    game.audio_manager
+        .play_music(MusicPreset::WhimsicalPopsicle, 1.0);
+
+    game.add_logic(logic);
+    game.run(GameState { selected: 0 });
+}
+
+fn logic(engine: &mut Engine, game_state: &mut GameState) {
+    let keyboard = &engine.keyboard_state;
+    let audio_manager = &mut engine.audio_manager;
+    if keyboard.just_pressed(KeyCode::Up) {
+        game_state.selected = (game_state.selected + BUSES.len() - 1) % BUSES.len();
+    }
+    if keyboard.just_pressed(KeyCode::Down) {
+        game_state.selected = (game_state.selected + 1) % BUSES.len();
+    }
+
+    let bus = BUSES[game_state.selected];
+    let volume = audio_manager.bus_volume(bus);
+    if keyboard.pressed(KeyCode::Right) {
+        audio_manager.set_bus_volume(bus, volume + engine.delta_f32 * 0.5);
+    }
+    if keyboard.pressed(KeyCode::Left) {
+        audio_manager.set_bus_volume(bus, volume - engine.delta_f32 * 0.5);
+    }
+    if keyboard.just_pressed(KeyCode::M) {
+        let muted = audio_manager.bus_muted(bus);
+        audio_manager.set_bus_muted(bus, !muted);
+    }
+
+    if keyboard.just_pressed(KeyCode::Space) {
+        audio_manager.play_sfx(SfxPreset::Jingle1, 1.0);
+    }
+    if keyboard.just_pressed(KeyCode::Return) {
+        let click = audio_manager.play_sfx(SfxPreset::Click, 1.0);
+        click.set_bus("ui");
+    }
+    if keyboard.just_pressed(KeyCode::S) {
+        // Any serde format works. Save this to a file to remember the player's settings.
+        println!("{}", ron::to_string(audio_manager.settings()).unwrap());
+    }
+
+    for (i, bus) in BUSES.iter().enumerate() {
+        let selected = if i == game_state.selected { ">" } else { " " };
+        let muted = if engine.audio_manager.bus_muted(bus) {
+            "(muted)"
+        } else {
+            ""
+        };
+        let text = engine.texts.get_mut(&format!("bus {}", i)).unwrap();
+        text.value = format!(
+            "{} {:<6} {:>3.0}% {:<7}",
+            selected,
+            bus,
+            engine.audio_manager.bus_volume(bus) * 100.0,
+            muted
+        );
+    }
+}
@@ -0,0 +1,63 @@
+//! To run this code, clone the rusty_engine repository and run the command:
+//!
+//!     cargo run --release --example bitmap_text
+
+use rusty_engine::prelude::*;
+
+struct GameState {
+    score: u32,
+}
+
+fn main() {
+    let mut game = Game::new();
+
+    // A BMFont .fnt file, which has a different width for each character
+    let title = game.add_bitmap_text("title", "font/pixel_5x7.fnt", "BITMAP FONTS!");
+    title.scale = 8;
+    title.translation = Vec2::new(0.0, 200.0);
+    title.color = Color::YELLOW;
+
+    // The same glyphs as a monospaced grid: 16 columns of 6x8 pixel cells
+    let grid_font = BitmapFont::grid(
+        "font/pixel_5x7.png",
+        6,
+        8,
+        16,
+        &BITMAP_FONT_ASCII[..64], // from space to `_`
+    );
+    let score = game.add_bitmap_text("score", grid_font, "SCORE: 0");
+    score.scale = 4;
+    score.space = Space::Screen;
+    score.anchor = ScreenAnchor::TopLeft;
+    score.horizontal_align = HorizontalAlign::Left;
+    score.vertical_align = VerticalAlign::Top;
+    score.translation = Vec2::new(20.0, -20.0);
+
+    let instructions = game.add_bitmap_text(
+        "instructions",
+        "font/pixel_5x7.fnt",
+        "PRESS SPACE TO SCORE\nUP/DOWN: CHANGE THE SCALE",
+    );
+    instructions.scale = 3;
+    instructions.translation = Vec2::new(0.0, -150.0);
+
+    game.add_logic(logic);
+    game.run(GameState { score: 0 });
+}
+
+fn logic(engine: &mut Engine, game_state: &mut GameState) {
+    if engine.keyboard_state.just_pressed(KeyCode::Space) {
+        game_state.score += 10;
+        let score = engine.bitmap_texts.get_mut("score").unwrap();
+        score.value = format!("SCORE: {}", game_state.score);
+    }
+
+    let title = engine.bitmap_texts.get_mut("title").unwrap();
+    if engine.keyboard_state.just_pressed(KeyCode::Up) {
+        title.scale += 1;
+    }
+    if engine.keyboard_state.just_pressed(KeyCode::Down) && title.scale > 1 {
+        title.scale -= 1;
+    }
+    title.rotation = (engine.time_since_startup_f64 * 2.0).sin() as f32 * 0.1;
+}
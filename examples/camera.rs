@@ -0,0 +1,119 @@
+//! To run this code, clone the rusty_engine repository and run the command:
+//!
+//!     cargo run --release --example camera
+
+use std::f32::consts::PI;
+
+use rusty_engine::prelude::*;
+
+fn main() {
+    let mut game = Game::new();
+
+    let race_car = game.add_sprite("Race Car", SpritePreset::RacingCarGreen);
+    race_car.rotation = UP;
+    race_car.layer = 10.0;
+
+    // Scatter some barrels around so you can tell that the camera is moving
+    for x in -5..=5 {
+        for y in -5..=5 {
+            let preset = if (x + y) % 2 == 0 {
+                SpritePreset::RacingBarrelBlue
+            } else {
+                SpritePreset::RacingBarrelRed
+            };
+            let barrel = game.add_sprite(format!("barrel{}_{}", x, y), preset);
+            barrel.translation = Vec2::new(x as f32 * 200.0, y as f32 * 200.0);
+            barrel.scale = 0.5;
+        }
+    }
+
+    // Follow the car, lagging behind a bit, and never show anything outside of the barrels
+    game.camera.follow("Race Car");
+    game.camera.follow_smoothing = 0.25;
+    game.camera.follow_deadzone = Vec2::new(100.0, 100.0);
+    game.camera
+        .set_bounds(Vec2::new(-1100.0, -1100.0), Vec2::new(1100.0, 1100.0));
+
+    let instructions = "Camera Example\n==============\nDrive: w a s d / arrows\nZoom: mouse wheel\nRotate camera: q e\nToggle following: f\nShake: space\nZoom punch: z\nMove to the center: m\nThe mouse location is shown in game space";
+    let text = game.add_text("instructions", instructions);
+    text.font_size = 20.0;
+    // Keep the instructions fixed to the top of the window, no matter where the camera goes
+    text.space = Space::Screen;
+    text.anchor = ScreenAnchor::TopCenter;
+    text.translation.y = -110.0;
+
+    game.add_logic(logic);
+    game.run(());
+}
+
+fn logic(engine: &mut Engine, _: &mut ()) {
+    let move_amount = 400.0 * engine.delta_f32;
+    let rotation_amount = PI * 0.5 * engine.delta_f32;
+
+    let race_car = engine.sprites.get_mut("Race Car").unwrap();
+    let ks = &engine.keyboard_state;
+    if ks.pressed_any(&[KeyCode::W, KeyCode::Up]) {
+        race_car.translation.y += move_amount;
+        race_car.rotation = UP;
+    }
+    if ks.pressed_any(&[KeyCode::A, KeyCode::Left]) {
+        race_car.translation.x -= move_amount;
+        race_car.rotation = LEFT;
+    }
+    if ks.pressed_any(&[KeyCode::S, KeyCode::Down]) {
+        race_car.translation.y -= move_amount;
+        race_car.rotation = DOWN;
+    }
+    if ks.pressed_any(&[KeyCode::D, KeyCode::Right]) {
+        race_car.translation.x += move_amount;
+        race_car.rotation = RIGHT;
+    }
+    race_car.translation = race_car
+        .translation
+        .clamp(Vec2::new(-1100.0, -1100.0), Vec2::new(1100.0, 1100.0));
+
+    // Rotate the camera
+    if ks.pressed(KeyCode::Q) {
+        engine.camera.rotation += rotation_amount;
+    }
+    if ks.pressed(KeyCode::E) {
+        engine.camera.rotation -= rotation_amount;
+    }
+
+    // Toggle following the car
+    if ks.just_pressed(KeyCode::F) {
+        if engine.camera.follow_target.is_some() {
+            engine.camera.stop_following();
+        } else {
+            engine.camera.follow("Race Car");
+        }
+    }
+
+    // Camera effects
+    if ks.just_pressed(KeyCode::Space) {
+        engine.camera.shake(20.0, 0.5);
+    }
+    if ks.just_pressed(KeyCode::Z) {
+        engine.camera.zoom_punch(0.3, 0.3);
+    }
+    if ks.just_pressed(KeyCode::M) {
+        engine.camera.stop_following();
+        engine.camera.move_to(Vec2::ZERO, 1.5);
+    }
+
+    // Zoom with the mouse wheel
+    let wheel = engine.mouse_state.wheel().y;
+    if wheel != 0.0 {
+        engine.camera.zoom = (engine.camera.zoom * (1.0 + 0.1 * wheel)).clamp(0.25, 4.0);
+    }
+
+    // Show where the mouse is in game space
+    let mouse_location = engine.mouse_state.location();
+    let text = engine.texts.get_mut("instructions").unwrap();
+    if let Some(location) = mouse_location {
+        text.value = format!(
+            "Camera Example\n==============\nDrive: w a s d / arrows\nZoom: mouse wheel\nRotate camera: q e\nToggle following: f\nShake: space\nZoom punch: z\nMove to the center: m\nMouse: ({:.0}, {:.0})",
+            location.x, location.y
+        );
+    }
+}
@@ -11,6 +11,9 @@
 //! in `assets/` somewhere, and run:
 //!
 //! cargo run --release --example collider assets/some_image.png
+//!
+//! Add `--features hot_reload` to the command to have the sprite pick up changes to its `.collider`
+//! file while the example is running, for example if you edit the file by hand.
 
 use std::path::PathBuf;
 
@@ -29,14 +32,6 @@ impl Default for GameState {
 }
 
 fn main() {
-    // Some trickiness to make assets load relative to the current working directory, which
-    // makes using it from `cargo install rusty_engine --example collider` possible.
-    // This takes advantage of bevy's hard-coded asset loading behavior, and may break in future
-    // bevy versions.
-    std::env::set_var(
-        "CARGO_MANIFEST_DIR",
-        std::env::var("PWD").unwrap_or_default(),
-    );
     // Make engine logging a bit quieter since we've got console instructions we want folks to see.
     std::env::set_var("RUST_LOG", "error");
     // We need an image file to work with, so the user must pass in the path of an image
@@ -49,18 +44,22 @@ fn main() {
         std::process::exit(1);
     }
 
-    // If the user passed in `assets/something...` then we need to strip `assets/` (the asset loader will prepend `assets/`)
+    // Start with the "game" part. Creating the game sets up the asset root, so do it before looking
+    // for the image.
+    let mut game = Game::new();
+
+    // If the user passed in `assets/something...` then we need to strip `assets/` (the asset loader will prepend the asset root)
     let mut path = PathBuf::from(args[0].clone());
-    if path.starts_with("assets") {
+    if let Ok(relative_path) = path.strip_prefix(asset_root()) {
+        path = relative_path.to_path_buf();
+    } else if path.starts_with("assets") {
         path = path.strip_prefix("assets").unwrap().to_path_buf();
     }
-    if !(PathBuf::from("assets").join(&path)).exists() {
+    if !asset_path(&path).exists() {
         println!("Couldn't find the file {}", path.to_string_lossy());
         std::process::exit(1);
     }
 
-    // Start with the "game" part
-    let mut game = Game::new();
     game.show_colliders = true;
     game.window_settings(WindowDescriptor {
         title: "Collider Creator".into(),
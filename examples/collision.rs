@@ -34,6 +34,20 @@ fn main() {
         }
     }
 
+    // When colliders are visible, fill them in and turn them red while they are colliding. The
+    // player's collider also shows its bounding box and which way it is facing.
+    game.collider_debug.fill_color = Some(Color::rgba(1.0, 1.0, 1.0, 0.15));
+    game.collider_debug.colliding_color = Some(Color::RED);
+    let race_car = game.sprites.get_mut("Player").unwrap();
+    race_car.collider_debug = Some(ColliderDebugSettings {
+        colliding_color: Some(Color::RED),
+        show_bounding_box: true,
+        show_origin: true,
+        show_direction: true,
+        thickness: 2.0,
+        ..Default::default()
+    });
+
     let mut text = game.add_text("collision text", "");
     text.translation = Vec2::new(0.0, -200.0);
 
@@ -48,11 +62,11 @@ fn logic(engine: &mut Engine, _: &mut ()) {
         match collision_event.state {
             CollisionState::Begin => {
                 text.value = format!("{:?}", collision_event.pair);
-                engine.audio_manager.play_sfx(SfxPreset::Switch1, 1.0)
+                engine.audio_manager.play_sfx(SfxPreset::Switch1, 1.0);
             }
             CollisionState::End => {
                 text.value = "".into();
-                engine.audio_manager.play_sfx(SfxPreset::Switch2, 1.0)
+                engine.audio_manager.play_sfx(SfxPreset::Switch2, 1.0);
             }
         }
     }
@@ -0,0 +1,60 @@
+//! To run this code, clone the rusty_engine repository and run the command:
+//!
+//!     cargo run --release --example debug_draw
+
+use rusty_engine::prelude::*;
+
+struct GameState {
+    velocity: Vec2,
+}
+
+fn main() {
+    let mut game = Game::new();
+
+    let race_car = game.add_sprite("Race Car", SpritePreset::RacingCarBlue);
+    race_car.scale = 0.75;
+
+    let text = game.add_text(
+        "instructions",
+        "The car chases the mouse.\nDebug drawings show its velocity and target.",
+    );
+    text.space = Space::Screen;
+    text.anchor = ScreenAnchor::TopCenter;
+    text.translation.y = -40.0;
+
+    game.add_logic(logic);
+    game.run(GameState {
+        velocity: Vec2::ZERO,
+    });
+}
+
+fn logic(engine: &mut Engine, game_state: &mut GameState) {
+    let target = engine.mouse_state.location().unwrap_or(Vec2::ZERO);
+    let race_car = engine.sprites.get_mut("Race Car").unwrap();
+
+    // Steer towards the target
+    let desired = (target - race_car.translation).clamp_length_max(400.0);
+    game_state.velocity += (desired - game_state.velocity) * 2.0 * engine.delta_f32;
+    race_car.translation += game_state.velocity * engine.delta_f32;
+    if game_state.velocity.length() > 1.0 {
+        race_car.rotation = game_state.velocity.y.atan2(game_state.velocity.x);
+    }
+    let location = race_car.translation;
+
+    // These only last for this frame, so we draw them again every frame
+    engine.debug_draw.arrow(
+        location,
+        location + game_state.velocity * 0.5,
+        Color::YELLOW,
+    );
+    engine.debug_draw.line(location, target, Color::GRAY);
+    engine.debug_draw.circle(target, 20.0, Color::RED);
+    engine
+        .debug_draw
+        .rect(location, Vec2::new(100.0, 100.0), Color::GREEN);
+    engine.debug_draw.text(
+        location + Vec2::new(0.0, 70.0),
+        format!("speed: {:.0}", game_state.velocity.length()),
+        Color::WHITE,
+    );
+}
@@ -35,14 +35,6 @@ impl Default for GameState {
 const MAX_LAYER: f32 = 900.0;
 
 fn main() {
-    // Some trickiness to make assets load relative to the current working directory, which
-    // makes using it from `cargo install rusty_engine --example collider` possible.
-    // This takes advantage of bevy's hard-coded asset loading behavior, and may break in future
-    // bevy versions.
-    std::env::set_var(
-        "CARGO_MANIFEST_DIR",
-        std::env::var("PWD").unwrap_or_default(),
-    );
     // Make engine logging a bit quieter since we've got console instructions we want folks to see.
     std::env::set_var("RUST_LOG", "error");
 
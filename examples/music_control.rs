@@ -0,0 +1,84 @@
+//! To run this code, clone the rusty_engine repository and run the command:
+//!
+//!     cargo run --release --example music_control
+
+//! This is an example of crossfading between pieces of music, and of controlling the music while it
+//! plays.
+
+use rusty_engine::prelude::*;
+
+struct GameState {
+    music_index: usize,
+}
+
+fn main() {
+    let mut game = Game::new();
+
+    let instructions = game.add_text(
+        "instructions",
+        "Space: crossfade to the next music\nP: pause/resume\nUp/Down: volume\nLeft/Right: speed\nS: stop",
+    );
+    instructions.translation.y = 100.0;
+
+    let status = game.add_text("status", "");
+    status.translation.y = -150.0;
+    status.font = "font/FiraMono-Medium.ttf".to_string();
+
+    game.audio_manager.play_music(MusicPreset::Classy8Bit, 0.5);
+
+    game.add_logic(logic);
+    game.run(GameState { music_index: 0 });
+}
+
+fn logic(engine: &mut Engine, game_state: &mut GameState) {
+    let keyboard = &engine.keyboard_state;
+    let audio_manager = &mut engine.audio_manager;
+    if keyboard.just_pressed(KeyCode::Space) {
+        game_state.music_index = (game_state.music_index + 1) % MusicPreset::variant_iter().len();
+        let music_preset = MusicPreset::variant_iter()
+            .nth(game_state.music_index)
+            .unwrap();
+        let volume = audio_manager.music_volume().unwrap_or(0.5);
+        audio_manager.crossfade_to(music_preset, volume, 3.0);
+    }
+    if keyboard.just_pressed(KeyCode::S) {
+        audio_manager.stop_music();
+    }
+    if keyboard.just_pressed(KeyCode::P) {
+        if audio_manager.music_paused() {
+            audio_manager.resume_music();
+        } else {
+            audio_manager.pause_music();
+        }
+    }
+    if let Some(volume) = audio_manager.music_volume() {
+        if keyboard.pressed(KeyCode::Up) {
+            audio_manager.set_music_volume(volume + engine.delta_f32);
+        }
+        if keyboard.pressed(KeyCode::Down) {
+            audio_manager.set_music_volume(volume - engine.delta_f32);
+        }
+    }
+    if let Some(speed) = audio_manager.music_speed() {
+        if keyboard.pressed(KeyCode::Right) {
+            audio_manager.set_music_speed(speed + engine.delta_f32);
+        }
+        if keyboard.pressed(KeyCode::Left) {
+            audio_manager.set_music_speed((speed - engine.delta_f32).max(0.1));
+        }
+    }
+
+    let status = engine.texts.get_mut("status").unwrap();
+    status.value = match (
+        engine.audio_manager.music_volume(),
+        engine.audio_manager.music_speed(),
+    ) {
+        (Some(volume), Some(speed)) => format!(
+            "volume {:.2}  speed {:.2}  playing {}",
+            volume,
+            speed,
+            engine.audio_manager.music_playing()
+        ),
+        _ => "No music".to_string(),
+    };
+}
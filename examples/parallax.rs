@@ -0,0 +1,62 @@
+//! To run this code, clone the rusty_engine repository and run the command:
+//!
+//!     cargo run --release --example parallax
+
+use rusty_engine::prelude::*;
+
+fn main() {
+    let mut game = Game::new();
+
+    // A far-away wall of blocks which fills the whole window and barely moves
+    let far = game.add_parallax_layer("far", "sprite/rolling/block_square.png");
+    far.scroll_factor = Vec2::new(0.1, 0.1);
+    far.repeat_y = true;
+    far.scale = 0.5;
+    far.layer = 0.0;
+
+    // A row of barriers in the middle distance
+    let middle = game.add_parallax_layer("middle", "sprite/racing/barrier_white.png");
+    middle.scroll_factor = Vec2::new(0.5, 0.5);
+    middle.offset = Vec2::new(0.0, -100.0);
+    middle.layer = 1.0;
+
+    // A row of cones that is closer to us than the car, so it moves faster than the world
+    let near = game.add_parallax_layer("near", "sprite/racing/cone_straight.png");
+    near.scroll_factor = Vec2::new(1.5, 1.0);
+    near.offset = Vec2::new(0.0, -250.0);
+    near.layer = 20.0;
+
+    let race_car = game.add_sprite("Race Car", SpritePreset::RacingCarRed);
+    race_car.translation.y = -175.0;
+    race_car.layer = 10.0;
+
+    game.camera.follow("Race Car");
+    game.camera.follow_smoothing = 0.1;
+
+    let text = game.add_text("instructions", "Parallax Example\nDrive: a d / arrows");
+    text.space = Space::Screen;
+    text.anchor = ScreenAnchor::TopCenter;
+    text.translation.y = -50.0;
+
+    game.add_logic(logic);
+    game.run(());
+}
+
+fn logic(engine: &mut Engine, _: &mut ()) {
+    let move_amount = 500.0 * engine.delta_f32;
+    let race_car = engine.sprites.get_mut("Race Car").unwrap();
+    if engine
+        .keyboard_state
+        .pressed_any(&[KeyCode::A, KeyCode::Left])
+    {
+        race_car.translation.x -= move_amount;
+        race_car.rotation = LEFT;
+    }
+    if engine
+        .keyboard_state
+        .pressed_any(&[KeyCode::D, KeyCode::Right])
+    {
+        race_car.translation.x += move_amount;
+        race_car.rotation = RIGHT;
+    }
+}
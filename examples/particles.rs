@@ -0,0 +1,105 @@
+//! To run this code, clone the rusty_engine repository and run the command:
+//!
+//!     cargo run --release --example particles
+
+use std::f32::consts::PI;
+
+use rusty_engine::prelude::*;
+
+fn main() {
+    let mut game = Game::new();
+
+    let race_car = game.add_sprite("Race Car", SpritePreset::RacingCarRed);
+    race_car.scale = 0.75;
+    race_car.layer = 10.0;
+
+    // Smoke comes out of the back of the car and is left behind as it drives
+    let smoke = game.add_particle_emitter("smoke", ShapeKind::Circle { radius: 6.0 });
+    smoke.attach_to = Some("Race Car".into());
+    smoke.translation = Vec2::new(-50.0, 0.0);
+    smoke.rotation = PI;
+    smoke.angle = -0.3..=0.3;
+    smoke.speed = 20.0..=60.0;
+    smoke.lifetime = 0.8..=1.5;
+    smoke.rate = 40.0;
+    smoke.start_color = Color::GRAY;
+    smoke.end_color = Color::DARK_GRAY;
+    smoke.start_alpha = 0.6;
+    smoke.end_scale = 3.0;
+    smoke.layer = 5.0;
+
+    // A fountain of sparks that falls back down
+    let sparks = game.add_particle_emitter(
+        "sparks",
+        ShapeKind::Rect {
+            size: Vec2::new(6.0, 2.0),
+        },
+    );
+    sparks.translation = Vec2::new(0.0, -250.0);
+    sparks.rotation = UP;
+    sparks.angle = -0.4..=0.4;
+    sparks.speed = 300.0..=450.0;
+    sparks.spin = -10.0..=10.0;
+    sparks.gravity = Vec2::new(0.0, -600.0);
+    sparks.lifetime = 1.0..=1.5;
+    sparks.rate = 100.0;
+    sparks.start_color = Color::YELLOW;
+    sparks.end_color = Color::ORANGE_RED;
+    sparks.end_alpha = 0.5;
+
+    // Explosions are emitted all at once with a burst, so they don't emit anything on their own
+    let explosion = game.add_particle_emitter("explosion", SpritePreset::RollingBallRed);
+    explosion.rate = 0.0;
+    explosion.speed = 100.0..=400.0;
+    explosion.lifetime = 0.3..=0.8;
+    explosion.start_scale = 0.3;
+    explosion.end_scale = 0.05;
+    explosion.layer = 20.0;
+
+    let text = game.add_text(
+        "instructions",
+        "Drive: w a s d / arrows\nClick: explode\nSpace: toggle the sparks",
+    );
+    text.space = Space::Screen;
+    text.anchor = ScreenAnchor::TopCenter;
+    text.translation.y = -50.0;
+
+    game.add_logic(logic);
+    game.run(());
+}
+
+fn logic(engine: &mut Engine, _: &mut ()) {
+    // Drive the car around
+    let race_car = engine.sprites.get_mut("Race Car").unwrap();
+    let ks = &engine.keyboard_state;
+    if ks.pressed_any(&[KeyCode::A, KeyCode::Left]) {
+        race_car.rotation += 3.0 * engine.delta_f32;
+    }
+    if ks.pressed_any(&[KeyCode::D, KeyCode::Right]) {
+        race_car.rotation -= 3.0 * engine.delta_f32;
+    }
+    let mut speed = 0.0;
+    if ks.pressed_any(&[KeyCode::W, KeyCode::Up]) {
+        speed = 300.0;
+    }
+    if ks.pressed_any(&[KeyCode::S, KeyCode::Down]) {
+        speed = -150.0;
+    }
+    race_car.translation +=
+        Vec2::new(race_car.rotation.cos(), race_car.rotation.sin()) * speed * engine.delta_f32;
+
+    // Blow things up wherever you click
+    if engine.mouse_state.just_pressed(MouseButton::Left) {
+        if let Some(location) = engine.mouse_state.location() {
+            let explosion = engine.particle_emitters.get_mut("explosion").unwrap();
+            explosion.translation = location;
+            explosion.burst(60);
+        }
+    }
+
+    // Turn the fountain of sparks on and off
+    if engine.keyboard_state.just_pressed(KeyCode::Space) {
+        let sparks = engine.particle_emitters.get_mut("sparks").unwrap();
+        sparks.emitting = !sparks.emitting;
+    }
+}
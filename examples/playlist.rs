@@ -0,0 +1,86 @@
+//! To run this code, clone the rusty_engine repository and run the command:
+//!
+//!     cargo run --release --example playlist
+
+//! This is an example of playing music once, chaining it into a playlist, and reacting to audio
+//! events.
+
+use rusty_engine::prelude::*;
+
+struct GameState {
+    intro_playing: bool,
+}
+
+fn main() {
+    let mut game = Game::new();
+
+    let instructions = game.add_text(
+        "instructions",
+        "An intro plays once, then a playlist starts\nN: next track   1: sequential   2: shuffle   3: loop all\nSpace: sound effect",
+    );
+    instructions.translation.y = 200.0;
+
+    let track = game.add_text("track", "");
+    track.font_size = 40.0;
+
+    let events = game.add_text("events", "");
+    events.translation.y = -200.0;
+    events.color = Color::YELLOW;
+
+    game.audio_manager
+        .play_music_once(MusicPreset::MysteriousMagic, 0.5);
+
+    game.add_logic(logic);
+    game.run(GameState {
+        intro_playing: true,
+    });
+}
+
+fn playlist(mode: PlaylistMode) -> Playlist {
+    Playlist::new(MusicPreset::variant_iter(), mode)
+}
+
+fn logic(engine: &mut Engine, game_state: &mut GameState) {
+    for audio_event in &engine.audio_events {
+        let message = match audio_event {
+            AudioEvent::MusicFinished { music } => {
+                // Chain the intro into the playlist
+                if game_state.intro_playing {
+                    game_state.intro_playing = false;
+                    engine
+                        .audio_manager
+                        .play_playlist(playlist(PlaylistMode::LoopAll), 0.5);
+                }
+                format!("Finished {}", music)
+            }
+            AudioEvent::PlaylistFinished => "The playlist is over".to_string(),
+            AudioEvent::SfxFinished { sfx, .. } => format!("Finished {}", sfx),
+        };
+        engine.texts.get_mut("events").unwrap().value = message;
+    }
+
+    let keyboard = &engine.keyboard_state;
+    let audio_manager = &mut engine.audio_manager;
+    let modes = [
+        (KeyCode::Key1, PlaylistMode::Sequential),
+        (KeyCode::Key2, PlaylistMode::Shuffle),
+        (KeyCode::Key3, PlaylistMode::LoopAll),
+    ];
+    for (key_code, mode) in modes {
+        if keyboard.just_pressed(key_code) {
+            game_state.intro_playing = false;
+            audio_manager.play_playlist(playlist(mode), 0.5);
+        }
+    }
+    if keyboard.just_pressed(KeyCode::N) {
+        audio_manager.next_track();
+    }
+    if keyboard.just_pressed(KeyCode::Space) {
+        audio_manager.play_sfx(SfxPreset::Confirmation1, 1.0);
+    }
+
+    engine.texts.get_mut("track").unwrap().value = match audio_manager.music_track() {
+        Some(track) => format!("Playing {}", track),
+        None => "No music".to_string(),
+    };
+}
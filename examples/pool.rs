@@ -0,0 +1,91 @@
+//! To run this code, clone the rusty_engine repository and run the command:
+//!
+//!     cargo run --release --example pool
+
+use rusty_engine::prelude::*;
+
+const BULLET_SPEED: f32 = 800.0;
+
+struct GameState {
+    fire_timer: Timer,
+}
+
+fn main() {
+    let mut game = Game::new();
+
+    let race_car = game.add_sprite("Race Car", SpritePreset::RacingCarBlue);
+    race_car.translation = Vec2::new(0.0, -300.0);
+    race_car.rotation = UP;
+    race_car.scale = 0.75;
+
+    // All the bullets are created once up front, and reused after that
+    let bullets = game.pool("bullet", SpritePreset::RollingBallBlue, 200);
+    bullets.template.scale = 0.2;
+    bullets.template.layer = 5.0;
+    bullets.template.rotation = UP;
+
+    let text = game.add_text("instructions", "Move: a d / arrows\nHold Space: shoot");
+    text.font_size = 20.0;
+    text.space = Space::Screen;
+    text.anchor = ScreenAnchor::TopLeft;
+    text.translation = Vec2::new(120.0, -40.0);
+
+    let available = game.add_text("available", "");
+    available.font_size = 20.0;
+    available.space = Space::Screen;
+    available.anchor = ScreenAnchor::TopRight;
+    available.translation = Vec2::new(-150.0, -40.0);
+
+    game.add_logic(logic);
+    game.run(GameState {
+        fire_timer: Timer::from_seconds(0.02, true),
+    });
+}
+
+fn logic(engine: &mut Engine, game_state: &mut GameState) {
+    // Move the car
+    let race_car = engine.sprites.get_mut("Race Car").unwrap();
+    if engine
+        .keyboard_state
+        .pressed_any(&[KeyCode::A, KeyCode::Left])
+    {
+        race_car.translation.x -= 400.0 * engine.delta_f32;
+    }
+    if engine
+        .keyboard_state
+        .pressed_any(&[KeyCode::D, KeyCode::Right])
+    {
+        race_car.translation.x += 400.0 * engine.delta_f32;
+    }
+    let gun = race_car.translation + Vec2::new(0.0, 50.0);
+
+    // Shoot by taking bullets out of the pool
+    if game_state.fire_timer.tick(engine.delta).just_finished()
+        && engine.keyboard_state.pressed(KeyCode::Space)
+    {
+        if let Some(bullet) = engine.acquire("bullet") {
+            bullet.translation = gun;
+        }
+    }
+
+    // Move the bullets, and put them back in the pool once they leave the screen
+    let top = engine.window_dimensions.y * 0.5 + 20.0;
+    let mut offscreen = Vec::new();
+    for bullet in engine
+        .sprites
+        .values_mut()
+        .filter(|sprite| sprite.visible && sprite.label.starts_with("bullet#"))
+    {
+        bullet.translation.y += BULLET_SPEED * engine.delta_f32;
+        if bullet.translation.y > top {
+            offscreen.push(bullet.label.clone());
+        }
+    }
+    for label in offscreen {
+        engine.release(&label);
+    }
+
+    let available = engine.pools.get("bullet").unwrap().available();
+    engine.texts.get_mut("available").unwrap().value =
+        format!("Bullets in the pool: {}", available);
+}
@@ -809,7 +809,9 @@ fn main() {
 
     // Stuff used to keep and display score
     let score_text = game.add_text("score_text", "Score: 0");
-    score_text.translation = Vec2::new(-10.0, 82.0);
+    score_text.space = Space::Screen;
+    score_text.anchor = ScreenAnchor::TopCenter;
+    score_text.translation = Vec2::new(0.0, -30.0);
     score_text.font_size = 24.0;
 
     // Win condition
@@ -7,10 +7,12 @@ use rusty_engine::prelude::*;
 
 const PLAYER_SPEED: f32 = 250.0;
 const ROAD_SPEED: f32 = 400.0;
+const RIVAL_SPEED: f32 = 300.0;
 
 struct GameState {
     health_amount: u8,
     lost: bool,
+    rival_engine: SfxHandle,
 }
 
 fn main() {
@@ -22,6 +24,17 @@ fn main() {
     player1.layer = 10.0;
     player1.collision = true;
 
+    // A rival car that keeps passing the player. Its engine sound comes from the car, so you can
+    // hear it go by from left to right.
+    let rival = game.add_sprite("rival", SpritePreset::RacingCarRed);
+    rival.translation = Vec2::new(-900.0, 200.0);
+    rival.layer = 9.0;
+    let rival_engine = game
+        .audio_manager
+        .play_sfx_on(SfxPreset::Forcefield1, "rival", 0.4);
+    rival_engine.set_looping(true);
+    game.audio_manager.set_listener("player1");
+
     // Start some background music
     game.audio_manager
         .play_music(MusicPreset::WhimsicalPopsicle, 0.2);
@@ -60,6 +73,7 @@ fn main() {
     game.run(GameState {
         health_amount: 5,
         lost: false,
+        rival_engine,
     });
 }
 
@@ -109,6 +123,14 @@ fn game_logic(engine: &mut Engine, game_state: &mut GameState) {
         }
     }
 
+    // Drive the rival past the player, in a new lane each time
+    let rival = engine.sprites.get_mut("rival").unwrap();
+    rival.translation.x += RIVAL_SPEED * engine.delta_f32;
+    if rival.translation.x > 900.0 {
+        rival.translation.x = -900.0;
+        rival.translation.y = thread_rng().gen_range(-300.0..300.0);
+    }
+
     // Deal with collisions
     let health_message = engine.texts.get_mut("health_message").unwrap();
     for event in engine.collision_events.drain(..) {
@@ -119,7 +141,11 @@ fn game_logic(engine: &mut Engine, game_state: &mut GameState) {
         if game_state.health_amount > 0 {
             game_state.health_amount -= 1;
             health_message.value = format!("Health: {}", game_state.health_amount);
-            engine.audio_manager.play_sfx(SfxPreset::Impact3, 0.5);
+            let player_location = engine.sprites["player1"].translation;
+            engine
+                .audio_manager
+                .play_sfx_at(SfxPreset::Impact3, player_location, 0.5);
+            engine.camera.shake(12.0, 0.4);
         }
     }
     if game_state.health_amount == 0 {
@@ -127,6 +153,7 @@ fn game_logic(engine: &mut Engine, game_state: &mut GameState) {
         let game_over = engine.add_text("game over", "Game Over");
         game_over.font_size = 128.0;
         engine.audio_manager.stop_music();
+        game_state.rival_engine.stop();
         engine.audio_manager.play_sfx(SfxPreset::Jingle3, 0.5);
     }
 }
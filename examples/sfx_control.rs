@@ -0,0 +1,82 @@
+//! To run this code, clone the rusty_engine repository and run the command:
+//!
+//!     cargo run --release --example sfx_control
+
+//! This is an example of controlling a sound effect while it plays, using the `SfxHandle` that
+//! `play_sfx` returns.
+
+use rusty_engine::prelude::*;
+
+#[derive(Default)]
+struct GameState {
+    hum: Option<SfxHandle>,
+}
+
+fn main() {
+    let mut game = Game::new();
+
+    let instructions = game.add_text(
+        "instructions",
+        "Space: start/stop a looping sound\nP: pause/resume\nUp/Down: volume\nLeft/Right: speed\nL: toggle looping",
+    );
+    instructions.translation.y = 100.0;
+
+    let status = game.add_text("status", "");
+    status.translation.y = -150.0;
+    status.font = "font/FiraMono-Medium.ttf".to_string();
+
+    game.add_logic(logic);
+    game.run(GameState::default());
+}
+
+fn logic(engine: &mut Engine, game_state: &mut GameState) {
+    let keyboard = &engine.keyboard_state;
+    if keyboard.just_pressed(KeyCode::Space) {
+        match game_state.hum.take() {
+            Some(hum) if hum.is_playing() => hum.stop(),
+            _ => {
+                let hum = engine.audio_manager.play_sfx(SfxPreset::Forcefield1, 0.5);
+                hum.set_looping(true);
+                game_state.hum = Some(hum);
+            }
+        }
+    }
+
+    let status = engine.texts.get_mut("status").unwrap();
+    let hum = match &game_state.hum {
+        Some(hum) if hum.is_playing() => hum,
+        _ => {
+            status.value = "Not playing".to_string();
+            return;
+        }
+    };
+    if keyboard.just_pressed(KeyCode::P) {
+        if hum.is_paused() {
+            hum.resume();
+        } else {
+            hum.pause();
+        }
+    }
+    if keyboard.just_pressed(KeyCode::L) {
+        hum.set_looping(!hum.looping());
+    }
+    if keyboard.pressed(KeyCode::Up) {
+        hum.set_volume(hum.volume() + engine.delta_f32);
+    }
+    if keyboard.pressed(KeyCode::Down) {
+        hum.set_volume(hum.volume() - engine.delta_f32);
+    }
+    if keyboard.pressed(KeyCode::Right) {
+        hum.set_speed(hum.speed() + engine.delta_f32);
+    }
+    if keyboard.pressed(KeyCode::Left) {
+        hum.set_speed((hum.speed() - engine.delta_f32).max(0.1));
+    }
+    status.value = format!(
+        "volume {:.2}  speed {:.2}  looping {}  paused {}",
+        hum.volume(),
+        hum.speed(),
+        hum.looping(),
+        hum.is_paused()
+    );
+}
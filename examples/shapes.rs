@@ -0,0 +1,110 @@
+//! To run this code, clone the rusty_engine repository and run the command:
+//!
+//!     cargo run --release --example shapes
+
+use rusty_engine::prelude::*;
+
+fn main() {
+    let mut game = Game::new();
+
+    // Shapes don't need any image files
+    let square = game.add_shape(
+        "square",
+        ShapeKind::Rect {
+            size: Vec2::new(120.0, 120.0),
+        },
+    );
+    square.translation = Vec2::new(-300.0, 100.0);
+    square.fill_color = Some(Color::ORANGE);
+    square.collision = true;
+
+    let rounded = game.add_shape(
+        "rounded",
+        ShapeKind::RoundedRect {
+            size: Vec2::new(200.0, 100.0),
+            radius: 25.0,
+        },
+    );
+    rounded.translation = Vec2::new(0.0, 150.0);
+    rounded.fill_color = Some(Color::TEAL);
+    rounded.stroke_color = Some(Color::WHITE);
+    rounded.stroke_width = 4.0;
+    rounded.collision = true;
+
+    let triangle = game.add_shape(
+        "triangle",
+        ShapeKind::Polygon {
+            points: vec![
+                Vec2::new(0.0, 70.0),
+                Vec2::new(-60.0, -40.0),
+                Vec2::new(60.0, -40.0),
+            ],
+        },
+    );
+    triangle.translation = Vec2::new(300.0, 100.0);
+    triangle.fill_color = Some(Color::PURPLE);
+    triangle.collision = true;
+
+    let zigzag = game.add_shape(
+        "zigzag",
+        ShapeKind::Path {
+            points: vec![
+                Vec2::new(-200.0, 0.0),
+                Vec2::new(-100.0, 50.0),
+                Vec2::new(0.0, 0.0),
+                Vec2::new(100.0, 50.0),
+                Vec2::new(200.0, 0.0),
+            ],
+            closed: false,
+        },
+    );
+    zigzag.translation = Vec2::new(0.0, -150.0);
+    zigzag.fill_color = None;
+    zigzag.stroke_color = Some(Color::YELLOW);
+    zigzag.stroke_width = 6.0;
+
+    let line = game.add_shape(
+        "line",
+        ShapeKind::Line {
+            start: Vec2::new(-500.0, -250.0),
+            end: Vec2::new(500.0, -250.0),
+        },
+    );
+    line.stroke_color = Some(Color::GRAY);
+
+    // The player is a circle that follows the mouse
+    let player = game.add_shape("player", ShapeKind::Circle { radius: 40.0 });
+    player.fill_color = Some(Color::GREEN);
+    player.layer = 10.0;
+    player.collision = true;
+
+    let text = game.add_text("instructions", "Move the circle with your mouse");
+    text.space = Space::Screen;
+    text.anchor = ScreenAnchor::TopCenter;
+    text.translation.y = -40.0;
+
+    game.add_logic(logic);
+    game.run(());
+}
+
+fn logic(engine: &mut Engine, _: &mut ()) {
+    if let Some(location) = engine.mouse_state.location() {
+        engine.shapes.get_mut("player").unwrap().translation = location;
+    }
+
+    // Spin the triangle
+    engine.shapes.get_mut("triangle").unwrap().rotation += engine.delta_f32;
+
+    // Turn the player red while it is touching anything
+    for event in engine.collision_events.drain(..) {
+        if !event.pair.either_equals_to("player") {
+            continue;
+        }
+        let player = engine.shapes.get_mut("player").unwrap();
+        if event.state.is_begin() {
+            player.fill_color = Some(Color::RED);
+        } else {
+            player.fill_color = Some(Color::GREEN);
+        }
+    }
+}
@@ -14,6 +14,20 @@ fn main() {
     fps.translation = Vec2::new(0.0, 250.0);
     fps.font = "font/FiraMono-Medium.ttf".to_string();
     fps.font_size = 60.0;
+    // The number is a separate section, so it can have its own color
+    fps.sections.push(TextSection {
+        color: Some(Color::YELLOW),
+        ..TextSection::new("")
+    });
+
+    let aligned = game.add_text(
+        "aligned",
+        "Left-aligned text starts at its translation,\nand so do all of its lines.",
+    );
+    aligned.font_size = 20.0;
+    aligned.horizontal_align = HorizontalAlign::Left;
+    aligned.vertical_align = VerticalAlign::Top;
+    aligned.translation = Vec2::new(-620.0, 340.0);
 
     let zoom_msg = game.add_text(
         "zoom_msg",
@@ -56,7 +70,7 @@ fn main() {
 fn game_logic(engine: &mut Engine, game_state: &mut GameState) {
     if game_state.timer.tick(engine.delta).just_finished() {
         let mut fps = engine.texts.get_mut("fps").unwrap();
-        fps.value = format!("FPS: {:.1}", 1.0 / engine.delta_f32);
+        fps.sections[0].value = format!("{:.1}", 1.0 / engine.delta_f32);
     }
 
     let t = engine.texts.get_mut("translation").unwrap();
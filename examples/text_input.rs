@@ -0,0 +1,68 @@
+//! To run this code, clone the rusty_engine repository and run the command:
+//!
+//!     cargo run --release --example text_input
+
+use rusty_engine::prelude::*;
+
+fn main() {
+    let mut game = Game::new();
+
+    let prompt = game.add_text(
+        "prompt",
+        "Enter your initials and a message, then press Enter",
+    );
+    prompt.translation = Vec2::new(0.0, 250.0);
+
+    // Three letters only, which are shown in upper case
+    let initials = game.add_text_input("initials");
+    initials.max_length = Some(3);
+    initials.filter = Some(|character| character.is_ascii_alphabetic());
+    initials.placeholder = "AAA".to_string();
+    initials.text.font_size = 80.0;
+    initials.text.translation = Vec2::new(0.0, 100.0);
+
+    let message = game.add_text_input("message");
+    message.max_length = Some(30);
+    message.placeholder = "Say something...".to_string();
+    message.focused = false;
+    message.text.translation = Vec2::new(0.0, -50.0);
+
+    let result = game.add_text("result", "Click on a field to type into it");
+    result.translation = Vec2::new(0.0, -250.0);
+    result.color = Color::YELLOW;
+
+    game.add_logic(logic);
+    game.run(());
+}
+
+fn logic(engine: &mut Engine, _: &mut ()) {
+    // Upper-case the initials as they are typed
+    let initials = engine.text_inputs.get_mut("initials").unwrap();
+    initials.value = initials.value.to_uppercase();
+
+    // Focus whichever field is clicked on
+    if engine.mouse_state.just_pressed(MouseButton::Left) {
+        if let Some(location) = engine.mouse_state.location() {
+            for text_input in engine.text_inputs.values_mut() {
+                text_input.focused = text_input.text.bounds.contains(location);
+            }
+        }
+    }
+
+    let mut result = None;
+    for text_event in &engine.text_events {
+        match text_event {
+            TextEvent::Submitted { label, value } => {
+                result = Some(format!("{} submitted {:?}", label, value));
+            }
+            TextEvent::Cancelled { label } => {
+                result = Some(format!("{} was cleared", label));
+                engine.text_inputs.get_mut(label).unwrap().set_value("");
+            }
+            _ => {}
+        }
+    }
+    if let Some(result) = result {
+        engine.texts.get_mut("result").unwrap().value = result;
+    }
+}
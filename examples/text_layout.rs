@@ -0,0 +1,82 @@
+//! To run this code, clone the rusty_engine repository and run the command:
+//!
+//!     cargo run --release --example text_layout
+
+use rusty_engine::prelude::*;
+
+const DIALOG: &str = "Text with a max_width wraps onto more lines when it doesn't fit. \
+    Press Space to add more words, and watch the panel behind this text grow to match the \
+    size that Bevy lays the text out at.";
+
+fn main() {
+    let mut game = Game::new();
+
+    // The panel is resized every frame to fit behind the dialog text
+    let panel = game.add_shape(
+        "panel",
+        ShapeKind::RoundedRect {
+            size: Vec2::ZERO,
+            radius: 10.0,
+        },
+    );
+    panel.fill_color = Some(Color::rgb(0.1, 0.1, 0.3));
+    panel.stroke_color = Some(Color::WHITE);
+    panel.layer = 800.0;
+
+    let dialog = game.add_text("dialog", DIALOG);
+    dialog.font_size = 24.0;
+    dialog.max_width = Some(500.0);
+    dialog.horizontal_align = HorizontalAlign::Left;
+    dialog.translation = Vec2::new(-250.0, 100.0);
+
+    // The button's size is measured before it is ever displayed
+    let button_size = game.measure_text("Click me!", "font/FiraSans-Bold.ttf", 40.0);
+    let button_panel = game.add_shape(
+        "button panel",
+        ShapeKind::RoundedRect {
+            size: button_size + Vec2::new(40.0, 20.0),
+            radius: 10.0,
+        },
+    );
+    button_panel.fill_color = Some(Color::DARK_GRAY);
+    button_panel.translation = Vec2::new(0.0, -200.0);
+    button_panel.layer = 800.0;
+
+    let button = game.add_text("button", "Click me!");
+    button.font_size = 40.0;
+    button.translation = Vec2::new(0.0, -200.0);
+
+    game.add_logic(logic);
+    game.run(());
+}
+
+fn logic(engine: &mut Engine, _: &mut ()) {
+    let dialog = engine.texts.get_mut("dialog").unwrap();
+    if engine.keyboard_state.just_pressed(KeyCode::Space) {
+        dialog.value.push_str(" More words!");
+    }
+    let bounds = dialog.bounds;
+
+    let panel = engine.shapes.get_mut("panel").unwrap();
+    panel.kind = ShapeKind::RoundedRect {
+        size: bounds.size() + Vec2::splat(30.0),
+        radius: 10.0,
+    };
+    panel.translation = bounds.center();
+
+    // Light the button up while the mouse is over it
+    let button = engine.texts.get("button").unwrap();
+    let hovered = engine
+        .mouse_state
+        .location()
+        .map_or(false, |location| button.bounds.contains(location));
+    let button_panel = engine.shapes.get_mut("button panel").unwrap();
+    button_panel.fill_color = Some(if hovered {
+        Color::ORANGE
+    } else {
+        Color::DARK_GRAY
+    });
+    if hovered && engine.mouse_state.just_pressed(MouseButton::Left) {
+        println!("Clicked!");
+    }
+}
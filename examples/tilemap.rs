@@ -0,0 +1,89 @@
+//! To run this code, clone the rusty_engine repository and run the command:
+//!
+//!     cargo run --release --example tilemap
+
+use rusty_engine::prelude::*;
+
+const FILES: [&str; 3] = [
+    "tilemap/level.tmj",
+    "tilemap/level.tmx",
+    "tilemap/level.ron",
+];
+
+fn main() {
+    let mut game = Game::new();
+
+    // Load a level made with the Tiled map editor. Walls and water are solid.
+    let level = game.load_tilemap("level", FILES[0]).unwrap();
+    level.scale = 1.5;
+
+    let race_car = game.add_sprite("Race Car", SpritePreset::RacingCarYellow);
+    race_car.translation = Vec2::new(-200.0, -100.0);
+    race_car.scale = 0.5;
+    race_car.layer = 10.0;
+    race_car.collision = true;
+
+    let text = game.add_text(
+        "instructions",
+        "Drive: w a s d / arrows\nClick: add or remove a wall\n1 2 3: load the .tmj, .tmx, or .ron version of the level",
+    );
+    text.font_size = 20.0;
+    text.space = Space::Screen;
+    text.anchor = ScreenAnchor::TopCenter;
+    text.translation.y = -40.0;
+
+    game.add_logic(logic);
+    game.run(());
+}
+
+fn logic(engine: &mut Engine, _: &mut ()) {
+    // Drive around, but bounce back if we hit a wall
+    let move_amount = 300.0 * engine.delta_f32;
+    let race_car = engine.sprites.get_mut("Race Car").unwrap();
+    let ks = &engine.keyboard_state;
+    if ks.pressed_any(&[KeyCode::W, KeyCode::Up]) {
+        race_car.translation.y += move_amount;
+        race_car.rotation = UP;
+    }
+    if ks.pressed_any(&[KeyCode::A, KeyCode::Left]) {
+        race_car.translation.x -= move_amount;
+        race_car.rotation = LEFT;
+    }
+    if ks.pressed_any(&[KeyCode::S, KeyCode::Down]) {
+        race_car.translation.y -= move_amount;
+        race_car.rotation = DOWN;
+    }
+    if ks.pressed_any(&[KeyCode::D, KeyCode::Right]) {
+        race_car.translation.x += move_amount;
+        race_car.rotation = RIGHT;
+    }
+    for event in engine.collision_events.drain(..) {
+        if event.state.is_begin() && event.pair.either_equals_to("level") {
+            let facing = Vec2::new(race_car.rotation.cos(), race_car.rotation.sin());
+            race_car.translation -= facing * 20.0;
+            println!("Bonk!");
+        }
+    }
+
+    // Toggle walls by clicking on tiles
+    if engine.mouse_state.just_pressed(MouseButton::Left) {
+        if let Some(location) = engine.mouse_state.location() {
+            let level = engine.tilemaps.get_mut("level").unwrap();
+            if let Some((x, y)) = level.tile_at(location) {
+                let wall = level.is_solid(x, y);
+                level.set_tile(x, y, Some(if wall { 0 } else { 1 }));
+                level.set_solid(x, y, !wall);
+            }
+        }
+    }
+
+    // Load the same level from a different file format
+    let keys = [KeyCode::Key1, KeyCode::Key2, KeyCode::Key3];
+    for (key, file) in keys.into_iter().zip(FILES) {
+        if engine.keyboard_state.just_pressed(key) {
+            if let Some(level) = engine.load_tilemap("level", file) {
+                level.scale = 1.5;
+            }
+        }
+    }
+}
@@ -0,0 +1,113 @@
+//! To run this code, clone the rusty_engine repository and run the command:
+//!
+//!     cargo run --release --example tween
+
+use std::f32::consts::TAU;
+
+use rusty_engine::prelude::*;
+
+fn main() {
+    let mut game = Game::new();
+
+    // Slide the title in from the top, then pulse it forever
+    let title = game.add_text("title", "Tweens!");
+    title.font_size = 60.0;
+    title.translation = Vec2::new(0.0, 500.0);
+    game.tween(
+        "title",
+        TweenProperty::Translation,
+        Vec2::new(0.0, 250.0),
+        1.0,
+        Easing::BounceOut,
+    );
+    let pulse = game.tween("title", TweenProperty::Scale, 1.2, 0.5, Easing::SineInOut);
+    pulse.delay = 1.0;
+    pulse.repeat = TweenRepeat::Forever;
+    pulse.yoyo = true;
+
+    // Drive the car around a square, over and over. The last turn is to `TAU` (a full circle) so
+    // that the car keeps turning left instead of spinning back to `RIGHT`.
+    let race_car = game.add_sprite("Race Car", SpritePreset::RacingCarBlue);
+    race_car.translation = Vec2::new(-200.0, -150.0);
+    let lap = game.tween(
+        "Race Car",
+        TweenProperty::Translation,
+        Vec2::new(200.0, -150.0),
+        1.5,
+        Easing::QuadInOut,
+    );
+    lap.then(TweenProperty::Rotation, UP, 0.3, Easing::BackOut)
+        .then(
+            TweenProperty::Translation,
+            Vec2::new(200.0, 100.0),
+            1.0,
+            Easing::QuadInOut,
+        )
+        .then(TweenProperty::Rotation, LEFT, 0.3, Easing::BackOut)
+        .then(
+            TweenProperty::Translation,
+            Vec2::new(-200.0, 100.0),
+            1.5,
+            Easing::QuadInOut,
+        )
+        .then(TweenProperty::Rotation, DOWN, 0.3, Easing::BackOut)
+        .then(
+            TweenProperty::Translation,
+            Vec2::new(-200.0, -150.0),
+            1.0,
+            Easing::QuadInOut,
+        )
+        .then(TweenProperty::Rotation, TAU, 0.3, Easing::BackOut);
+    lap.repeat = TweenRepeat::Forever;
+
+    // A barrel which changes color and fades out when you click
+    let barrel = game.add_sprite("barrel", SpritePreset::RacingBarrelBlue);
+    barrel.translation = Vec2::new(0.0, -25.0);
+    barrel.scale = 0.75;
+
+    let text = game.add_text(
+        "instructions",
+        "Click: fade the barrel out and back in\nSpace: zoom the camera in and out",
+    );
+    text.font_size = 20.0;
+    text.space = Space::Screen;
+    text.anchor = ScreenAnchor::BottomCenter;
+    text.translation.y = 50.0;
+
+    game.add_logic(logic);
+    game.run(());
+}
+
+fn logic(engine: &mut Engine, _: &mut ()) {
+    if engine.mouse_state.just_pressed(MouseButton::Left) {
+        engine.stop_tweens("barrel");
+        engine
+            .tween(
+                "barrel",
+                TweenProperty::Color,
+                Color::ORANGE,
+                0.5,
+                Easing::Linear,
+            )
+            .then(TweenProperty::Alpha, 0.0, 0.5, Easing::QuadIn)
+            .wait(0.5)
+            .then(TweenProperty::Color, Color::WHITE, 0.5, Easing::QuadOut);
+    }
+
+    if engine.keyboard_state.just_pressed(KeyCode::Space) {
+        engine.stop_tweens(TweenTarget::Camera);
+        let zoom = engine.tween(
+            TweenTarget::Camera,
+            TweenProperty::Scale,
+            2.0,
+            0.75,
+            Easing::ElasticOut,
+        );
+        zoom.repeat = TweenRepeat::Times(2);
+        zoom.yoyo = true;
+    }
+
+    for event in engine.tween_events.drain(..) {
+        println!("Finished tweening {:?}", event.target);
+    }
+}
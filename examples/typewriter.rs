@@ -0,0 +1,80 @@
+//! To run this code, clone the rusty_engine repository and run the command:
+//!
+//!     cargo run --release --example typewriter
+
+use rusty_engine::prelude::*;
+
+const LINES: [&str; 3] = [
+    "Welcome, traveler! Texts can be typed out one character at a time.",
+    "Press Space to show the rest of a line right away, or to go on to the next one.",
+    "That's all there is to it. Press Space to start over!",
+];
+
+struct GameState {
+    line: usize,
+}
+
+fn main() {
+    let mut game = Game::new();
+
+    let dialog = game.add_text("dialog", LINES[0]);
+    dialog.font_size = 28.0;
+    dialog.max_width = Some(700.0);
+    dialog.translation = Vec2::new(0.0, -150.0);
+    dialog.reveal = Some(TextReveal::new(30.0));
+
+    let subtitle = game.add_text("subtitle", "Every character on its own");
+    subtitle.font_size = 24.0;
+    subtitle.translation = Vec2::new(0.0, 60.0);
+    subtitle.effect = TextEffect::Wave {
+        amplitude: 4.0,
+        speed: 0.5,
+    };
+
+    let title = game.add_bitmap_text("title", "font/pixel_5x7.fnt", "TYPEWRITER");
+    title.scale = 8;
+    title.translation = Vec2::new(0.0, 150.0);
+    title.color = Color::YELLOW;
+    title.effect = TextEffect::Wave {
+        amplitude: 2.0,
+        speed: 1.0,
+    };
+    title.reveal = Some(TextReveal::new(8.0));
+
+    let prompt = game.add_bitmap_text("prompt", "font/pixel_5x7.fnt", "PRESS SPACE");
+    prompt.scale = 3;
+    prompt.translation = Vec2::new(0.0, -280.0);
+    prompt.effect = TextEffect::Shake { intensity: 1.0 };
+
+    game.add_logic(logic);
+    game.run(GameState { line: 0 });
+}
+
+fn logic(engine: &mut Engine, game_state: &mut GameState) {
+    for text_event in &engine.text_events {
+        if let TextEvent::RevealFinished { label } = text_event {
+            println!("Finished revealing {}", label);
+        }
+    }
+
+    let prompt = engine.bitmap_texts.get_mut("prompt").unwrap();
+    let dialog = engine.texts.get_mut("dialog").unwrap();
+    let finished = dialog.reveal.as_ref().map_or(true, TextReveal::is_finished);
+    // The prompt only shakes while it's waiting for the player
+    prompt.effect = if finished {
+        TextEffect::Shake { intensity: 1.0 }
+    } else {
+        TextEffect::None
+    };
+
+    if !engine.keyboard_state.just_pressed(KeyCode::Space) {
+        return;
+    }
+    if finished {
+        game_state.line = (game_state.line + 1) % LINES.len();
+        dialog.value = LINES[game_state.line].to_string();
+        dialog.reveal = Some(TextReveal::new(30.0));
+    } else {
+        dialog.skip_reveal();
+    }
+}
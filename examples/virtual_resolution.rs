@@ -0,0 +1,65 @@
+//! To run this code, clone the rusty_engine repository and run the command:
+//!
+//!     cargo run --release --example virtual_resolution
+
+use rusty_engine::prelude::*;
+
+fn main() {
+    let mut game = Game::new();
+
+    game.window_settings(WindowDescriptor {
+        title: "Virtual Resolution".into(),
+        resizable: true,
+        ..Default::default()
+    });
+
+    // Lay the game out as if the window were always 640x360
+    game.virtual_resolution = Some(VirtualResolution::new(640.0, 360.0, ResolutionMode::Fit));
+
+    // Put a barrel in each corner of the virtual resolution. No matter how you resize the window,
+    // they stay in the corners.
+    for (i, (x, y)) in [(-1.0, -1.0), (-1.0, 1.0), (1.0, -1.0), (1.0, 1.0)]
+        .into_iter()
+        .enumerate()
+    {
+        let barrel = game.add_sprite(format!("barrel{}", i), SpritePreset::RacingBarrelRed);
+        barrel.translation = Vec2::new(x * 300.0, y * 160.0);
+        barrel.scale = 0.5;
+    }
+    let _ = game.add_sprite("cursor", SpritePreset::RollingBallBlue);
+
+    let text = game.add_text(
+        "instructions",
+        "Resize the window!\n1: Fit  2: Fill  3: Stretch  4: Pixel Perfect",
+    );
+    text.font_size = 20.0;
+    text.space = Space::Screen;
+    text.anchor = ScreenAnchor::BottomCenter;
+    text.translation.y = 40.0;
+
+    game.add_logic(logic);
+    game.run(());
+}
+
+fn logic(engine: &mut Engine, _: &mut ()) {
+    // Switch between the different ways of fitting the game into the window
+    let modes = [
+        (KeyCode::Key1, ResolutionMode::Fit),
+        (KeyCode::Key2, ResolutionMode::Fill),
+        (KeyCode::Key3, ResolutionMode::Stretch),
+        (KeyCode::Key4, ResolutionMode::PixelPerfect),
+    ];
+    for (key, mode) in modes {
+        if engine.keyboard_state.just_pressed(key) {
+            if let Some(virtual_resolution) = engine.virtual_resolution.as_mut() {
+                virtual_resolution.mode = mode;
+            }
+        }
+    }
+
+    // The mouse location is in virtual pixels, so the ball sits right under the mouse
+    if let Some(location) = engine.mouse_state.location() {
+        let cursor = engine.sprites.get_mut("cursor").unwrap();
+        cursor.translation = location;
+    }
+}
@@ -0,0 +1,201 @@
+//! Facilities for finding the files in the asset directory. By default, assets are read from the
+//! `assets/` directory in the current working directory. Use
+//! [`Game::with_asset_root`](crate::prelude::Game::with_asset_root) to read them from somewhere
+//! else.
+//!
+//! With the `embedded_assets` cargo feature, the default asset pack which comes with Rusty Engine
+//! (the `rusty_engine_assets` crate) is built into your binary, so all of the presets work without
+//! downloading the assets. Files in the asset directory are still used first, so you can add your
+//! own assets or replace the built-in ones. To embed your own asset directory instead, set the
+//! `RUSTY_ENGINE_ASSETS` environment variable to its absolute path when building.
+
+use std::{
+    fmt, fs, io,
+    path::{Path, PathBuf},
+    sync::RwLock,
+};
+
+/// The directory that assets are read from. `None` means the default of `assets`.
+static ASSET_ROOT: RwLock<Option<PathBuf>> = RwLock::new(None);
+
+/// The directory that assets are read from. All asset paths (sprite images, fonts, sounds,
+/// collider files, etc.) are relative to this directory.
+pub fn asset_root() -> PathBuf {
+    ASSET_ROOT
+        .read()
+        .unwrap()
+        .clone()
+        .unwrap_or_else(|| PathBuf::from("assets"))
+}
+
+/// Make an asset root absolute (from the current working directory), because Bevy would otherwise
+/// look for a relative one next to the executable.
+pub(crate) fn absolute_asset_root(asset_root: PathBuf) -> PathBuf {
+    match std::env::current_dir() {
+        Ok(current_dir) if asset_root.is_relative() => current_dir.join(asset_root),
+        _ => asset_root,
+    }
+}
+
+/// Change the directory that assets are read from
+pub(crate) fn set_asset_root(asset_root: PathBuf) {
+    *ASSET_ROOT.write().unwrap() = Some(asset_root);
+}
+
+/// The location of an asset on disk, given its path relative to the asset root
+pub fn asset_path<P: AsRef<Path>>(relative_path: P) -> PathBuf {
+    asset_root().join(relative_path)
+}
+
+/// Whether an asset exists, either on disk or in the embedded assets
+pub(crate) fn asset_exists<P: AsRef<Path>>(relative_path: P) -> bool {
+    let relative_path = relative_path.as_ref();
+    asset_path(relative_path).exists() || embedded_asset(relative_path).is_some()
+}
+
+/// Read an asset from disk, falling back to the embedded assets if it isn't there
+pub(crate) fn read_asset<P: AsRef<Path>>(relative_path: P) -> io::Result<Vec<u8>> {
+    let relative_path = relative_path.as_ref();
+    match fs::read(asset_path(relative_path)) {
+        Err(e) if e.kind() == io::ErrorKind::NotFound => {
+            embedded_asset(relative_path).map(<[u8]>::to_vec).ok_or(e)
+        }
+        result => result,
+    }
+}
+
+/// The error returned by [`Game::try_new`](crate::prelude::Game::try_new) and
+/// [`Game::with_asset_root`](crate::prelude::Game::with_asset_root) when the asset directory
+/// doesn't exist.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AssetRootError {
+    /// The asset directory that could not be found
+    pub asset_root: PathBuf,
+}
+
+impl fmt::Display for AssetRootError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "could not find the assets directory {}. Have you downloaded the assets?\n\
+            https://github.com/CleanCut/rusty_engine#you-must-download-the-assets-separately\n\
+            (or enable the `embedded_assets` feature of rusty_engine to build them into your game)",
+            self.asset_root.to_string_lossy()
+        )
+    }
+}
+
+impl std::error::Error for AssetRootError {}
+
+#[cfg(feature = "embedded_assets")]
+pub(crate) use embedded::EmbeddedAssetIo;
+
+#[cfg(feature = "embedded_assets")]
+use embedded::embedded_asset;
+
+/// Without the `embedded_assets` feature, there is nothing to fall back on
+#[cfg(not(feature = "embedded_assets"))]
+fn embedded_asset(_relative_path: &Path) -> Option<&'static [u8]> {
+    None
+}
+
+#[cfg(feature = "embedded_assets")]
+mod embedded {
+    use super::asset_path;
+    use bevy::asset::{AssetIo, AssetIoError, BoxedFuture, FileAssetIo, FileType, Metadata};
+    use rusty_engine_assets::FILES as EMBEDDED_ASSETS;
+    use std::{
+        collections::BTreeSet,
+        path::{Path, PathBuf},
+    };
+
+    /// The contents of an embedded file
+    pub(crate) fn embedded_asset(relative_path: &Path) -> Option<&'static [u8]> {
+        EMBEDDED_ASSETS
+            .iter()
+            .find(|(path, _)| Path::new(path) == relative_path)
+            .map(|(_, bytes)| *bytes)
+    }
+
+    /// Whether there are any embedded files inside this directory
+    fn is_embedded_directory(relative_path: &Path) -> bool {
+        EMBEDDED_ASSETS
+            .iter()
+            .any(|(path, _)| Path::new(path).starts_with(relative_path))
+    }
+
+    /// Bevy's `AssetIo` for Rusty Engine: reads assets from the asset root like Bevy normally
+    /// does, and falls back to the embedded assets for files that aren't there.
+    pub(crate) struct EmbeddedAssetIo {
+        file_asset_io: FileAssetIo,
+    }
+
+    impl EmbeddedAssetIo {
+        pub(crate) fn new(asset_root: &Path, watch_for_changes: bool) -> Self {
+            Self {
+                file_asset_io: FileAssetIo::new(asset_root, watch_for_changes),
+            }
+        }
+    }
+
+    impl AssetIo for EmbeddedAssetIo {
+        fn load_path<'a>(
+            &'a self,
+            path: &'a Path,
+        ) -> BoxedFuture<'a, Result<Vec<u8>, AssetIoError>> {
+            Box::pin(async move {
+                match self.file_asset_io.load_path(path).await {
+                    Err(AssetIoError::NotFound(not_found)) => embedded_asset(path)
+                        .map(<[u8]>::to_vec)
+                        .ok_or(AssetIoError::NotFound(not_found)),
+                    result => result,
+                }
+            })
+        }
+
+        fn read_directory(
+            &self,
+            path: &Path,
+        ) -> Result<Box<dyn Iterator<Item = PathBuf>>, AssetIoError> {
+            match self.file_asset_io.read_directory(path) {
+                Err(_) if is_embedded_directory(path) => {
+                    // The direct children of the directory, whether they are files or directories
+                    let entries: BTreeSet<PathBuf> = EMBEDDED_ASSETS
+                        .iter()
+                        .filter_map(|(embedded_path, _)| {
+                            let rest = Path::new(embedded_path).strip_prefix(path).ok()?;
+                            Some(path.join(rest.iter().next()?))
+                        })
+                        .collect();
+                    Ok(Box::new(entries.into_iter()))
+                }
+                result => result,
+            }
+        }
+
+        fn get_metadata(&self, path: &Path) -> Result<Metadata, AssetIoError> {
+            self.file_asset_io.get_metadata(path).or_else(|e| {
+                if embedded_asset(path).is_some() {
+                    Ok(Metadata::new(FileType::File))
+                } else if is_embedded_directory(path) {
+                    Ok(Metadata::new(FileType::Directory))
+                } else {
+                    Err(e)
+                }
+            })
+        }
+
+        fn watch_path_for_changes(&self, path: &Path) -> Result<(), AssetIoError> {
+            // Embedded assets never change, and the file watcher fails on paths that don't exist
+            if asset_path(path).exists() {
+                self.file_asset_io.watch_path_for_changes(path)
+            } else {
+                Ok(())
+            }
+        }
+
+        fn watch_for_changes(&self) -> Result<(), AssetIoError> {
+            self.file_asset_io.watch_for_changes()
+        }
+    }
+}
@@ -47,9 +47,27 @@
 //! ```
 //!
 
-use crate::prelude::Engine;
-use bevy::{audio::AudioSink, prelude::*};
-use std::{array::IntoIter, fmt::Debug};
+use crate::{prelude::Engine, sprite::Sprite};
+use bevy::{
+    asset::HandleId,
+    audio::{play_queued_audio_system, AudioOutput, AudioSink, Decodable},
+    prelude::*,
+    reflect::TypeUuid,
+    utils::HashMap,
+};
+use rodio::Source;
+use serde::{Deserialize, Serialize};
+use std::{
+    array::IntoIter,
+    collections::{hash_map::RandomState, BTreeMap},
+    fmt::Debug,
+    hash::{BuildHasher, Hasher},
+    sync::{
+        atomic::{AtomicU32, Ordering},
+        Arc, Mutex, MutexGuard, PoisonError,
+    },
+    time::Duration,
+};
 
 #[derive(Default)]
 #[doc(hidden)]
@@ -58,7 +76,14 @@ pub struct AudioManagerPlugin;
 
 impl Plugin for AudioManagerPlugin {
     fn build(&self, app: &mut bevy::prelude::App) {
-        app.add_system(queue_managed_audio_system);
+        // Bevy 0.8 only knows how to play `AudioSource`s, so the panned sound effects need their own
+        // output, queue, and system to play them, just like the ones `AudioPlugin` sets up
+        app.init_non_send_resource::<AudioOutput<PannedSfx>>()
+            .add_asset::<PannedSfx>()
+            .init_resource::<Audio<PannedSfx>>()
+            .add_system_to_stage(CoreStage::PostUpdate, play_queued_audio_system::<PannedSfx>)
+            // Sprites are only back in the Bevy world (where the listener is found) after this
+            .add_system(queue_managed_audio_system.after("game_logic_sync"));
     }
 }
 
@@ -68,49 +93,726 @@ impl Plugin for AudioManagerPlugin {
 /// accessible through the [`Game`](crate::prelude::Game) struct in your `main` function.
 #[derive(Default)]
 pub struct AudioManager {
-    sfx_queue: Vec<(String, f32)>,
-    music_queue: Vec<Option<(String, f32)>>,
-    playing: Option<Handle<AudioSink>>,
-    music_playing: bool,
+    settings: AudioSettings,
+    sfx_queue: Vec<(String, SfxHandle, Option<SfxLocation>)>,
+    // the label of the sprite that positional sound effects are heard from, if not the camera
+    listener: Option<String>,
+    hearing: Hearing,
+    music: Option<Music>,
+    // how many times music has been started, so each new piece of music can be told apart
+    music_count: u64,
+    // how long the change to the current music fades over, in seconds
+    music_fade: f32,
+    playlist: Option<PlaylistState>,
+    // events from the audio system, waiting to be put in `Engine::audio_events`
+    events: Vec<AudioEvent>,
 }
 
 impl Debug for AudioManager {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("AudioManager")
+            .field("settings", &self.settings)
             .field("sfx_queue", &self.sfx_queue)
-            .field("music_queue", &self.music_queue)
-            .field("music_playing", &self.music_playing)
+            .field("listener", &self.listener)
+            .field("hearing", &self.hearing)
+            .field("music", &self.music)
+            .field("music_fade", &self.music_fade)
+            .field("playlist", &self.playlist)
+            .field("events", &self.events)
             .finish()
     }
 }
 
+/// The music the user wants to hear, and how
+#[derive(Clone, Debug)]
+struct Music {
+    path: String,
+    volume: f32,
+    speed: f32,
+    paused: bool,
+    looping: bool,
+    // which time music was started that this is
+    id: u64,
+}
+
 impl AudioManager {
     /// Play a sound effect. `volume` ranges from `0.0` to `1.0`. `sfx` can be an [`SfxPreset`] or a
     /// string containing the relative path/filename of a sound file within the `assets/`
-    /// directory. Sound effects are "fire and forget". They will play to completion and then stop.
+    /// directory. Sound effects play to completion and then stop, unless they are set to loop.
     /// Multiple sound effects will be mixed and play simultaneously.
-    pub fn play_sfx<S: Into<String>>(&mut self, sfx: S, volume: f32) {
-        self.sfx_queue.push((sfx.into(), volume.clamp(0.0, 1.0)));
+    ///
+    /// The returned [`SfxHandle`] can be used to stop, pause, or change the sound effect while it
+    /// plays. If you don't need it, you can ignore it.
+    pub fn play_sfx<S: Into<String>>(&mut self, sfx: S, volume: f32) -> SfxHandle {
+        let handle = SfxHandle::new(volume.clamp(0.0, 1.0));
+        self.sfx_queue.push((sfx.into(), handle.clone(), None));
+        handle
+    }
+    /// Play a sound effect at a `position` in the game world, like [`play_sfx`](Self::play_sfx).
+    /// The sound effect is panned to the left or right speaker and gets quieter with distance,
+    /// depending on where it is compared to the listener (see
+    /// [`set_listener`](Self::set_listener)).
+    pub fn play_sfx_at<S: Into<String>>(
+        &mut self,
+        sfx: S,
+        position: Vec2,
+        volume: f32,
+    ) -> SfxHandle {
+        let handle = SfxHandle::new(volume.clamp(0.0, 1.0));
+        self.sfx_queue
+            .push((sfx.into(), handle.clone(), Some(SfxLocation::At(position))));
+        handle
+    }
+    /// Play a sound effect coming from the sprite with this label, like
+    /// [`play_sfx_at`](Self::play_sfx_at). The sound effect follows the sprite as it moves, until
+    /// the sprite is removed.
+    pub fn play_sfx_on<S: Into<String>, L: Into<String>>(
+        &mut self,
+        sfx: S,
+        sprite_label: L,
+        volume: f32,
+    ) -> SfxHandle {
+        let handle = SfxHandle::new(volume.clamp(0.0, 1.0));
+        self.sfx_queue.push((
+            sfx.into(),
+            handle.clone(),
+            Some(SfxLocation::On(sprite_label.into())),
+        ));
+        handle
+    }
+    /// Set which sprite hears positional sound effects (for example, the player). By default,
+    /// they are heard from the center of the camera.
+    pub fn set_listener<L: Into<String>>(&mut self, sprite_label: L) {
+        self.listener = Some(sprite_label.into());
+    }
+    /// Hear positional sound effects from the center of the camera again
+    pub fn clear_listener(&mut self) {
+        self.listener = None;
+    }
+    /// The label of the sprite that hears positional sound effects, if it isn't the camera
+    pub fn listener(&self) -> Option<&str> {
+        self.listener.as_deref()
+    }
+    /// Set how far away positional sound effects can be heard. They are at full volume within
+    /// `min_distance` of the listener and silent beyond `max_distance`, getting quieter in between.
+    /// The defaults are `200.0` and `1500.0`.
+    pub fn set_hearing_distance(&mut self, min_distance: f32, max_distance: f32) {
+        self.hearing = Hearing {
+            min_distance: min_distance.max(0.0),
+            max_distance: max_distance.max(min_distance),
+        };
     }
     /// Play looping music. `volume` ranges from `0.0` to `1.0`. Music will loop until stopped with
     /// [`stop_music`](AudioManager::stop_music). Playing music stops any previously playing music.
     /// `music` can be a [`MusicPreset`] or a string containing the relative path/filename of a
     /// sound file within the `assets/` directory.
     pub fn play_music<S: Into<String>>(&mut self, music: S, volume: f32) {
-        self.music_playing = true;
-        self.music_queue
-            .push(Some((music.into(), volume.clamp(0.0, 1.0))));
+        self.playlist = None;
+        self.start_music(music.into(), volume, 0.0, true);
+    }
+    /// Play music once, without looping, like [`play_music`](AudioManager::play_music). When it
+    /// finishes, an [`AudioEvent::MusicFinished`] is added to
+    /// [`Engine::audio_events`](crate::prelude::Engine::audio_events). That way, you can start
+    /// other music when an intro finishes, for example.
+    pub fn play_music_once<S: Into<String>>(&mut self, music: S, volume: f32) {
+        self.playlist = None;
+        self.start_music(music.into(), volume, 0.0, false);
+    }
+    /// Play the tracks of a [`Playlist`] one after the other, at `volume` (from `0.0` to `1.0`).
+    /// Each time a track finishes, an [`AudioEvent::MusicFinished`] is added to
+    /// [`Engine::audio_events`](crate::prelude::Engine::audio_events). Playing other music stops
+    /// the playlist. Ignored if the playlist has no tracks.
+    pub fn play_playlist(&mut self, playlist: Playlist, volume: f32) {
+        let mut state = PlaylistState {
+            order: (0..playlist.tracks.len()).collect(),
+            playlist,
+            position: 0,
+        };
+        if state.playlist.mode == PlaylistMode::Shuffle {
+            state.shuffle(None);
+        }
+        let track = match state.track() {
+            Some(track) => track.to_string(),
+            None => return,
+        };
+        self.playlist = Some(state);
+        self.start_music(track, volume, 0.0, false);
+    }
+    /// Skip to the next track of the playlist that is playing. Ignored if no playlist is playing.
+    pub fn next_track(&mut self) {
+        if self.playlist.is_some() {
+            self.advance_playlist();
+        }
+    }
+    /// The music that is playing (or paused), if any
+    pub fn music_track(&self) -> Option<&str> {
+        self.music.as_ref().map(|music| music.path.as_str())
+    }
+    /// Fade from the music that is playing to other music over `duration` seconds: the old music
+    /// fades out while the new music fades in, up to `volume` (from `0.0` to `1.0`). If no music is
+    /// playing, the new music just fades in. `music` can be a [`MusicPreset`] or a string
+    /// containing the relative path/filename of a sound file within the `assets/` directory.
+    pub fn crossfade_to<S: Into<String>>(&mut self, music: S, volume: f32, duration: f32) {
+        self.playlist = None;
+        self.start_music(music.into(), volume, duration.max(0.0), true);
+    }
+    fn start_music(&mut self, path: String, volume: f32, fade: f32, looping: bool) {
+        self.music_count += 1;
+        self.music = Some(Music {
+            path,
+            volume: volume.clamp(0.0, 1.0),
+            speed: 1.0,
+            paused: false,
+            looping,
+            id: self.music_count,
+        });
+        self.music_fade = fade;
+    }
+    /// Start the next track of the playlist at the same volume and speed as the last one, or stop
+    /// the music if the playlist is over
+    fn advance_playlist(&mut self) {
+        let next_track = self
+            .playlist
+            .as_mut()
+            .and_then(PlaylistState::advance)
+            .map(str::to_string);
+        match (next_track, self.music.clone()) {
+            (Some(track), Some(music)) => {
+                self.start_music(track, music.volume, 0.0, false);
+                self.set_music_speed(music.speed);
+            }
+            (Some(track), None) => self.start_music(track, 1.0, 0.0, false),
+            (None, _) => {
+                if self.playlist.take().is_some() {
+                    self.events.push(AudioEvent::PlaylistFinished);
+                }
+                self.music = None;
+            }
+        }
+    }
+    /// The events from the audio system since the last frame
+    pub(crate) fn take_events(&mut self) -> Vec<AudioEvent> {
+        std::mem::take(&mut self.events)
     }
     /// Stop any music currently playing. Ignored if no music is currently playing.
     pub fn stop_music(&mut self) {
-        if self.music_playing {
-            self.music_playing = false;
-            self.music_queue.push(None);
+        self.playlist = None;
+        self.music = None;
+        self.music_fade = 0.0;
+    }
+    /// Pause the music where it is. Ignored if no music is currently playing.
+    pub fn pause_music(&mut self) {
+        if let Some(music) = self.music.as_mut() {
+            music.paused = true;
+        }
+    }
+    /// Continue playing paused music from where it was paused
+    pub fn resume_music(&mut self) {
+        if let Some(music) = self.music.as_mut() {
+            music.paused = false;
+        }
+    }
+    /// Change the volume of the current music. `volume` ranges from `0.0` to `1.0`. Ignored if no
+    /// music is currently playing.
+    pub fn set_music_volume(&mut self, volume: f32) {
+        if let Some(music) = self.music.as_mut() {
+            music.volume = volume.clamp(0.0, 1.0);
+        }
+    }
+    /// The volume of the current music, if there is any
+    pub fn music_volume(&self) -> Option<f32> {
+        self.music.as_ref().map(|music| music.volume)
+    }
+    /// Change how fast the current music plays. `1.0` is normal speed. Like a record player, this
+    /// changes the pitch as well. Ignored if no music is currently playing.
+    pub fn set_music_speed(&mut self, speed: f32) {
+        if let Some(music) = self.music.as_mut() {
+            music.speed = speed.max(0.0);
         }
     }
-    /// Whether music is currently playing.
+    /// How fast the current music plays, if there is any
+    pub fn music_speed(&self) -> Option<f32> {
+        self.music.as_ref().map(|music| music.speed)
+    }
+    /// Whether music is currently playing. Paused music isn't playing.
     pub fn music_playing(&self) -> bool {
-        self.music_playing
+        self.music.as_ref().map_or(false, |music| !music.paused)
+    }
+    /// Whether the current music is paused
+    pub fn music_paused(&self) -> bool {
+        self.music.as_ref().map_or(false, |music| music.paused)
+    }
+    /// Change the volume of an audio bus, adding the bus if it doesn't exist yet. `volume` ranges
+    /// from `0.0` to `1.0`. The change is heard right away, even by sounds that are already
+    /// playing. See [`AudioSettings`].
+    pub fn set_bus_volume<S: Into<String>>(&mut self, bus: S, volume: f32) {
+        self.settings.bus_mut(bus).volume = volume.clamp(0.0, 1.0);
+    }
+    /// The volume of an audio bus. Buses that don't exist have a volume of `1.0`.
+    pub fn bus_volume(&self, bus: &str) -> f32 {
+        self.settings.buses.get(bus).map_or(1.0, |bus| bus.volume)
+    }
+    /// Mute or unmute an audio bus, adding the bus if it doesn't exist yet. Muting a bus doesn't
+    /// change its volume, so unmuting it brings the sound back the way it was.
+    pub fn set_bus_muted<S: Into<String>>(&mut self, bus: S, muted: bool) {
+        self.settings.bus_mut(bus).muted = muted;
+    }
+    /// Whether an audio bus is muted
+    pub fn bus_muted(&self, bus: &str) -> bool {
+        self.settings.buses.get(bus).map_or(false, |bus| bus.muted)
+    }
+    /// The volumes of all the audio buses, which can be saved (it implements serde's `Serialize`)
+    /// and restored later with [`set_settings`](AudioManager::set_settings)
+    pub fn settings(&self) -> &AudioSettings {
+        &self.settings
+    }
+    /// Replace the volumes of all the audio buses, for example with settings the player saved
+    pub fn set_settings(&mut self, settings: AudioSettings) {
+        self.settings = settings;
+    }
+}
+
+/// The order a [`Playlist`] plays its tracks in
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PlaylistMode {
+    /// Play each track once, in order, and then stop
+    Sequential,
+    /// Play the tracks in a random order, reshuffling them each time they have all been played,
+    /// until the music is stopped
+    Shuffle,
+    /// Play the tracks in order, starting over from the first track after the last one, until the
+    /// music is stopped
+    LoopAll,
+}
+
+/// A list of music tracks to play one after the other with
+/// [`AudioManager::play_playlist`]. Each track can be a [`MusicPreset`] or a string containing the
+/// relative path/filename of a sound file within the `assets/` directory.
+///
+/// ```rust,no_run
+/// # use rusty_engine::prelude::*;
+/// #
+/// # fn main() {
+/// # let mut game = Game::new();
+/// let playlist = Playlist::new(
+///     [MusicPreset::Classy8Bit, MusicPreset::WhimsicalPopsicle],
+///     PlaylistMode::Shuffle,
+/// );
+/// game.audio_manager.play_playlist(playlist, 0.5);
+/// # game.run(());
+/// # }
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Playlist {
+    /// The tracks of the playlist
+    pub tracks: Vec<String>,
+    /// The order the tracks are played in
+    pub mode: PlaylistMode,
+}
+
+impl Playlist {
+    pub fn new<I, S>(tracks: I, mode: PlaylistMode) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self {
+            tracks: tracks.into_iter().map(Into::into).collect(),
+            mode,
+        }
+    }
+}
+
+/// A playlist that is playing, and which of its tracks it is on
+#[derive(Clone, Debug)]
+struct PlaylistState {
+    playlist: Playlist,
+    // the indices of the tracks, in the order they are played
+    order: Vec<usize>,
+    position: usize,
+}
+
+impl PlaylistState {
+    fn track(&self) -> Option<&str> {
+        let index = *self.order.get(self.position)?;
+        self.playlist.tracks.get(index).map(String::as_str)
+    }
+    /// Move on to the next track, returning it, or `None` if the playlist is over
+    fn advance(&mut self) -> Option<&str> {
+        self.position += 1;
+        if self.position >= self.order.len() {
+            match self.playlist.mode {
+                PlaylistMode::Sequential => return None,
+                PlaylistMode::LoopAll => self.position = 0,
+                PlaylistMode::Shuffle => {
+                    let last = self.order.last().copied();
+                    self.shuffle(last);
+                    self.position = 0;
+                }
+            }
+        }
+        self.track()
+    }
+    /// Put the tracks in a random order, which doesn't start with the track at index `last`
+    fn shuffle(&mut self, last: Option<usize>) {
+        // A random seed, without needing a random number generator
+        let mut seed = RandomState::new().build_hasher().finish() | 1;
+        let mut random = move || {
+            seed ^= seed << 13;
+            seed ^= seed >> 7;
+            seed ^= seed << 17;
+            seed
+        };
+        for i in (1..self.order.len()).rev() {
+            let j = (random() % (i as u64 + 1)) as usize;
+            self.order.swap(i, j);
+        }
+        // Don't play the same track twice in a row
+        if self.order.len() > 1 && self.order.first().copied() == last {
+            let len = self.order.len();
+            self.order.swap(0, len - 1);
+        }
+    }
+}
+
+/// Events from the audio system, which are found in
+/// [`Engine::audio_events`](crate::prelude::Engine::audio_events). Since sounds play alongside
+/// your game, these may arrive a frame after the sound actually finished.
+#[derive(Clone, Debug, PartialEq)]
+pub enum AudioEvent {
+    /// Music that doesn't loop finished playing: music started with
+    /// [`AudioManager::play_music_once`], or a track of a playlist
+    MusicFinished { music: String },
+    /// The last track of a [`PlaylistMode::Sequential`] playlist finished playing
+    PlaylistFinished,
+    /// A sound effect finished playing. Sound effects that are stopped don't send this event.
+    SfxFinished { sfx: String, handle: SfxHandle },
+}
+
+/// The name of the audio bus that every sound goes through
+pub const MASTER_BUS: &str = "master";
+/// The name of the audio bus that music goes through
+pub const MUSIC_BUS: &str = "music";
+/// The name of the audio bus that sound effects go through, unless they are put on another bus
+/// with [`SfxHandle::set_bus`]
+pub const SFX_BUS: &str = "sfx";
+
+/// The volume and mute switch of an audio bus. See [`AudioSettings`].
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct AudioBus {
+    /// From `0.0` to `1.0`
+    pub volume: f32,
+    /// A muted bus is silent, whatever its volume
+    pub muted: bool,
+}
+
+impl Default for AudioBus {
+    fn default() -> Self {
+        Self {
+            volume: 1.0,
+            muted: false,
+        }
+    }
+}
+
+impl AudioBus {
+    /// The volume of the bus, or `0.0` if it is muted
+    pub fn gain(&self) -> f32 {
+        if self.muted {
+            0.0
+        } else {
+            self.volume
+        }
+    }
+}
+
+/// The audio buses of the [`AudioManager`], by name. Each bus has a volume and can be muted, and
+/// the volume of every sound is multiplied by the volume of its bus and of the [`MASTER_BUS`].
+/// Music goes through the [`MUSIC_BUS`] and sound effects go through the [`SFX_BUS`], or any other
+/// bus you put them on with [`SfxHandle::set_bus`] (for example, a `"ui"` bus for menu sounds).
+///
+/// This is just what an options menu needs. The settings can be serialized with serde to save
+/// them, and restored with [`AudioManager::set_settings`].
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct AudioSettings {
+    /// The buses, by name. There are always master, music, and sfx buses by default.
+    pub buses: BTreeMap<String, AudioBus>,
+}
+
+impl Default for AudioSettings {
+    fn default() -> Self {
+        Self {
+            buses: [MASTER_BUS, MUSIC_BUS, SFX_BUS]
+                .into_iter()
+                .map(|bus| (bus.to_string(), AudioBus::default()))
+                .collect(),
+        }
+    }
+}
+
+impl AudioSettings {
+    /// How loud a sound on `bus` is: the gain of the bus times the gain of the [`MASTER_BUS`]
+    pub fn gain(&self, bus: &str) -> f32 {
+        let bus_gain = |bus: &str| self.buses.get(bus).map_or(1.0, AudioBus::gain);
+        if bus == MASTER_BUS {
+            bus_gain(MASTER_BUS)
+        } else {
+            bus_gain(MASTER_BUS) * bus_gain(bus)
+        }
+    }
+    fn bus_mut<S: Into<String>>(&mut self, bus: S) -> &mut AudioBus {
+        self.buses.entry(bus.into()).or_default()
+    }
+}
+
+/// How far away positional sound effects can be heard
+#[derive(Clone, Copy, Debug)]
+struct Hearing {
+    min_distance: f32,
+    max_distance: f32,
+}
+
+impl Default for Hearing {
+    fn default() -> Self {
+        Self {
+            min_distance: 200.0,
+            max_distance: 1500.0,
+        }
+    }
+}
+
+impl Hearing {
+    /// How loud a sound `distance` away from the listener is, from `0.0` to `1.0`
+    fn attenuation(&self, distance: f32) -> f32 {
+        if distance <= self.min_distance {
+            1.0
+        } else if distance >= self.max_distance {
+            0.0
+        } else {
+            1.0 - (distance - self.min_distance) / (self.max_distance - self.min_distance)
+        }
+    }
+}
+
+/// Where a positional sound effect comes from
+#[derive(Clone, Debug)]
+enum SfxLocation {
+    At(Vec2),
+    On(String),
+}
+
+/// The volume of the left and right speakers of a positional sound effect, which the audio thread
+/// reads while the sound plays
+#[derive(Debug)]
+struct StereoGains {
+    left: AtomicU32,
+    right: AtomicU32,
+}
+
+impl StereoGains {
+    fn new() -> Self {
+        Self {
+            left: AtomicU32::new(1.0f32.to_bits()),
+            right: AtomicU32::new(1.0f32.to_bits()),
+        }
+    }
+    fn set(&self, left: f32, right: f32) {
+        self.left.store(left.to_bits(), Ordering::Relaxed);
+        self.right.store(right.to_bits(), Ordering::Relaxed);
+    }
+    fn get(&self) -> (f32, f32) {
+        (
+            f32::from_bits(self.left.load(Ordering::Relaxed)),
+            f32::from_bits(self.right.load(Ordering::Relaxed)),
+        )
+    }
+}
+
+/// A sound file that is played in stereo, with the volume of each speaker controlled while it
+/// plays. Bevy can't pan sounds by itself, so positional sound effects are played as these.
+#[derive(TypeUuid)]
+#[uuid = "3b1c8e2a-5f47-4a8e-9d62-7c0f4e1b9a35"]
+#[doc(hidden)]
+pub struct PannedSfx {
+    source: AudioSource,
+    gains: Arc<StereoGains>,
+}
+
+impl Decodable for PannedSfx {
+    type DecoderItem = i16;
+    type Decoder = PannedDecoder;
+
+    fn decoder(&self) -> Self::Decoder {
+        PannedDecoder {
+            decoder: self.source.decoder(),
+            gains: self.gains.clone(),
+            right: None,
+        }
+    }
+}
+
+/// Decodes a sound file into stereo samples, scaling each channel by its gain
+#[doc(hidden)]
+pub struct PannedDecoder {
+    decoder: <AudioSource as Decodable>::Decoder,
+    gains: Arc<StereoGains>,
+    // the right sample of the frame whose left sample was just returned
+    right: Option<i16>,
+}
+
+impl Iterator for PannedDecoder {
+    type Item = i16;
+
+    fn next(&mut self) -> Option<i16> {
+        if let Some(right) = self.right.take() {
+            return Some(right);
+        }
+        // Mono sounds are played in both speakers, and any channels past the first two are dropped
+        let channels = self.decoder.channels();
+        let left = self.decoder.next()?;
+        let right = if channels >= 2 {
+            self.decoder.next()?
+        } else {
+            left
+        };
+        for _ in 2..channels {
+            self.decoder.next();
+        }
+        let (left_gain, right_gain) = self.gains.get();
+        self.right = Some((right as f32 * right_gain) as i16);
+        Some((left as f32 * left_gain) as i16)
+    }
+}
+
+impl Source for PannedDecoder {
+    fn current_frame_len(&self) -> Option<usize> {
+        None
+    }
+    fn channels(&self) -> u16 {
+        2
+    }
+    fn sample_rate(&self) -> u32 {
+        self.decoder.sample_rate()
+    }
+    fn total_duration(&self) -> Option<Duration> {
+        self.decoder.total_duration()
+    }
+}
+
+/// What the user wants a sound effect to do, and whether it has finished
+#[derive(Debug)]
+struct SfxControl {
+    bus: String,
+    volume: f32,
+    speed: f32,
+    paused: bool,
+    looping: bool,
+    stopped: bool,
+    finished: bool,
+}
+
+/// A handle to a sound effect started with [`AudioManager::play_sfx`], which controls it while it
+/// plays. Handles can be cloned and stored (for example, in your game state) to control the sound
+/// effect in a later frame. Changes take effect at the end of the frame.
+///
+/// ```rust,no_run
+/// # use rusty_engine::prelude::*;
+/// #
+/// # fn main() {
+/// # let mut game = Game::new();
+/// // Start an engine sound that loops until it is stopped
+/// let engine_sound = game.audio_manager.play_sfx(SfxPreset::Forcefield1, 0.5);
+/// engine_sound.set_looping(true);
+/// // ...later on
+/// engine_sound.stop();
+/// # game.run(());
+/// # }
+/// ```
+///
+/// The engine knows how long `.ogg` and `.wav` sound files are, so it can tell when they finish.
+/// Sound effects in other formats are considered to be playing until they are stopped.
+#[derive(Clone, Debug)]
+pub struct SfxHandle(Arc<Mutex<SfxControl>>);
+
+/// Handles are equal if they control the same sound effect
+impl PartialEq for SfxHandle {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl SfxHandle {
+    fn new(volume: f32) -> Self {
+        Self(Arc::new(Mutex::new(SfxControl {
+            bus: SFX_BUS.to_string(),
+            volume,
+            speed: 1.0,
+            paused: false,
+            looping: false,
+            stopped: false,
+            finished: false,
+        })))
+    }
+    fn control(&self) -> MutexGuard<'_, SfxControl> {
+        self.0.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+    /// Stop the sound effect. It can't be started again, but you can play it again with
+    /// [`AudioManager::play_sfx`].
+    pub fn stop(&self) {
+        self.control().stopped = true;
+    }
+    /// Pause the sound effect where it is
+    pub fn pause(&self) {
+        self.control().paused = true;
+    }
+    /// Continue playing a paused sound effect from where it was paused
+    pub fn resume(&self) {
+        self.control().paused = false;
+    }
+    /// Whether the sound effect is paused
+    pub fn is_paused(&self) -> bool {
+        self.control().paused
+    }
+    /// Change the volume of the sound effect. `volume` ranges from `0.0` to `1.0`.
+    pub fn set_volume(&self, volume: f32) {
+        self.control().volume = volume.clamp(0.0, 1.0);
+    }
+    /// The volume of the sound effect, from `0.0` to `1.0`
+    pub fn volume(&self) -> f32 {
+        self.control().volume
+    }
+    /// Change how fast the sound effect plays. `1.0` is normal speed. Like a record player, this
+    /// changes the pitch as well.
+    pub fn set_speed(&self, speed: f32) {
+        self.control().speed = speed.max(0.0);
+    }
+    /// How fast the sound effect plays. `1.0` is normal speed.
+    pub fn speed(&self) -> f32 {
+        self.control().speed
+    }
+    /// Set whether the sound effect starts over when it gets to the end. When looping is turned
+    /// off, the sound effect finishes the loop it is on and then stops.
+    pub fn set_looping(&self, looping: bool) {
+        self.control().looping = looping;
+    }
+    /// Whether the sound effect starts over when it gets to the end
+    pub fn looping(&self) -> bool {
+        self.control().looping
+    }
+    /// Put the sound effect on another audio bus, so its volume is controlled by that bus instead
+    /// of the [`SFX_BUS`]. See [`AudioSettings`].
+    pub fn set_bus<S: Into<String>>(&self, bus: S) {
+        self.control().bus = bus.into();
+    }
+    /// The audio bus the sound effect is on
+    pub fn bus(&self) -> String {
+        self.control().bus.clone()
+    }
+    /// Whether the sound effect is still playing: it hasn't finished or been stopped. Paused sound
+    /// effects are still playing.
+    pub fn is_playing(&self) -> bool {
+        let control = self.control();
+        !control.stopped && !control.finished
     }
 }
 
@@ -227,46 +929,468 @@ impl From<MusicPreset> for String {
     }
 }
 
+/// A sound effect that has been started, and how far into the sound file it is
+#[doc(hidden)]
+pub struct PlayingSfx {
+    path: String,
+    handle: SfxHandle,
+    source: Handle<AudioSource>,
+    sink: Handle<AudioSink>,
+    // whether Bevy repeats the sound file by itself
+    repeat: bool,
+    // seconds into the sound file, counting every time it repeated
+    position: f32,
+    // when looping is turned off, where the current loop ends
+    stop_at: Option<f32>,
+    spatial: Option<SpatialSfx>,
+}
+
+/// The parts of a positional sound effect
+struct SpatialSfx {
+    location: SfxLocation,
+    // where the sound effect was last heard from, in case its sprite is removed
+    last_position: Option<Vec2>,
+    // the panned sound, which can only be made once the sound file has loaded
+    panned: Handle<PannedSfx>,
+    loaded: bool,
+    gains: Arc<StereoGains>,
+}
+
+/// The Bevy resources needed to start playing a sound effect
+struct SfxPlayer<'a> {
+    asset_server: &'a AssetServer,
+    audio: &'a Audio,
+    panned_audio: &'a Audio<PannedSfx>,
+    panned_sfx: &'a Assets<PannedSfx>,
+    audio_sinks: &'a Assets<AudioSink>,
+    audio_settings: &'a AudioSettings,
+}
+
+impl PlayingSfx {
+    fn start(
+        path: String,
+        handle: SfxHandle,
+        location: Option<SfxLocation>,
+        player: &SfxPlayer,
+    ) -> Self {
+        let source = player.asset_server.load(format!("audio/{}", path).as_str());
+        let (repeat, playback_settings) = {
+            let control = handle.control();
+            (
+                control.looping,
+                PlaybackSettings {
+                    repeat: control.looping,
+                    volume: control.volume * player.audio_settings.gain(&control.bus),
+                    speed: control.speed,
+                },
+            )
+        };
+        let (sink, spatial) = match location {
+            None => (
+                player
+                    .audio
+                    .play_with_settings(source.clone(), playback_settings),
+                None,
+            ),
+            Some(location) => {
+                // Bevy waits for the panned sound to exist before playing it
+                let panned = player
+                    .panned_sfx
+                    .get_handle(HandleId::random::<PannedSfx>());
+                let sink = player
+                    .panned_audio
+                    .play_with_settings(panned.clone(), playback_settings);
+                let spatial = SpatialSfx {
+                    location,
+                    last_position: None,
+                    panned,
+                    loaded: false,
+                    gains: Arc::new(StereoGains::new()),
+                };
+                (sink, Some(spatial))
+            }
+        };
+        Self {
+            path,
+            handle,
+            source,
+            sink: player.audio_sinks.get_handle(sink),
+            repeat,
+            position: 0.0,
+            stop_at: None,
+            spatial,
+        }
+    }
+}
+
+/// The length in seconds of a sound file, if it is a format we can tell the length of without
+/// decoding it (Ogg Vorbis or WAV)
+fn sound_duration(bytes: &[u8]) -> Option<f32> {
+    if bytes.starts_with(b"OggS") {
+        ogg_duration(bytes)
+    } else if bytes.starts_with(b"RIFF") && bytes.get(8..12) == Some(&b"WAVE"[..]) {
+        wav_duration(bytes)
+    } else {
+        None
+    }
+}
+
+/// The length of an Ogg Vorbis file: the granule position of the last page is the number of
+/// samples, and the sample rate is in the header packet at the start of the first page
+fn ogg_duration(bytes: &[u8]) -> Option<f32> {
+    let first_segments = *bytes.get(26)? as usize;
+    let header = bytes.get(27 + first_segments..)?;
+    if header.get(..7)? != b"\x01vorbis" {
+        return None;
+    }
+    let sample_rate = u32::from_le_bytes(header.get(12..16)?.try_into().ok()?);
+
+    let mut samples = 0;
+    let mut page = 0;
+    while bytes.get(page..page + 4) == Some(&b"OggS"[..]) {
+        let granule_position = i64::from_le_bytes(bytes.get(page + 6..page + 14)?.try_into().ok()?);
+        // -1 means no packet ends on this page
+        if granule_position >= 0 {
+            samples = granule_position;
+        }
+        let segments = *bytes.get(page + 26)? as usize;
+        let segment_table = bytes.get(page + 27..page + 27 + segments)?;
+        page += 27 + segments + segment_table.iter().map(|&len| len as usize).sum::<usize>();
+    }
+    (sample_rate > 0).then(|| samples as f32 / sample_rate as f32)
+}
+
+/// The length of a WAV file: the size of its data chunk divided by its byte rate
+fn wav_duration(bytes: &[u8]) -> Option<f32> {
+    let mut byte_rate = None;
+    let mut chunk = 12;
+    while let Some(id) = bytes.get(chunk..chunk + 4) {
+        let size = u32::from_le_bytes(bytes.get(chunk + 4..chunk + 8)?.try_into().ok()?) as usize;
+        match id {
+            b"fmt " => {
+                byte_rate = Some(u32::from_le_bytes(
+                    bytes.get(chunk + 16..chunk + 20)?.try_into().ok()?,
+                ));
+            }
+            b"data" => return byte_rate.map(|byte_rate| size as f32 / byte_rate as f32),
+            _ => {}
+        }
+        // Chunks are padded to an even size
+        chunk += 8 + size + size % 2;
+    }
+    None
+}
+
 /// The Bevy system that checks to see if there is any audio management that needs to be done.
 #[doc(hidden)]
+#[allow(clippy::too_many_arguments)]
 pub fn queue_managed_audio_system(
     asset_server: Res<AssetServer>,
     audio: Res<Audio>,
+    panned_audio: Res<Audio<PannedSfx>>,
+    mut panned_sfx: ResMut<Assets<PannedSfx>>,
     audio_sinks: Res<Assets<AudioSink>>,
+    audio_sources: Res<Assets<AudioSource>>,
+    time: Res<Time>,
     mut game_state: ResMut<Engine>,
+    sprite_query: Query<&Sprite>,
+    mut playing_sfx: Local<Vec<PlayingSfx>>,
+    mut playing_music: Local<PlayingMusic>,
+    mut durations: Local<HashMap<String, Option<f32>>>,
 ) {
-    for (sfx, volume) in game_state.audio_manager.sfx_queue.drain(..) {
-        let sfx_path = format!("audio/{}", sfx);
-        let sfx_handle = asset_server.load(sfx_path.as_str());
-        audio.play_with_settings(
-            sfx_handle,
-            PlaybackSettings {
-                volume,
-                ..Default::default()
-            },
-        );
-    }
-    #[allow(clippy::for_loops_over_fallibles)]
-    for item in game_state.audio_manager.music_queue.drain(..).last() {
-        // stop any music currently playing
-        if let Some(sink_handle) = &game_state.audio_manager.playing {
-            if let Some(sink) = audio_sinks.get(sink_handle) {
-                sink.stop();
+    // Every sound is played at its own volume times the volume of its buses
+    let audio_settings = game_state.audio_manager.settings.clone();
+    let music_gain = audio_settings.gain(MUSIC_BUS);
+
+    let queued_sfx: Vec<_> = game_state.audio_manager.sfx_queue.drain(..).collect();
+    {
+        let player = SfxPlayer {
+            asset_server: &asset_server,
+            audio: &audio,
+            panned_audio: &panned_audio,
+            panned_sfx: &panned_sfx,
+            audio_sinks: &audio_sinks,
+            audio_settings: &audio_settings,
+        };
+        for (sfx, handle, location) in queued_sfx {
+            // Stopped before it even started
+            if !handle.is_playing() {
+                continue;
+            }
+            playing_sfx.push(PlayingSfx::start(sfx, handle, location, &player));
+        }
+    }
+
+    // Positional sound effects are heard from the listener sprite, or else the camera
+    let hearing = game_state.audio_manager.hearing;
+    let sprite_translation = |label: &String| {
+        sprite_query
+            .iter()
+            .find(|sprite| &sprite.label == label)
+            .map(|sprite| sprite.translation)
+    };
+    let listener = game_state
+        .audio_manager
+        .listener
+        .as_ref()
+        .and_then(sprite_translation)
+        .unwrap_or(game_state.camera.translation);
+    // A sound at the edge of the window is all the way to one side
+    let pan_distance = (game_state.window_dimensions.x * 0.5).max(1.0);
+
+    // Apply the changes the user made to the sound effects, and find out which ones are done
+    let delta = time.delta_seconds();
+    let mut finished_sfx = Vec::new();
+    for sfx in playing_sfx.iter_mut() {
+        let mut attenuation = 1.0;
+        if let Some(spatial) = sfx.spatial.as_mut() {
+            if !spatial.loaded {
+                if let Some(source) = audio_sources.get(&sfx.source) {
+                    panned_sfx.set_untracked(
+                        &spatial.panned,
+                        PannedSfx {
+                            source: source.clone(),
+                            gains: spatial.gains.clone(),
+                        },
+                    );
+                    spatial.loaded = true;
+                }
+            }
+            let position = match &spatial.location {
+                SfxLocation::At(position) => Some(*position),
+                SfxLocation::On(label) => sprite_translation(label).or(spatial.last_position),
+            };
+            if let Some(position) = position {
+                spatial.last_position = Some(position);
+                let offset = position - listener;
+                attenuation = hearing.attenuation(offset.length());
+                let pan = (offset.x / pan_distance).clamp(-1.0, 1.0);
+                spatial
+                    .gains
+                    .set((1.0 - pan).min(1.0), (1.0 + pan).min(1.0));
+            }
+        }
+
+        // The sink only exists once the sound file has loaded and started playing
+        let sink = match audio_sinks.get(&sfx.sink) {
+            Some(sink) => sink,
+            None => continue,
+        };
+        let duration = *durations.entry(sfx.path.clone()).or_insert_with(|| {
+            audio_sources
+                .get(&sfx.source)
+                .and_then(|source| sound_duration(&source.bytes))
+        });
+        let mut control = sfx.handle.control();
+        if control.stopped {
+            sink.stop();
+            continue;
+        }
+        if control.paused != sink.is_paused() {
+            if control.paused {
+                sink.pause();
+            } else {
+                sink.play();
             }
         }
-        // start the new music...if we have some
-        if let Some((music, volume)) = item {
-            let music_path = format!("audio/{}", music);
-            let music_handle = asset_server.load(music_path.as_str());
-            let sink_handle = audio_sinks.get_handle(audio.play_with_settings(
-                music_handle,
+        sink.set_volume(control.volume * audio_settings.gain(&control.bus) * attenuation);
+        sink.set_speed(control.speed);
+        if !control.paused {
+            sfx.position += delta * control.speed;
+        }
+
+        let duration = match duration {
+            Some(duration) if duration > 0.0 => duration,
+            _ => continue,
+        };
+        if sfx.repeat {
+            // Finish the current loop before stopping
+            if control.looping {
+                sfx.stop_at = None;
+            } else {
+                let stop_at = *sfx
+                    .stop_at
+                    .get_or_insert(((sfx.position / duration).ceil() * duration).max(duration));
+                if sfx.position >= stop_at {
+                    sink.stop();
+                    control.finished = true;
+                }
+            }
+        } else if sfx.position >= duration {
+            control.finished = true;
+        }
+        if control.finished && !control.looping {
+            finished_sfx.push(AudioEvent::SfxFinished {
+                sfx: sfx.path.clone(),
+                handle: sfx.handle.clone(),
+            });
+        }
+    }
+    game_state.audio_manager.events.extend(finished_sfx);
+
+    // Sound effects that were set to loop after they started are started again from the top
+    let mut restarted = Vec::new();
+    playing_sfx.retain(|sfx| {
+        let mut control = sfx.handle.control();
+        // A sound effect that was stopped while it was loading is kept until it can be stopped
+        let done = (control.stopped && audio_sinks.get(&sfx.sink).is_some()) || control.finished;
+        if done && !control.stopped && !sfx.repeat && control.looping {
+            control.finished = false;
+            let location = sfx.spatial.as_ref().map(|spatial| spatial.location.clone());
+            restarted.push((sfx.path.clone(), sfx.handle.clone(), location));
+        }
+        !done
+    });
+    let player = SfxPlayer {
+        asset_server: &asset_server,
+        audio: &audio,
+        panned_audio: &panned_audio,
+        panned_sfx: &panned_sfx,
+        audio_sinks: &audio_sinks,
+        audio_settings: &audio_settings,
+    };
+    for (sfx, handle, location) in restarted {
+        playing_sfx.push(PlayingSfx::start(sfx, handle, location, &player));
+    }
+
+    // Music that doesn't loop is done at the end of the sound file. Start the next track of the
+    // playlist, if there is one.
+    let finished_music = playing_music.current.as_ref().and_then(|current| {
+        // Like the sound effects, the duration can only be found once the sound file has loaded,
+        // which is when the sink exists
+        audio_sinks.get(&current.sink)?;
+        let duration = (*durations.entry(current.path.clone()).or_insert_with(|| {
+            audio_sources
+                .get(&current.source)
+                .and_then(|source| sound_duration(&source.bytes))
+        }))?;
+        (!current.looping && current.position >= duration).then(|| current.path.clone())
+    });
+    if let Some(music) = finished_music {
+        let audio_manager = &mut game_state.audio_manager;
+        audio_manager
+            .events
+            .push(AudioEvent::MusicFinished { music });
+        if audio_manager.playlist.is_some() {
+            audio_manager.advance_playlist();
+        } else {
+            audio_manager.music = None;
+        }
+    }
+
+    // Start new music, fading out the old music if it is being crossfaded
+    let music = game_state.audio_manager.music.clone();
+    let music_fade = game_state.audio_manager.music_fade;
+    let music_id = music.as_ref().map(|music| music.id);
+    if playing_music.current.as_ref().map(|current| current.id) != music_id {
+        if let Some(old) = playing_music.current.take() {
+            playing_music.fading_out.push(FadingMusic {
+                sink: old.sink,
+                volume: old.volume,
+                elapsed: 0.0,
+                duration: music_fade,
+            });
+        }
+        playing_music.current = music.as_ref().map(|music| {
+            let source = asset_server.load(format!("audio/{}", music.path).as_str());
+            let sink = audio_sinks.get_handle(audio.play_with_settings(
+                source.clone(),
                 PlaybackSettings {
-                    repeat: true,
-                    volume,
-                    ..Default::default()
+                    repeat: music.looping,
+                    volume: 0.0,
+                    speed: music.speed,
                 },
             ));
-            game_state.audio_manager.playing = Some(sink_handle);
+            CurrentMusic {
+                id: music.id,
+                path: music.path.clone(),
+                source,
+                sink,
+                looping: music.looping,
+                position: 0.0,
+                volume: 0.0,
+                elapsed: 0.0,
+                fade_in: music_fade,
+            }
+        });
+    }
+
+    // Apply the user's changes to the current music, fading it in if it just started
+    if let (Some(current), Some(music)) = (playing_music.current.as_mut(), music.as_ref()) {
+        if let Some(sink) = audio_sinks.get(&current.sink) {
+            current.elapsed += delta;
+            let fade = fade_progress(current.elapsed, current.fade_in);
+            current.volume = music.volume * fade;
+            sink.set_volume(current.volume * music_gain);
+            sink.set_speed(music.speed);
+            if !music.paused {
+                current.position += delta * music.speed;
+            }
+            if music.paused != sink.is_paused() {
+                if music.paused {
+                    sink.pause();
+                } else {
+                    sink.play();
+                }
+            }
         }
     }
+
+    // Fade out the old music, and stop it when it is silent
+    playing_music.fading_out.retain_mut(|fading| {
+        // Music that is still loading is kept until it can be stopped
+        let sink = match audio_sinks.get(&fading.sink) {
+            Some(sink) => sink,
+            None => return true,
+        };
+        fading.elapsed += delta;
+        let fade = fade_progress(fading.elapsed, fading.duration);
+        if fade >= 1.0 {
+            sink.stop();
+            return false;
+        }
+        sink.set_volume(fading.volume * (1.0 - fade) * music_gain);
+        true
+    });
+}
+
+/// How far along a fade of `duration` seconds is after `elapsed` seconds, from `0.0` to `1.0`
+fn fade_progress(elapsed: f32, duration: f32) -> f32 {
+    if duration > 0.0 {
+        (elapsed / duration).min(1.0)
+    } else {
+        1.0
+    }
+}
+
+/// The music that is playing, and how far it has faded in
+struct CurrentMusic {
+    id: u64,
+    path: String,
+    source: Handle<AudioSource>,
+    sink: Handle<AudioSink>,
+    looping: bool,
+    // seconds into the sound file
+    position: f32,
+    // the volume the music was last set to, before the buses
+    volume: f32,
+    elapsed: f32,
+    fade_in: f32,
+}
+
+/// Music that is fading out because other music was started
+struct FadingMusic {
+    sink: Handle<AudioSink>,
+    // the volume the music was at when it started fading out
+    volume: f32,
+    elapsed: f32,
+    duration: f32,
+}
+
+/// All the music the audio system is playing
+#[doc(hidden)]
+#[derive(Default)]
+pub struct PlayingMusic {
+    current: Option<CurrentMusic>,
+    fading_out: Vec<FadingMusic>,
 }
@@ -0,0 +1,638 @@
+//! Facilities for text drawn with bitmap fonts, for a crisp, retro, pixel-perfect look. A
+//! [`BitmapText`] works like a [`Text`](crate::prelude::Text), except that its glyphs come from an
+//! image instead of a TTF/OTF font. Each glyph is drawn as a sprite from a single texture atlas, so
+//! Bevy draws them in one batch, and the text is only ever scaled by whole numbers.
+//!
+//! Two kinds of bitmap fonts are supported (see [`BitmapFont`]):
+//!
+//! - [AngelCode BMFont](https://www.angelcode.com/products/bmfont/) `.fnt` files in the text
+//!   format, which many bitmap font tools can export. Only single-page fonts are supported.
+//! - Monospaced glyph sheets: an image with all the glyphs laid out in a grid of equal cells.
+
+use crate::{
+    assets::read_asset,
+    hud::{place, ScreenAnchor, Space},
+    prelude::Engine,
+    text::{HorizontalAlign, TextEffect, TextReveal, VerticalAlign, TEXT_DEFAULT_LAYER},
+    traits::EngineEntity,
+};
+use bevy::{
+    prelude::{
+        App, AssetServer, Assets, BuildChildren, Color, Commands, Component, DespawnRecursiveExt,
+        Entity, Handle, Local, ParallelSystemDescriptorCoercion, Plugin, Quat, Query, Res, ResMut,
+        SpatialBundle, Time, Transform, Vec2, Vec3, Visibility, With, Without,
+    },
+    sprite::{Rect, SpriteSheetBundle, TextureAtlas, TextureAtlasSprite},
+    utils::HashMap,
+};
+use std::{
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+pub(crate) struct BitmapTextPlugin;
+
+impl Plugin for BitmapTextPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system(sync_bitmap_texts.after("game_logic_sync"));
+    }
+}
+
+impl EngineEntity for BitmapText {
+    fn label(&self) -> &str {
+        &self.label
+    }
+}
+
+/// The printable ASCII characters, from space to `~`, in order. This is the order that the glyphs
+/// of most monospaced glyph sheets are laid out in.
+pub const BITMAP_FONT_ASCII: &str = " !\"#$%&'()*+,-./0123456789:;<=>?@ABCDEFGHIJKLMNOPQRSTUVWXYZ[\\]^_`abcdefghijklmnopqrstuvwxyz{|}~";
+
+/// A bitmap font for a [`BitmapText`]. The asset pack includes a small uppercase pixel font in
+/// both formats: `"font/pixel_5x7.fnt"`, and `"font/pixel_5x7.png"` as a grid of 6x8 cells, 16
+/// columns wide, with the characters from space to `_` in [`BITMAP_FONT_ASCII`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum BitmapFont {
+    /// An AngelCode BMFont `.fnt` file (in the text format) relative to the `assets/` directory.
+    /// The image of the font is found relative to the `.fnt` file, as BMFont expects.
+    BmFont(PathBuf),
+    /// A monospaced glyph sheet. The glyphs of `characters` are in order from left to right and
+    /// top to bottom, in cells of `glyph_width` by `glyph_height` pixels, `columns` cells per row.
+    Grid {
+        /// The image file, relative to the `assets/` directory
+        image: PathBuf,
+        /// The width of each cell in pixels, which is also how far apart the characters are
+        glyph_width: u32,
+        /// The height of each cell in pixels, which is also how far apart the lines are
+        glyph_height: u32,
+        /// How many cells there are in each row of the image
+        columns: u32,
+        /// The characters in the image, in order
+        characters: String,
+    },
+}
+
+impl BitmapFont {
+    /// A monospaced glyph sheet. See [`BitmapFont::Grid`].
+    pub fn grid<P: Into<PathBuf>, S: Into<String>>(
+        image: P,
+        glyph_width: u32,
+        glyph_height: u32,
+        columns: u32,
+        characters: S,
+    ) -> Self {
+        Self::Grid {
+            image: image.into(),
+            glyph_width,
+            glyph_height,
+            columns,
+            characters: characters.into(),
+        }
+    }
+}
+
+impl From<PathBuf> for BitmapFont {
+    fn from(filepath: PathBuf) -> Self {
+        Self::BmFont(filepath)
+    }
+}
+
+impl From<&str> for BitmapFont {
+    fn from(filepath: &str) -> Self {
+        Self::BmFont(filepath.into())
+    }
+}
+
+impl From<String> for BitmapFont {
+    fn from(filepath: String) -> Self {
+        Self::BmFont(filepath.into())
+    }
+}
+
+/// A [`BitmapText`] is a bit of text drawn with a [`BitmapFont`]. Add one with
+/// [`Engine::add_bitmap_text`](crate::prelude::Engine::add_bitmap_text).
+///
+/// Characters which aren't in the font are skipped.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BitmapText {
+    /// READONLY: A label to identify the text. This is not the text that is displayed! This is the
+    /// label you use to retrieve and modify your text from the
+    /// [`Engine::bitmap_texts`](crate::prelude::Engine::bitmap_texts) HashMap.
+    pub label: String,
+    /// SYNCED: The actual text value you want to display.
+    pub value: String,
+    /// SYNCED: The bitmap font to draw the text with
+    pub font: BitmapFont,
+    /// SYNCED: The tint of the glyphs. Bitmap fonts are usually white, so this is the color of the
+    /// text. Defaults to white.
+    pub color: Color,
+    /// SYNCED: Where you are in 2D game space. Positive x is right. Positive y is up. (0.0, 0.0)
+    /// is the center of the screen. If `space` is [`Space::Screen`], this is instead an offset from
+    /// the `anchor` point on the window.
+    pub translation: Vec2,
+    /// SYNCED: Depth of the text. 0.0 (back) to 999.0 (front)  Defaults to [`TEXT_DEFAULT_LAYER`]
+    pub layer: f32,
+    /// SYNCED: Direction you face in radians. Defaults to [`RIGHT`](crate::RIGHT).
+    pub rotation: f32,
+    /// SYNCED: How many screen pixels each pixel of the font is. Only whole numbers are allowed, so
+    /// the font stays crisp. Defaults to `1`.
+    pub scale: u32,
+    /// SYNCED: How the lines of the text are lined up horizontally, and which side of the text
+    /// `translation` is on. Defaults to [`HorizontalAlign::Center`].
+    pub horizontal_align: HorizontalAlign,
+    /// SYNCED: Which side of the text `translation` is on vertically. Defaults to
+    /// [`VerticalAlign::Center`].
+    pub vertical_align: VerticalAlign,
+    /// SYNCED: Whether the text lives in the game world (the default) or is fixed to the screen
+    /// like a HUD element. See [`Space`].
+    pub space: Space,
+    /// SYNCED: The point on the window that `translation` is relative to. Only used when `space` is
+    /// [`Space::Screen`]. Defaults to [`ScreenAnchor::Center`].
+    pub anchor: ScreenAnchor,
+    /// SYNCED: Set this to reveal the text a few characters at a time, like a typewriter. See
+    /// [`TextReveal`]. Defaults to `None`, meaning the whole text is shown.
+    pub reveal: Option<TextReveal>,
+    /// SYNCED: An animation which moves each character on its own, like a wave. Offsets are in font
+    /// pixels, so they are multiplied by `scale`. Defaults to [`TextEffect::None`].
+    pub effect: TextEffect,
+}
+
+impl BitmapText {
+    pub fn new<L: Into<String>, F: Into<BitmapFont>, S: Into<String>>(
+        label: L,
+        font: F,
+        value: S,
+    ) -> Self {
+        Self {
+            label: label.into(),
+            value: value.into(),
+            font: font.into(),
+            color: Color::WHITE,
+            translation: Vec2::ZERO,
+            layer: TEXT_DEFAULT_LAYER,
+            rotation: 0.0,
+            scale: 1,
+            horizontal_align: HorizontalAlign::Center,
+            vertical_align: VerticalAlign::Center,
+            space: Space::World,
+            anchor: ScreenAnchor::Center,
+            reveal: None,
+            effect: TextEffect::None,
+        }
+    }
+    /// How many characters there are in `value`
+    pub fn character_count(&self) -> usize {
+        self.value.chars().count()
+    }
+    /// Reveal the rest of the text right away, if it is being revealed
+    pub fn skip_reveal(&mut self) {
+        if let Some(reveal) = self.reveal.as_mut() {
+            reveal.skip();
+        }
+    }
+    #[doc(hidden)]
+    pub fn bevy_transform(&self) -> Transform {
+        let mut transform = Transform::from_translation(self.translation.extend(self.layer));
+        transform.rotation = Quat::from_axis_angle(Vec3::Z, self.rotation);
+        transform.scale = Vec3::splat(self.scale.max(1) as f32);
+        transform
+    }
+    /// Whether this has the same glyphs as `other`, so they don't need to be laid out again
+    fn same_glyphs(&self, other: &BitmapText) -> bool {
+        self.value == other.value
+            && self.font == other.font
+            && self.color == other.color
+            && self.horizontal_align == other.horizontal_align
+            && self.vertical_align == other.vertical_align
+            && self.space == other.space
+    }
+}
+
+/// A glyph in a loaded bitmap font, in font pixels
+#[derive(Clone, Copy, Debug)]
+struct Glyph {
+    /// The index of the glyph in the texture atlas. Glyphs with nothing to draw (like space) have
+    /// no index.
+    index: Option<usize>,
+    /// From the top-left of the glyph's position to the top-left of its image
+    offset: Vec2,
+    size: Vec2,
+    /// How far to move along the line after this glyph
+    advance: f32,
+}
+
+/// A bitmap font which has been loaded and turned into a texture atlas
+struct LoadedBitmapFont {
+    atlas: Handle<TextureAtlas>,
+    glyphs: HashMap<char, Glyph>,
+    kernings: HashMap<(char, char), f32>,
+    line_height: f32,
+}
+
+/// Splits a line of a BMFont file into its tag and its `key=value` attributes
+fn parse_bmfont_line(line: &str) -> (&str, HashMap<&str, &str>) {
+    let line = line.trim();
+    let (tag, mut rest) = line.split_once(' ').unwrap_or((line, ""));
+    let mut attributes = HashMap::default();
+    while let Some((key, after_key)) = rest.split_once('=') {
+        // Quoted values (like file names) may contain spaces
+        let (value, after_value) = match after_key.strip_prefix('"') {
+            Some(quoted) => quoted.split_once('"').unwrap_or((quoted, "")),
+            None => after_key.split_once(' ').unwrap_or((after_key, "")),
+        };
+        attributes.insert(key.trim(), value);
+        rest = after_value;
+    }
+    (tag, attributes)
+}
+
+/// Reads a number attribute of a BMFont line
+fn bmfont_number<T: FromStr>(attributes: &HashMap<&str, &str>, key: &str) -> Result<T, String> {
+    let value = attributes
+        .get(key)
+        .ok_or_else(|| format!("missing `{}`", key))?;
+    value
+        .trim()
+        .parse()
+        .map_err(|_| format!("`{}` is not a number: {}", key, value))
+}
+
+/// Loads a BMFont `.fnt` file, and builds its texture atlas
+fn load_bmfont(
+    filepath: &Path,
+    asset_server: &AssetServer,
+    texture_atlases: &mut Assets<TextureAtlas>,
+) -> Result<LoadedBitmapFont, String> {
+    let contents = read_asset(filepath).map_err(|e| e.to_string())?;
+    let contents = String::from_utf8_lossy(&contents);
+    let mut atlas_size = None;
+    let mut line_height = 0.0;
+    let mut page_file = None;
+    let mut chars = Vec::new();
+    let mut kernings = HashMap::default();
+    for line in contents.lines() {
+        let (tag, attributes) = parse_bmfont_line(line);
+        match tag {
+            "common" => {
+                line_height = bmfont_number(&attributes, "lineHeight")?;
+                atlas_size = Some(Vec2::new(
+                    bmfont_number(&attributes, "scaleW")?,
+                    bmfont_number(&attributes, "scaleH")?,
+                ));
+                if bmfont_number::<u32>(&attributes, "pages")? > 1 {
+                    eprintln!(
+                        "warning: only the first page of bitmap font {} is used",
+                        filepath.to_string_lossy()
+                    );
+                }
+            }
+            "page" => {
+                if bmfont_number::<u32>(&attributes, "id")? == 0 {
+                    page_file = attributes.get("file").map(|file| file.to_string());
+                }
+            }
+            "char" => {
+                if bmfont_number::<u32>(&attributes, "page").unwrap_or(0) != 0 {
+                    continue;
+                }
+                let character = match char::from_u32(bmfont_number(&attributes, "id")?) {
+                    Some(character) => character,
+                    None => continue,
+                };
+                let position = Vec2::new(
+                    bmfont_number(&attributes, "x")?,
+                    bmfont_number(&attributes, "y")?,
+                );
+                let size = Vec2::new(
+                    bmfont_number(&attributes, "width")?,
+                    bmfont_number(&attributes, "height")?,
+                );
+                let offset = Vec2::new(
+                    bmfont_number(&attributes, "xoffset")?,
+                    bmfont_number(&attributes, "yoffset")?,
+                );
+                let advance = bmfont_number(&attributes, "xadvance")?;
+                chars.push((character, position, size, offset, advance));
+            }
+            "kerning" => {
+                let first = char::from_u32(bmfont_number(&attributes, "first")?);
+                let second = char::from_u32(bmfont_number(&attributes, "second")?);
+                if let (Some(first), Some(second)) = (first, second) {
+                    kernings.insert((first, second), bmfont_number(&attributes, "amount")?);
+                }
+            }
+            _ => {}
+        }
+    }
+    let atlas_size = atlas_size.ok_or("missing the `common` line")?;
+    let page_file = page_file.ok_or("missing the `page` line")?;
+    // The image is relative to the .fnt file
+    let image_path = filepath
+        .parent()
+        .unwrap_or_else(|| Path::new(""))
+        .join(page_file);
+
+    let mut atlas = TextureAtlas::new_empty(asset_server.load(image_path), atlas_size);
+    let mut glyphs = HashMap::default();
+    for (character, position, size, offset, advance) in chars {
+        let index = (size.x > 0.0 && size.y > 0.0).then(|| {
+            atlas.add_texture(Rect {
+                min: position,
+                max: position + size,
+            })
+        });
+        glyphs.insert(
+            character,
+            Glyph {
+                index,
+                offset,
+                size,
+                advance,
+            },
+        );
+    }
+    Ok(LoadedBitmapFont {
+        atlas: texture_atlases.add(atlas),
+        glyphs,
+        kernings,
+        line_height,
+    })
+}
+
+/// Loads a bitmap font, and builds its texture atlas
+fn load_bitmap_font(
+    font: &BitmapFont,
+    asset_server: &AssetServer,
+    texture_atlases: &mut Assets<TextureAtlas>,
+) -> Result<LoadedBitmapFont, String> {
+    match font {
+        BitmapFont::BmFont(filepath) => load_bmfont(filepath, asset_server, texture_atlases),
+        BitmapFont::Grid {
+            image,
+            glyph_width,
+            glyph_height,
+            columns,
+            characters,
+        } => {
+            if *glyph_width == 0 || *glyph_height == 0 || *columns == 0 {
+                return Err("the glyph size and number of columns must not be zero".into());
+            }
+            let columns = *columns as usize;
+            let rows = (characters.chars().count() + columns - 1) / columns;
+            let size = Vec2::new(*glyph_width as f32, *glyph_height as f32);
+            let atlas =
+                TextureAtlas::from_grid(asset_server.load(image.as_path()), size, columns, rows);
+            let glyphs = characters
+                .chars()
+                .enumerate()
+                .map(|(index, character)| {
+                    let glyph = Glyph {
+                        index: Some(index),
+                        offset: Vec2::ZERO,
+                        size,
+                        advance: size.x,
+                    };
+                    (character, glyph)
+                })
+                .collect();
+            Ok(LoadedBitmapFont {
+                atlas: texture_atlases.add(atlas),
+                glyphs,
+                kernings: HashMap::default(),
+                line_height: size.y,
+            })
+        }
+    }
+}
+
+/// Lays out the glyphs of a text, returning the index of the character in `value`, the atlas
+/// index, and the center (relative to the text's translation, in font pixels) of each glyph that
+/// needs to be drawn
+fn layout_glyphs(text: &BitmapText, font: &LoadedBitmapFont) -> Vec<(usize, usize, Vec2)> {
+    let lines: Vec<&str> = text.value.split('\n').collect();
+    let height = lines.len() as f32 * font.line_height;
+    // Round the offsets, so that the pixels of the font line up with the pixels of the screen
+    let top = match text.vertical_align {
+        VerticalAlign::Top => 0.0,
+        VerticalAlign::Center => (height * 0.5).round(),
+        VerticalAlign::Bottom => height,
+    };
+    let mut placed = Vec::new();
+    let mut character_index = 0;
+    for (line_number, line) in lines.iter().enumerate() {
+        let line_top = top - line_number as f32 * font.line_height;
+        let mut x = 0.0;
+        let mut previous = None;
+        let mut line_glyphs = Vec::new();
+        for character in line.chars() {
+            character_index += 1;
+            let glyph = match font.glyphs.get(&character) {
+                Some(glyph) => glyph,
+                None => continue,
+            };
+            if let Some(previous) = previous {
+                x += font
+                    .kernings
+                    .get(&(previous, character))
+                    .copied()
+                    .unwrap_or(0.0);
+            }
+            if let Some(index) = glyph.index {
+                // Font coordinates go down from the top of the line
+                let center = Vec2::new(
+                    x + glyph.offset.x + glyph.size.x * 0.5,
+                    line_top - glyph.offset.y - glyph.size.y * 0.5,
+                );
+                line_glyphs.push((character_index - 1, index, center));
+            }
+            x += glyph.advance;
+            previous = Some(character);
+        }
+        // The newline at the end of the line counts as a character too
+        character_index += 1;
+        let left = match text.horizontal_align {
+            HorizontalAlign::Left => 0.0,
+            HorizontalAlign::Center => -(x * 0.5).round(),
+            HorizontalAlign::Right => -x,
+        };
+        placed.extend(
+            line_glyphs
+                .into_iter()
+                .map(|(character_index, index, center)| {
+                    (character_index, index, center + Vec2::new(left, 0.0))
+                }),
+        );
+    }
+    placed
+}
+
+/// Where a glyph is drawn this frame (in font pixels, relative to the text's translation), and
+/// whether it has been revealed
+fn glyph_placement(
+    bitmap_text: &BitmapText,
+    character_index: usize,
+    center: Vec2,
+    time: f32,
+) -> (Vec3, bool) {
+    let offset = bitmap_text.effect.offset(character_index, time);
+    let revealed = bitmap_text
+        .reveal
+        .as_ref()
+        .map_or(true, |reveal| character_index < reveal.visible_characters());
+    // Keep the glyphs on whole font pixels
+    ((center + offset.round()).extend(0.0), revealed)
+}
+
+/// Marks the entity that holds the glyphs of a [`BitmapText`]
+#[derive(Component)]
+#[doc(hidden)]
+pub struct BitmapTextRoot;
+
+/// Marks the sprite of one glyph of a [`BitmapText`]
+#[derive(Component)]
+#[doc(hidden)]
+pub struct BitmapGlyph;
+
+/// The entities of a bitmap text, and what its glyphs were last laid out from
+struct BitmapTextEntity {
+    entity: Entity,
+    laid_out: BitmapText,
+    /// The entity, character index, and center of each glyph
+    glyphs: Vec<(Entity, usize, Vec2)>,
+    /// Whether the glyphs were being revealed or moved by an effect last frame
+    animated: bool,
+}
+
+/// system - spawn, update, and remove the glyph sprites of the bitmap texts
+#[allow(clippy::type_complexity, clippy::too_many_arguments)]
+fn sync_bitmap_texts(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    time: Res<Time>,
+    mut texture_atlases: ResMut<Assets<TextureAtlas>>,
+    engine: Res<Engine>,
+    mut fonts: Local<HashMap<BitmapFont, Option<LoadedBitmapFont>>>,
+    mut bitmap_text_entities: Local<HashMap<String, BitmapTextEntity>>,
+    mut transform_query: Query<&mut Transform, With<BitmapTextRoot>>,
+    mut glyph_query: Query<
+        (&mut Transform, &mut Visibility),
+        (With<BitmapGlyph>, Without<BitmapTextRoot>),
+    >,
+) {
+    let time = time.seconds_since_startup() as f32;
+
+    // Remove the entities for any bitmap texts that the user removed
+    bitmap_text_entities.retain(|label, bitmap_text_entity| {
+        let keep = engine.bitmap_texts.contains_key(label);
+        if !keep {
+            commands
+                .entity(bitmap_text_entity.entity)
+                .despawn_recursive();
+        }
+        keep
+    });
+
+    for bitmap_text in engine.bitmap_texts.values() {
+        let transform = place(
+            bitmap_text.bevy_transform(),
+            bitmap_text.space,
+            bitmap_text.anchor,
+            engine.window_dimensions,
+        );
+        let animated = bitmap_text.reveal.is_some() || bitmap_text.effect != TextEffect::None;
+        let bitmap_text_entity = match bitmap_text_entities.get_mut(&bitmap_text.label) {
+            Some(bitmap_text_entity) => {
+                if let Ok(mut existing_transform) =
+                    transform_query.get_mut(bitmap_text_entity.entity)
+                {
+                    *existing_transform = transform;
+                }
+                if bitmap_text.same_glyphs(&bitmap_text_entity.laid_out) {
+                    // Only touch the glyphs if they are (or just stopped) being animated
+                    if animated || bitmap_text_entity.animated {
+                        for &(entity, character_index, center) in &bitmap_text_entity.glyphs {
+                            if let Ok((mut transform, mut visibility)) = glyph_query.get_mut(entity)
+                            {
+                                let (translation, revealed) =
+                                    glyph_placement(bitmap_text, character_index, center, time);
+                                transform.translation = translation;
+                                visibility.is_visible = revealed;
+                            }
+                        }
+                    }
+                    bitmap_text_entity.animated = animated;
+                    continue;
+                }
+                bitmap_text_entity.laid_out = bitmap_text.clone();
+                commands
+                    .entity(bitmap_text_entity.entity)
+                    .despawn_descendants();
+                bitmap_text_entity.glyphs.clear();
+                bitmap_text_entity
+            }
+            None => {
+                let entity = commands
+                    .spawn_bundle(SpatialBundle {
+                        transform,
+                        ..Default::default()
+                    })
+                    .insert(BitmapTextRoot)
+                    .id();
+                bitmap_text_entities
+                    .entry(bitmap_text.label.clone())
+                    .or_insert(BitmapTextEntity {
+                        entity,
+                        laid_out: bitmap_text.clone(),
+                        glyphs: Vec::new(),
+                        animated,
+                    })
+            }
+        };
+        bitmap_text_entity.animated = animated;
+
+        // Lay out the glyphs, loading the font the first time it is used
+        let font =
+            fonts.entry(bitmap_text.font.clone()).or_insert_with(|| {
+                match load_bitmap_font(&bitmap_text.font, &asset_server, &mut texture_atlases) {
+                    Ok(font) => Some(font),
+                    Err(e) => {
+                        eprintln!("failed to load bitmap font {:?}: {}", bitmap_text.font, e);
+                        None
+                    }
+                }
+            });
+        let font = match font {
+            Some(font) => font,
+            None => continue,
+        };
+        let render_layers = bitmap_text.space.render_layers();
+        let color = bitmap_text.color;
+        let glyphs = &mut bitmap_text_entity.glyphs;
+        commands
+            .entity(bitmap_text_entity.entity)
+            .with_children(|parent| {
+                for (character_index, index, center) in layout_glyphs(bitmap_text, font) {
+                    let (translation, revealed) =
+                        glyph_placement(bitmap_text, character_index, center, time);
+                    let entity = parent
+                        .spawn_bundle(SpriteSheetBundle {
+                            sprite: TextureAtlasSprite {
+                                index,
+                                color,
+                                ..Default::default()
+                            },
+                            texture_atlas: font.atlas.clone(),
+                            transform: Transform::from_translation(translation),
+                            visibility: Visibility {
+                                is_visible: revealed,
+                            },
+                            ..Default::default()
+                        })
+                        .insert(BitmapGlyph)
+                        .insert(render_layers)
+                        .id();
+                    glyphs.push((entity, character_index, center));
+                }
+            });
+    }
+}
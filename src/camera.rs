@@ -0,0 +1,339 @@
+//! Facilities for controlling the camera: panning, zooming, rotating, following a sprite, and
+//! camera effects like screen shake.
+
+use crate::{prelude::Engine, sprite::Sprite};
+use bevy::prelude::{
+    App, Camera2dBundle, Commands, Component, OrthographicProjection,
+    ParallelSystemDescriptorCoercion, Plugin, Quat, Query, ResMut, Transform, Vec2, Vec3, With,
+};
+
+pub(crate) struct CameraPlugin;
+
+impl Plugin for CameraPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_startup_system(spawn_camera)
+            .add_system(sync_camera.label("sync_camera").after("game_logic_sync"));
+    }
+}
+
+/// Component to mark the Bevy camera that renders the game world
+#[derive(Component)]
+#[doc(hidden)]
+pub struct GameCamera;
+
+/// The [`Camera`] determines which part of your 2D game world is shown in the window. It is
+/// exposed through the [`Engine::camera`](crate::prelude::Engine::camera) field. By default the
+/// camera sits at `(0.0, 0.0)` without any zoom or rotation, which means game space coordinates
+/// match up with the window the same way they always have.
+///
+/// ```rust,no_run
+/// # use rusty_engine::prelude::*;
+/// #
+/// # fn main() {
+/// # let mut game = Game::new();
+/// // Have the camera follow the player around, with a little lag
+/// game.camera.follow("player");
+/// game.camera.follow_smoothing = 0.2;
+/// // ...but never show anything outside of the level
+/// game.camera.set_bounds(Vec2::new(-2000.0, -1000.0), Vec2::new(2000.0, 1000.0));
+/// # game.run(());
+/// # }
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct Camera {
+    /// SYNCED: The point in 2D game space that appears in the center of the window. Defaults to
+    /// `(0.0, 0.0)`.
+    pub translation: Vec2,
+    /// SYNCED: How far the camera is zoomed in. `1.0` is normal. `2.0` makes everything appear
+    /// twice as large, `0.5` makes everything appear half as large. Must be greater than `0.0`.
+    pub zoom: f32,
+    /// SYNCED: Rotation of the camera in radians. Rotating the camera counter-clockwise makes the
+    /// world appear to rotate clockwise.
+    pub rotation: f32,
+    /// SYNCED: The label of a sprite for the camera to follow. If no sprite with this label exists,
+    /// the camera stays where it is. See [`Camera::follow`].
+    pub follow_target: Option<String>,
+    /// SYNCED: Roughly how many seconds the camera takes to catch up with the sprite it is
+    /// following. `0.0` (the default) means the camera snaps to the sprite immediately.
+    pub follow_smoothing: f32,
+    /// SYNCED: The size of a box (in logical pixels at zoom `1.0`) centered on the camera in which
+    /// the sprite being followed can move around without the camera moving. Defaults to
+    /// `(0.0, 0.0)`, so the camera moves whenever the sprite moves.
+    pub follow_deadzone: Vec2,
+    /// SYNCED: The bottom-left and top-right corners of the area of the game world that the camera
+    /// is allowed to show. If the area is smaller than the window, the camera is centered on it.
+    /// A rotated camera is kept far enough inside the bounds that none of its (tilted) view goes
+    /// outside of them. `None` (the default) means the camera can go anywhere.
+    pub bounds: Option<(Vec2, Vec2)>,
+    // Screen shake state. Trauma goes from 1.0 down to 0.0, and the shake is proportional to the
+    // square of the trauma so that it tapers off nicely.
+    trauma: f32,
+    trauma_decay: f32,
+    shake_intensity: f32,
+    shake_time: f32,
+    shake_offset: Vec2,
+    zoom_punch: Option<ZoomPunch>,
+    move_to: Option<CameraMove>,
+}
+
+/// An in-progress zoom punch: a sudden zoom which eases back to normal
+#[derive(Clone, Debug, PartialEq)]
+struct ZoomPunch {
+    amount: f32,
+    elapsed: f32,
+    duration: f32,
+}
+
+impl ZoomPunch {
+    /// The amount to multiply the zoom by right now
+    fn factor(&self) -> f32 {
+        let remaining = 1.0 - (self.elapsed / self.duration).clamp(0.0, 1.0);
+        1.0 + self.amount * remaining * remaining
+    }
+}
+
+/// An in-progress smooth move of the camera from one place to another
+#[derive(Clone, Debug, PartialEq)]
+struct CameraMove {
+    from: Vec2,
+    to: Vec2,
+    elapsed: f32,
+    duration: f32,
+}
+
+impl Default for Camera {
+    fn default() -> Self {
+        Self {
+            translation: Vec2::ZERO,
+            zoom: 1.0,
+            rotation: 0.0,
+            follow_target: None,
+            follow_smoothing: 0.0,
+            follow_deadzone: Vec2::ZERO,
+            bounds: None,
+            trauma: 0.0,
+            trauma_decay: 0.0,
+            shake_intensity: 0.0,
+            shake_time: 0.0,
+            shake_offset: Vec2::ZERO,
+            zoom_punch: None,
+            move_to: None,
+        }
+    }
+}
+
+impl Camera {
+    /// Follow the sprite with the given label. The camera will keep following the sprite until
+    /// you call [`stop_following`](Camera::stop_following).
+    pub fn follow<T: Into<String>>(&mut self, label: T) {
+        self.follow_target = Some(label.into());
+    }
+    /// Stop following a sprite. The camera stays wherever it currently is.
+    pub fn stop_following(&mut self) {
+        self.follow_target = None;
+    }
+    /// Restrict the camera so that it never shows anything outside of the rectangle described by
+    /// the `bottom_left` and `top_right` corners (in game space).
+    pub fn set_bounds(&mut self, bottom_left: Vec2, top_right: Vec2) {
+        self.bounds = Some((bottom_left.min(top_right), bottom_left.max(top_right)));
+    }
+    /// Let the camera go anywhere again.
+    pub fn clear_bounds(&mut self) {
+        self.bounds = None;
+    }
+    /// Shake the camera. `intensity` is the furthest (in logical pixels) that the camera will be
+    /// knocked away from where it should be, and `duration` is how many seconds it takes for the
+    /// shaking to die down. Shaking again while the camera is already shaking adds to the shake
+    /// instead of replacing it.
+    pub fn shake(&mut self, intensity: f32, duration: f32) {
+        // Combine with whatever shake is left over, so rapid impacts feel like they pile up
+        let remaining_intensity = self.shake_intensity * self.trauma * self.trauma;
+        self.shake_intensity = intensity.abs().max(remaining_intensity);
+        self.trauma = 1.0;
+        self.trauma_decay = 1.0 / duration.max(f32::EPSILON);
+    }
+    /// Stop shaking the camera immediately.
+    pub fn stop_shaking(&mut self) {
+        self.trauma = 0.0;
+        self.shake_offset = Vec2::ZERO;
+    }
+    /// Whether the camera is currently shaking.
+    pub fn is_shaking(&self) -> bool {
+        self.trauma > 0.0
+    }
+    /// Suddenly zoom in by `amount` (`0.2` means 20% closer) and then ease back to the normal
+    /// [`zoom`](Camera::zoom) over `duration` seconds. Use a negative `amount` to punch outwards.
+    pub fn zoom_punch(&mut self, amount: f32, duration: f32) {
+        self.zoom_punch = Some(ZoomPunch {
+            amount: amount.max(-0.95),
+            elapsed: 0.0,
+            duration: duration.max(f32::EPSILON),
+        });
+    }
+    /// Smoothly move the camera to `target` (in game space) over `duration` seconds, easing in and
+    /// out. While the camera is moving, it doesn't [`follow`](Camera::follow) anything.
+    pub fn move_to(&mut self, target: Vec2, duration: f32) {
+        self.move_to = Some(CameraMove {
+            from: self.translation,
+            to: target,
+            elapsed: 0.0,
+            duration: duration.max(f32::EPSILON),
+        });
+    }
+    /// Whether the camera is in the middle of a [`move_to`](Camera::move_to).
+    pub fn is_moving(&self) -> bool {
+        self.move_to.is_some()
+    }
+    /// The zoom that is actually used this frame, including any zoom punch
+    fn effective_zoom(&self) -> f32 {
+        let punch = self.zoom_punch.as_ref().map_or(1.0, ZoomPunch::factor);
+        (self.zoom * punch).max(f32::EPSILON)
+    }
+    /// The translation that is actually used this frame, including any screen shake
+    fn effective_translation(&self) -> Vec2 {
+        self.translation + self.shake_offset
+    }
+    /// Convert a location relative to the center of the window (positive y is up) into a location
+    /// in 2D game space, taking the camera's translation, zoom, rotation, and effects into account.
+    pub fn screen_to_world(&self, screen_location: Vec2) -> Vec2 {
+        let scaled = screen_location / self.effective_zoom();
+        self.effective_translation() + rotate(scaled, self.rotation)
+    }
+    /// Convert a location in 2D game space into a location relative to the center of the window
+    /// (positive y is up). This is the opposite of [`screen_to_world`](Camera::screen_to_world).
+    pub fn world_to_screen(&self, world_location: Vec2) -> Vec2 {
+        let unrotated = rotate(
+            world_location - self.effective_translation(),
+            -self.rotation,
+        );
+        unrotated * self.effective_zoom()
+    }
+    /// Advance any camera effects by `delta` seconds, move the camera towards the `target` (if
+    /// any), and then keep it inside of the bounds (if any). `window_dimensions` are used to figure
+    /// out how much of the world is visible.
+    #[doc(hidden)]
+    pub fn update(&mut self, target: Option<Vec2>, delta: f32, window_dimensions: Vec2) {
+        self.zoom = self.zoom.max(f32::EPSILON);
+        self.update_effects(delta);
+        if let Some(camera_move) = &mut self.move_to {
+            camera_move.elapsed += delta;
+            let t = (camera_move.elapsed / camera_move.duration).clamp(0.0, 1.0);
+            // smoothstep, so the camera eases in and out
+            let eased = t * t * (3.0 - 2.0 * t);
+            self.translation = camera_move.from.lerp(camera_move.to, eased);
+            if t >= 1.0 {
+                self.move_to = None;
+            }
+        } else if let Some(target) = target {
+            // Only move far enough to get the target back inside the deadzone
+            let half_deadzone = self.follow_deadzone.abs() * 0.5;
+            let offset = target - self.translation;
+            let overshoot = offset - offset.clamp(-half_deadzone, half_deadzone);
+            let goal = self.translation + overshoot;
+            if self.follow_smoothing > 0.0 {
+                let t = 1.0 - (-delta / self.follow_smoothing).exp();
+                self.translation = self.translation.lerp(goal, t);
+            } else {
+                self.translation = goal;
+            }
+        }
+        if let Some((bottom_left, top_right)) = self.bounds {
+            // The half-size of the axis-aligned box around the (possibly rotated) view
+            let half_view = window_dimensions * 0.5 / self.zoom;
+            let (sin, cos) = (self.rotation.sin().abs(), self.rotation.cos().abs());
+            let half_view = Vec2::new(
+                cos * half_view.x + sin * half_view.y,
+                sin * half_view.x + cos * half_view.y,
+            );
+            let min = bottom_left + half_view;
+            let max = top_right - half_view;
+            let center = (bottom_left + top_right) * 0.5;
+            self.translation.x = if min.x <= max.x {
+                self.translation.x.clamp(min.x, max.x)
+            } else {
+                center.x
+            };
+            self.translation.y = if min.y <= max.y {
+                self.translation.y.clamp(min.y, max.y)
+            } else {
+                center.y
+            };
+        }
+    }
+    /// Advance the screen shake and zoom punch by `delta` seconds
+    fn update_effects(&mut self, delta: f32) {
+        if self.trauma > 0.0 {
+            self.trauma = (self.trauma - self.trauma_decay * delta).max(0.0);
+            self.shake_time += delta;
+            let shake = self.shake_intensity * self.trauma * self.trauma;
+            self.shake_offset =
+                Vec2::new(wobble(self.shake_time, 0.0), wobble(self.shake_time, 100.0)) * shake;
+        } else {
+            self.shake_offset = Vec2::ZERO;
+        }
+        if let Some(zoom_punch) = &mut self.zoom_punch {
+            zoom_punch.elapsed += delta;
+            if zoom_punch.elapsed >= zoom_punch.duration {
+                self.zoom_punch = None;
+            }
+        }
+    }
+    /// Apply the camera's translation (including shake) and rotation to Bevy's camera transform,
+    /// leaving the depth of the camera alone.
+    #[doc(hidden)]
+    pub fn apply_to(&self, transform: &mut Transform) {
+        transform.translation = self.effective_translation().extend(transform.translation.z);
+        transform.rotation = Quat::from_axis_angle(Vec3::Z, self.rotation);
+    }
+    /// The scale to use for Bevy's orthographic projection, including any zoom punch
+    #[doc(hidden)]
+    pub fn projection_scale(&self) -> f32 {
+        1.0 / self.effective_zoom()
+    }
+}
+
+/// Rotate a point around the origin by `rotation` radians
+fn rotate(p: Vec2, rotation: f32) -> Vec2 {
+    let sin = rotation.sin();
+    let cos = rotation.cos();
+    Vec2::new(p.x * cos - p.y * sin, p.x * sin + p.y * cos)
+}
+
+/// Smooth pseudo-random noise in the range `-1.0..=1.0`, made by layering a few sine waves with
+/// frequencies that don't line up. `seed` selects a different-looking wave.
+fn wobble(time: f32, seed: f32) -> f32 {
+    ((time * 31.0 + seed).sin()
+        + (time * 57.0 + seed * 1.7).sin() * 0.5
+        + (time * 89.0 + seed * 2.3).sin() * 0.25)
+        / 1.75
+}
+
+/// startup system - spawn the camera which renders the game world
+fn spawn_camera(mut commands: Commands) {
+    commands
+        .spawn_bundle(Camera2dBundle::default())
+        .insert(GameCamera);
+}
+
+/// system - move the camera towards whatever it is following and sync it over to Bevy
+fn sync_camera(
+    mut engine: ResMut<Engine>,
+    sprite_query: Query<&Sprite>,
+    mut camera_query: Query<(&mut Transform, &mut OrthographicProjection), With<GameCamera>>,
+) {
+    let target = engine.camera.follow_target.as_ref().and_then(|label| {
+        sprite_query
+            .iter()
+            .find(|sprite| &sprite.label == label)
+            .map(|sprite| sprite.translation)
+    });
+    let delta = engine.delta_f32;
+    let window_dimensions = engine.window_dimensions;
+    engine.camera.update(target, delta, window_dimensions);
+
+    for (mut transform, mut projection) in camera_query.iter_mut() {
+        engine.camera.apply_to(&mut transform);
+        engine.screen_mapping.apply_to(&mut projection);
+        projection.scale = engine.camera.projection_scale();
+    }
+}
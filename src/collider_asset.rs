@@ -0,0 +1,167 @@
+//! Facilities for loading `.collider` files through Bevy's asset system, so that colliders are
+//! loaded asynchronously and are reloaded when the files change. A new sprite gets its collider
+//! from the collider cache if the file has already been loaded, and otherwise once it has loaded
+//! (usually a frame or two later).
+//!
+//! Reloading colliders while the game is running requires the `hot_reload` cargo feature, which
+//! turns on Bevy's file watcher. With it enabled, you can edit a `.collider` file (by hand, or with
+//! the `collider` example) and every sprite using it is updated right away.
+
+use crate::{
+    assets::{asset_exists, asset_path},
+    physics::Collider,
+    prelude::Engine,
+    sprite::Sprite,
+};
+use bevy::{
+    asset::{AssetLoader, BoxedFuture, LoadContext, LoadedAsset},
+    prelude::{
+        AddAsset, Added, App, AssetEvent, AssetServer, Assets, EventReader, Handle, Local,
+        ParallelSystemDescriptorCoercion, ParamSet, Plugin, Query, Res, ResMut, Time,
+    },
+    reflect::TypeUuid,
+    utils::{HashMap, HashSet},
+};
+use std::path::PathBuf;
+
+pub(crate) struct ColliderAssetPlugin;
+
+impl Plugin for ColliderAssetPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_asset::<ColliderAsset>()
+            .init_asset_loader::<ColliderAssetLoader>()
+            .add_system(sync_collider_assets.before("game_logic_sync"));
+    }
+}
+
+/// A [`Collider`] loaded from a `.collider` file by Bevy's asset system
+#[derive(Clone, Debug, PartialEq, TypeUuid)]
+#[uuid = "804c9635-3da6-4211-8ca4-dcf63be66c0b"]
+pub struct ColliderAsset(pub Collider);
+
+/// Loads `.collider` files, which contain a [`Collider`] in the
+/// [RON](https://github.com/ron-rs/ron) format
+#[derive(Default)]
+#[doc(hidden)]
+pub struct ColliderAssetLoader;
+
+impl AssetLoader for ColliderAssetLoader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<(), anyhow::Error>> {
+        Box::pin(async move {
+            let collider = ron::de::from_bytes::<Collider>(bytes)?;
+            load_context.set_default_asset(LoadedAsset::new(ColliderAsset(collider)));
+            Ok(())
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["collider"]
+    }
+}
+
+/// How often to look again for the collider files of sprites which didn't have one, in seconds
+const MISSING_COLLIDER_CHECK_INTERVAL: f32 = 1.0;
+
+/// The collider files that have been asked for, so far
+#[derive(Default)]
+struct ColliderFiles {
+    handles: HashMap<PathBuf, Handle<ColliderAsset>>,
+    // files which didn't exist when a sprite asked for them
+    missing: HashSet<PathBuf>,
+    // seconds since we last looked for the missing files
+    since_missing_check: f32,
+}
+
+/// system - load the collider files of all the sprites as assets, and when one of them is loaded or
+/// changes, update the collider cache and every sprite that uses the file
+#[allow(clippy::type_complexity, clippy::too_many_arguments)]
+fn sync_collider_assets(
+    asset_server: Res<AssetServer>,
+    collider_assets: Res<Assets<ColliderAsset>>,
+    time: Res<Time>,
+    mut asset_events: EventReader<AssetEvent<ColliderAsset>>,
+    mut engine: ResMut<Engine>,
+    mut collider_files: Local<ColliderFiles>,
+    mut sprite_queries: ParamSet<(Query<&Sprite, Added<Sprite>>, Query<&mut Sprite>)>,
+) {
+    let ColliderFiles {
+        handles,
+        missing,
+        since_missing_check,
+    } = &mut *collider_files;
+
+    // Start loading the collider file of any new sprite whose file we haven't seen before. Sprites
+    // without a collider file are common, so don't ask the asset server for files that don't exist.
+    for sprite in sprite_queries.p0().iter() {
+        let collider_filepath = &sprite.collider_filepath;
+        if handles.contains_key(collider_filepath) || missing.contains(collider_filepath) {
+            continue;
+        }
+        if asset_exists(collider_filepath) {
+            handles.insert(
+                collider_filepath.clone(),
+                asset_server.load(collider_filepath.as_path()),
+            );
+        } else {
+            eprintln!(
+                "warning: could not find collider file {} -- consider creating one with the `collider` example.",
+                asset_path(collider_filepath).to_string_lossy()
+            );
+            missing.insert(collider_filepath.clone());
+        }
+    }
+
+    // Files can be created while the game is running (by the `collider` example, for one), so look
+    // for the missing ones again every now and then
+    *since_missing_check += time.delta_seconds();
+    if *since_missing_check >= MISSING_COLLIDER_CHECK_INTERVAL {
+        *since_missing_check = 0.0;
+        missing.retain(|collider_filepath| {
+            if !asset_exists(collider_filepath) {
+                return true;
+            }
+            handles.insert(
+                collider_filepath.clone(),
+                asset_server.load(collider_filepath.as_path()),
+            );
+            false
+        });
+    }
+
+    for event in asset_events.iter() {
+        let handle = match event {
+            AssetEvent::Created { handle } | AssetEvent::Modified { handle } => handle,
+            AssetEvent::Removed { .. } => continue,
+        };
+        let collider = match collider_assets.get(handle) {
+            Some(collider_asset) => collider_asset.0.clone(),
+            None => continue,
+        };
+        let collider_filepath = handles
+            .iter()
+            .find_map(|(path, known_handle)| (known_handle.id == handle.id).then(|| path.clone()));
+        let collider_filepath = match collider_filepath {
+            Some(collider_filepath) => collider_filepath,
+            None => continue,
+        };
+        // Sprites created from now on should get the new collider
+        Collider::cache(&collider_filepath, collider.clone());
+        // The first load matches what the sprites already read from the cache, unless the file
+        // changed in the meantime. Either way, only touch sprites whose collider is different.
+        for mut sprite in sprite_queries.p1().iter_mut() {
+            if sprite.collider_filepath == collider_filepath && sprite.collider != collider {
+                sprite.collider = collider.clone();
+                sprite.collider_dirty = true;
+            }
+        }
+        for pool in engine.pools.values_mut() {
+            if pool.template.collider_filepath == collider_filepath {
+                pool.template.collider = collider.clone();
+            }
+        }
+    }
+}
@@ -0,0 +1,147 @@
+//! Facilities for configuring how colliders are drawn when
+//! [`Engine::show_colliders`](crate::prelude::Engine::show_colliders) is turned on.
+
+use crate::physics::Collider;
+use bevy::prelude::{Color, Vec2};
+use bevy_prototype_lyon::prelude::{DrawMode, FillMode, Path, PathBuilder, StrokeMode};
+
+/// Settings for drawing colliders. The settings in
+/// [`Engine::collider_debug`](crate::prelude::Engine::collider_debug) apply to every sprite, unless
+/// a sprite has its own settings in [`Sprite::collider_debug`](crate::prelude::Sprite).
+///
+/// ```rust,no_run
+/// # use rusty_engine::prelude::*;
+/// #
+/// # fn main() {
+/// # let mut game = Game::new();
+/// game.show_colliders = true;
+/// // Fill colliders in with a translucent color, and turn them red while they are colliding
+/// game.collider_debug.fill_color = Some(Color::rgba(1.0, 1.0, 1.0, 0.2));
+/// game.collider_debug.colliding_color = Some(Color::RED);
+/// # game.run(());
+/// # }
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct ColliderDebugSettings {
+    /// The color of the collider outline. Defaults to white.
+    pub color: Color,
+    /// The width of the collider outline in logical pixels. It stays the same no matter how the
+    /// sprite is scaled. Defaults to `1.0`.
+    pub thickness: f32,
+    /// The color to fill the inside of the collider with, or `None` to leave it empty. Use a
+    /// translucent color (with an alpha less than `1.0`) to still be able to see the sprite.
+    /// Defaults to `None`.
+    pub fill_color: Option<Color>,
+    /// The color to use for the outline (and the bounding box) while the sprite is colliding with
+    /// something, or `None` to always use `color`. Only sprites with `collision` set to `true` can
+    /// collide. Defaults to `None`.
+    pub colliding_color: Option<Color>,
+    /// Whether to also draw the axis-aligned bounding box around the collider. Defaults to
+    /// `false`.
+    pub show_bounding_box: bool,
+    /// Whether to also draw a small cross at the sprite's origin (its `translation`). Defaults to
+    /// `false`.
+    pub show_origin: bool,
+    /// Whether to also draw a line from the origin in the direction the sprite is facing (its
+    /// `rotation`). Defaults to `false`.
+    pub show_direction: bool,
+    /// How much higher the collider is drawn than the sprite's `layer`, so that it appears on top
+    /// of the sprite. Defaults to `0.1`.
+    pub z_offset: f32,
+}
+
+impl Default for ColliderDebugSettings {
+    fn default() -> Self {
+        Self {
+            color: Color::WHITE,
+            thickness: 1.0,
+            fill_color: None,
+            colliding_color: None,
+            show_bounding_box: false,
+            show_origin: false,
+            show_direction: false,
+            z_offset: 0.1,
+        }
+    }
+}
+
+impl ColliderDebugSettings {
+    /// The outline color to use, depending on whether the sprite is colliding
+    pub(crate) fn outline_color(&self, colliding: bool) -> Color {
+        match self.colliding_color {
+            Some(colliding_color) if colliding => colliding_color,
+            _ => self.color,
+        }
+    }
+
+    /// How lyon should draw the collider. Stroke line width gets scaled with the transform, but we
+    /// want it to appear to be the same regardless of scale, so we have to counter the `scale`.
+    pub(crate) fn draw_mode(&self, colliding: bool, scale: f32) -> DrawMode {
+        let outline_mode = StrokeMode::new(
+            self.outline_color(colliding),
+            self.thickness / scale.max(f32::EPSILON),
+        );
+        match self.fill_color {
+            Some(fill_color) => DrawMode::Outlined {
+                fill_mode: FillMode::color(fill_color),
+                outline_mode,
+            },
+            None => DrawMode::Stroke(outline_mode),
+        }
+    }
+
+    /// Whether switching from `other` to these settings changes the shape of the collider lines
+    pub(crate) fn same_geometry(&self, other: &Self) -> bool {
+        self.show_origin == other.show_origin && self.show_direction == other.show_direction
+    }
+
+    /// The path for a collider (relative to the sprite), plus the origin and direction markers if
+    /// they are turned on. Returns `None` if there is nothing to draw.
+    pub(crate) fn path(&self, collider: &Collider) -> Option<Path> {
+        let points = collider.points(); // will be empty vector if NoCollider
+        if points.len() < 2 {
+            return None;
+        }
+        let mut path_builder = PathBuilder::new();
+        path_builder.move_to(points[0]);
+        for point in &points[1..] {
+            path_builder.line_to(*point);
+        }
+        path_builder.close(); // draws the line from the last point to the first point
+
+        // Size the markers relative to the collider, so they scale along with the sprite
+        let extent = points
+            .iter()
+            .map(|point| point.length())
+            .fold(0.0, f32::max)
+            .max(10.0);
+        if self.show_origin {
+            let arm = extent * 0.15;
+            path_builder.move_to(Vec2::new(-arm, 0.0));
+            path_builder.line_to(Vec2::new(arm, 0.0));
+            path_builder.move_to(Vec2::new(0.0, -arm));
+            path_builder.line_to(Vec2::new(0.0, arm));
+        }
+        if self.show_direction {
+            // An unrotated sprite faces right, so the transform takes care of the rotation
+            path_builder.move_to(Vec2::ZERO);
+            path_builder.line_to(Vec2::new(extent, 0.0));
+        }
+        Some(path_builder.build())
+    }
+}
+
+/// The path of the axis-aligned bounding box around `points` (in 2D game space)
+pub(crate) fn bounding_box_path(points: &[Vec2]) -> Option<Path> {
+    let first = *points.first()?;
+    let (min, max) = points.iter().fold((first, first), |(min, max), point| {
+        (min.min(*point), max.max(*point))
+    });
+    let mut path_builder = PathBuilder::new();
+    path_builder.move_to(min);
+    path_builder.line_to(Vec2::new(max.x, min.y));
+    path_builder.line_to(max);
+    path_builder.line_to(Vec2::new(min.x, max.y));
+    path_builder.close();
+    Some(path_builder.build())
+}
@@ -0,0 +1,223 @@
+//! Facilities for immediate-mode debug drawing: lines, circles, rectangles, arrows, and text that
+//! only last for a single frame. Handy for visualizing velocities, targets, and raycasts.
+//!
+//! Debug drawing is enabled by the `debug_draw` cargo feature, which is on by default. When the
+//! feature is disabled, all of the drawing calls do nothing.
+
+#[cfg(feature = "debug_draw")]
+use crate::{prelude::Engine, shape::ShapeKind};
+#[cfg(feature = "debug_draw")]
+use bevy::prelude::{
+    App, AssetServer, Commands, Component, Entity, HorizontalAlign,
+    ParallelSystemDescriptorCoercion, Plugin, Query, Res, ResMut, Text as BevyText, Text2dBundle,
+    TextAlignment, TextStyle, Transform, VerticalAlign, With,
+};
+use bevy::prelude::{Color, Vec2};
+#[cfg(feature = "debug_draw")]
+use bevy_prototype_lyon::prelude::{DrawMode, GeometryBuilder, StrokeMode};
+
+/// The depth that debug drawings are drawn at, on top of everything else in the game world
+#[cfg(feature = "debug_draw")]
+const DEBUG_DRAW_LAYER: f32 = 999.5;
+
+#[cfg(feature = "debug_draw")]
+pub(crate) struct DebugDrawPlugin;
+
+#[cfg(feature = "debug_draw")]
+impl Plugin for DebugDrawPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system(sync_debug_draw.after("game_logic_sync"));
+    }
+}
+
+/// A single thing to draw this frame
+#[cfg(feature = "debug_draw")]
+#[derive(Clone, Debug, PartialEq)]
+enum DebugShape {
+    Geometry {
+        kind: ShapeKind,
+        translation: Vec2,
+        color: Color,
+    },
+    Text {
+        location: Vec2,
+        value: String,
+        color: Color,
+    },
+}
+
+/// [`DebugDraw`] is exposed through the [`Engine::debug_draw`](crate::prelude::Engine::debug_draw)
+/// field. Everything you draw with it is shown on top of the game world (in 2D game space) for the
+/// current frame only, so call the drawing methods every frame for as long as you want to see
+/// something.
+///
+/// ```rust,no_run
+/// # use rusty_engine::prelude::*;
+/// #
+/// fn logic(engine: &mut Engine, _: &mut ()) {
+///     let player = engine.sprites.get("player").unwrap();
+///     let (start, rotation) = (player.translation, player.rotation);
+///     // Show which way the player is facing
+///     let end = start + Vec2::new(rotation.cos(), rotation.sin()) * 100.0;
+///     engine.debug_draw.arrow(start, end, Color::YELLOW);
+/// }
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct DebugDraw {
+    /// The width of lines and outlines. Defaults to `2.0`.
+    pub thickness: f32,
+    /// The font size of text. Defaults to `16.0`.
+    pub font_size: f32,
+    #[cfg(feature = "debug_draw")]
+    shapes: Vec<DebugShape>,
+}
+
+impl Default for DebugDraw {
+    fn default() -> Self {
+        Self {
+            thickness: 2.0,
+            font_size: 16.0,
+            #[cfg(feature = "debug_draw")]
+            shapes: Vec::new(),
+        }
+    }
+}
+
+impl DebugDraw {
+    /// Draw a line from `start` to `end`
+    #[inline]
+    pub fn line(&mut self, start: Vec2, end: Vec2, color: Color) {
+        #[cfg(feature = "debug_draw")]
+        self.shapes.push(DebugShape::Geometry {
+            kind: ShapeKind::Line { start, end },
+            translation: Vec2::ZERO,
+            color,
+        });
+        #[cfg(not(feature = "debug_draw"))]
+        let _ = (start, end, color);
+    }
+    /// Draw the outline of a circle
+    #[inline]
+    pub fn circle(&mut self, center: Vec2, radius: f32, color: Color) {
+        #[cfg(feature = "debug_draw")]
+        self.shapes.push(DebugShape::Geometry {
+            kind: ShapeKind::Circle { radius },
+            translation: center,
+            color,
+        });
+        #[cfg(not(feature = "debug_draw"))]
+        let _ = (center, radius, color);
+    }
+    /// Draw the outline of a rectangle
+    #[inline]
+    pub fn rect(&mut self, center: Vec2, size: Vec2, color: Color) {
+        #[cfg(feature = "debug_draw")]
+        self.shapes.push(DebugShape::Geometry {
+            kind: ShapeKind::Rect { size },
+            translation: center,
+            color,
+        });
+        #[cfg(not(feature = "debug_draw"))]
+        let _ = (center, size, color);
+    }
+    /// Draw an arrow from `start` pointing at `end`
+    #[inline]
+    pub fn arrow(&mut self, start: Vec2, end: Vec2, color: Color) {
+        #[cfg(feature = "debug_draw")]
+        {
+            self.line(start, end, color);
+            let length = (end - start).length();
+            if length > 0.0 {
+                // Two short lines angled back from the tip make the arrow head
+                let back = (start - end) / length * (length * 0.3).min(12.0);
+                let (sin, cos) = 0.45_f32.sin_cos();
+                let left = Vec2::new(back.x * cos - back.y * sin, back.x * sin + back.y * cos);
+                let right = Vec2::new(back.x * cos + back.y * sin, -back.x * sin + back.y * cos);
+                self.shapes.push(DebugShape::Geometry {
+                    kind: ShapeKind::Path {
+                        points: vec![end + left, end, end + right],
+                        closed: false,
+                    },
+                    translation: Vec2::ZERO,
+                    color,
+                });
+            }
+        }
+        #[cfg(not(feature = "debug_draw"))]
+        let _ = (start, end, color);
+    }
+    /// Draw some text centered on `location`
+    #[inline]
+    pub fn text<T: Into<String>>(&mut self, location: Vec2, text: T, color: Color) {
+        #[cfg(feature = "debug_draw")]
+        self.shapes.push(DebugShape::Text {
+            location,
+            value: text.into(),
+            color,
+        });
+        #[cfg(not(feature = "debug_draw"))]
+        let _ = (location, text, color);
+    }
+}
+
+/// Component to mark the Bevy entities that are drawn for a single frame by [`DebugDraw`]
+#[cfg(feature = "debug_draw")]
+#[derive(Component)]
+#[doc(hidden)]
+pub struct DebugDrawing;
+
+/// system - replace last frame's debug drawings with this frame's
+#[cfg(feature = "debug_draw")]
+fn sync_debug_draw(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut engine: ResMut<Engine>,
+    query: Query<Entity, With<DebugDrawing>>,
+) {
+    for entity in query.iter() {
+        commands.entity(entity).despawn();
+    }
+    let thickness = engine.debug_draw.thickness;
+    let font_size = engine.debug_draw.font_size;
+    for shape in engine.debug_draw.shapes.drain(..) {
+        match shape {
+            DebugShape::Geometry {
+                kind,
+                translation,
+                color,
+            } => {
+                commands
+                    .spawn_bundle(GeometryBuilder::build_as(
+                        &kind.path(),
+                        DrawMode::Stroke(StrokeMode::new(color, thickness)),
+                        Transform::from_translation(translation.extend(DEBUG_DRAW_LAYER)),
+                    ))
+                    .insert(DebugDrawing);
+            }
+            DebugShape::Text {
+                location,
+                value,
+                color,
+            } => {
+                commands
+                    .spawn_bundle(Text2dBundle {
+                        text: BevyText::from_section(
+                            value,
+                            TextStyle {
+                                font: asset_server.load("font/FiraMono-Medium.ttf"),
+                                font_size,
+                                color,
+                            },
+                        )
+                        .with_alignment(TextAlignment {
+                            vertical: VerticalAlign::Center,
+                            horizontal: HorizontalAlign::Center,
+                        }),
+                        transform: Transform::from_translation(location.extend(DEBUG_DRAW_LAYER)),
+                        ..Default::default()
+                    })
+                    .insert(DebugDrawing);
+            }
+        }
+    }
+}
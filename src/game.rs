@@ -1,32 +1,57 @@
 use bevy::{
     app::AppExit,
+    asset::AssetServerSettings,
+    input::Input,
     prelude::{
-        debug, App, AssetServer, Camera2dBundle, Color, Commands, Component, DefaultPlugins,
-        Entity, EventReader, EventWriter, HorizontalAlign, ParallelSystemDescriptorCoercion,
-        ParamSet, Query, Res, ResMut, SpriteBundle, Text as BevyText, Text2dBundle, TextAlignment,
-        TextStyle, Transform, Vec2, VerticalAlign, Windows,
+        debug, App, AssetServer, Commands, Component, DefaultPlugins, Entity, EventReader,
+        EventWriter, KeyCode, Local, ParallelSystemDescriptorCoercion, ParamSet, Query, Res,
+        ResMut, Sprite as BevySprite, SpriteBundle, Text as BevyText, Text2dBundle, TextAlignment,
+        Transform, Vec2, Visibility, Windows, With,
     },
     render::texture::ImageSettings,
+    text::{Text2dBounds, Text2dSize},
     time::Time,
-    window::close_on_esc,
+    window::{WindowFocused, WindowId},
 };
 use bevy_prototype_lyon::prelude::*;
 use std::{
+    collections::HashSet,
     ops::{Deref, DerefMut},
     path::PathBuf,
     time::Duration,
 };
 
+#[cfg(feature = "embedded_assets")]
+use crate::assets::EmbeddedAssetIo;
+#[cfg(feature = "debug_draw")]
+use crate::debug_draw::DebugDrawPlugin;
 use crate::{
-    audio::AudioManager,
+    assets::{absolute_asset_root, asset_root, set_asset_root, AssetRootError},
+    audio::{AudioEvent, AudioManager},
+    bitmap_text::{BitmapFont, BitmapText, BitmapTextPlugin},
+    camera::{Camera, CameraPlugin},
+    collider_asset::ColliderAssetPlugin,
+    collider_debug::{bounding_box_path, ColliderDebugSettings},
+    debug_draw::DebugDraw,
+    hud::{place, HudPlugin, Space},
     mouse::{CursorMoved, MouseButtonInput, MouseMotion, MousePlugin, MouseWheel},
+    parallax::{ParallaxLayer, ParallaxPlugin},
+    particles::{ParticleAppearance, ParticleEmitter, ParticlePlugin},
+    pool::SpritePool,
     prelude::{
-        AudioManagerPlugin, CollisionEvent, KeyboardInput, KeyboardPlugin, KeyboardState,
-        MouseState, PhysicsPlugin,
+        AudioManagerPlugin, CollisionEvent, CollisionPair, CollisionState, KeyboardInput,
+        KeyboardPlugin, KeyboardState, MouseState, PhysicsPlugin, ReceivedCharacter,
     },
+    resolution::{ResolutionPlugin, ScreenMapping, VirtualResolution},
+    shape::{Shape, ShapeKind},
     sprite::Sprite,
-    text::Text,
+    text::{measure_text, Text, TextEffectPlugin, TextEvent},
+    text_input::{sync_text_input_texts, update_text_inputs, TextInput},
+    tilemap::{Tilemap, TilemapPlugin, Tileset},
     traits::EngineRepo,
+    tween::{
+        update_tweens, Easing, Tween, TweenEvent, TweenId, TweenProperty, TweenTarget, TweenValue,
+    },
 };
 
 // Public re-export
@@ -52,21 +77,78 @@ pub struct Engine {
     /// SYNCED - The state of all sprites this frame. To add a sprite, use the
     /// [`add_sprite`](Engine::add_sprite) method. Modify & remove sprites as you like.
     pub sprites: EngineRepo<Sprite, PathBuf>,
+    /// SYNCED - The sprite pools. To create a pool, use the [`pool`](Engine::pool) method. Use
+    /// [`acquire`](Engine::acquire) and [`release`](Engine::release) to take sprites out of a pool
+    /// and put them back.
+    pub pools: EngineRepo<SpritePool, Sprite>,
     /// SYNCED - The state of all texts this frame. For convenience adding a text, use the
     /// [`add_text`](Engine::add_text) method. Modify & remove text as you like.
     pub texts: EngineRepo<Text, String>,
+    /// SYNCED - The state of all bitmap texts. To add a bitmap text, use the
+    /// [`add_bitmap_text`](Engine::add_bitmap_text) method. Modify & remove bitmap texts as you
+    /// like.
+    pub bitmap_texts: EngineRepo<BitmapText, BitmapFont>,
+    /// SYNCED - The fields the player can type into. To add one, use the
+    /// [`add_text_input`](Engine::add_text_input) method. Modify & remove text inputs as you like.
+    pub text_inputs: EngineRepo<TextInput, String>,
+    // the labels of the texts which show text inputs, so they can be removed with their input
+    pub(crate) text_input_labels: Vec<String>,
+    /// SYNCED - The state of all shapes this frame. To add a shape, use the
+    /// [`add_shape`](Engine::add_shape) method. Modify & remove shapes as you like.
+    pub shapes: EngineRepo<Shape, ShapeKind>,
+    /// SYNCED - The parallax background layers. To add a layer, use the
+    /// [`add_parallax_layer`](Engine::add_parallax_layer) method. Modify & remove layers as you
+    /// like.
+    pub parallax_layers: EngineRepo<ParallaxLayer, PathBuf>,
+    /// SYNCED - The state of all tilemaps. To add a tilemap, use the
+    /// [`add_tilemap`](Engine::add_tilemap) or [`load_tilemap`](Engine::load_tilemap) methods.
+    /// Modify & remove tilemaps as you like.
+    pub tilemaps: EngineRepo<Tilemap, PathBuf>,
+    /// SYNCED - The particle emitters. To add an emitter, use the
+    /// [`add_particle_emitter`](Engine::add_particle_emitter) method. Modify & remove emitters as
+    /// you like. Removing an emitter removes all of its particles.
+    pub particle_emitters: EngineRepo<ParticleEmitter, ParticleAppearance>,
+    /// SYNCED - The camera which determines what part of the game world is shown in the window. Move,
+    /// zoom, or rotate it, or have it follow a sprite. See [`Camera`] for details.
+    pub camera: Camera,
+    /// SYNCED - A fixed resolution to lay the game out at, regardless of the size of the window.
+    /// `None` (the default) means that one logical pixel of the window is one unit of game space.
+    /// See [`VirtualResolution`] for details.
+    pub virtual_resolution: Option<VirtualResolution>,
+    // how the window maps onto game space, updated every frame
+    pub(crate) screen_mapping: ScreenMapping,
     /// SYNCED - If set to `true`, the game exits. Note: the current frame will run to completion first.
     pub should_exit: bool,
     /// SYNCED - If set to `true`, then debug lines are shown depicting sprite colliders
     pub show_colliders: bool,
     // so we can tell if the value changed this frame
     last_show_colliders: bool,
+    /// SYNCED - How colliders are drawn when [`Engine::show_colliders`] is `true`: outline color
+    /// and thickness, fill, coloring by collision state, bounding boxes, etc. Individual sprites
+    /// can override these with [`Sprite::collider_debug`]. See [`ColliderDebugSettings`].
+    pub collider_debug: ColliderDebugSettings,
+    // the collisions which have begun but not yet ended, for coloring colliders
+    current_collisions: HashSet<CollisionPair>,
+    /// SYNCED - Draw lines, circles, rectangles, arrows, and text on top of the game world for the
+    /// current frame only. Useful for visualizing things like velocities and targets. See
+    /// [`DebugDraw`] for details.
+    pub debug_draw: DebugDraw,
     /// INFO - All the collision events that occurred this frame. For collisions to be generated
     /// between sprites, both sprites must have [`Sprite.collision`] set to `true` and both sprites
     /// must have colliders (use the collider example to create a collider for your own images).
     /// Collision events are generated when two sprites' colliders begin or end overlapping in 2D
     /// space.
     pub collision_events: Vec<CollisionEvent>,
+    /// SYNCED - The tweens which are currently playing. Start a tween with the
+    /// [`tween`](Engine::tween) method. Remove a tween to stop it where it is.
+    pub tweens: Vec<Tween>,
+    // the id of the most recently started tween
+    last_tween_id: u64,
+    /// INFO - All the tweens that finished this frame. See [`Tween`].
+    pub tween_events: Vec<TweenEvent>,
+    /// INFO - All the text events that occurred this frame, such as a typewriter reveal finishing.
+    /// See [`TextEvent`].
+    pub text_events: Vec<TextEvent>,
     /// INFO - The current state of mouse location and buttons. Useful for input handling that only
     /// cares about the final state of the mouse each frame, and not the intermediate states.
     pub mouse_state: MouseState,
@@ -92,6 +174,10 @@ pub struct Engine {
     /// at the same rate that additional letters would show up in a word processor. When the key is
     /// finally released, a single released event is emitted.
     pub keyboard_events: Vec<KeyboardInput>,
+    /// INFO - All the characters that were typed this frame, with the keyboard layout, Shift, etc.
+    /// taken into account. Use these instead of [`Engine::keyboard_events`] when you want to know
+    /// _what_ the player typed, like when they enter their name. See also [`TextInput`].
+    pub received_character_events: Vec<ReceivedCharacter>,
     /// INFO - The delta time (time between frames) for the current frame as a [`Duration`], perfect
     /// for use with [`Timer`](crate::prelude::Timer)s
     pub delta: Duration,
@@ -107,9 +193,13 @@ pub struct Engine {
     pub time_since_startup_f64: f64,
     /// A struct with methods to play sound effects and music
     pub audio_manager: AudioManager,
+    /// INFO - All the audio events that occurred since the last frame, such as music or a sound
+    /// effect finishing. See [`AudioEvent`].
+    pub audio_events: Vec<AudioEvent>,
     /// INFO - Window dimensions in logical pixels. On high DPI screens, there will often be four
     /// physical pixels per logical pixel. On low DPI screens, one logical pixel is one physical
-    /// pixel.
+    /// pixel. If [`Engine::virtual_resolution`] is set, these are instead the dimensions of the
+    /// part of the window that the game is shown in (not counting any borders), in virtual pixels.
     pub window_dimensions: Vec2,
 }
 
@@ -131,6 +221,65 @@ impl Engine {
         self.sprites.get_mut(&label).unwrap()
     }
 
+    #[inline]
+    /// Create a [`SpritePool`] named `name` with `capacity` hidden sprites made from
+    /// `file_or_preset`, and add them all to the game up front. Use the `&mut SpritePool` that is
+    /// returned to adjust the `template` that the sprites are reset to when they are acquired.
+    /// Creating a pool with the same name as an existing pool replaces it, along with its sprites.
+    pub fn pool<T: Into<String>, P: Into<PathBuf>>(
+        &mut self,
+        name: T,
+        file_or_preset: P,
+        capacity: usize,
+    ) -> &mut SpritePool {
+        let name = name.into();
+        if let Some(old_pool) = self.pools.remove(&name) {
+            self.sprites.retain(|label, _| !old_pool.contains(label));
+        }
+        let template = Sprite::new(name.clone(), file_or_preset);
+        let (pool, sprites) = SpritePool::new(name.clone(), template, capacity);
+        for sprite in sprites {
+            self.sprites.insert(sprite.label.clone(), sprite);
+        }
+        self.pools.insert(name.clone(), pool);
+        // Unwrap: Can't crash because we just inserted the pool
+        self.pools.get_mut(&name).unwrap()
+    }
+
+    /// Take a sprite out of the pool named `pool_name`. The sprite is reset to the pool's
+    /// `template` and made visible. Returns `None` if the pool doesn't exist or all of its sprites
+    /// are in use.
+    pub fn acquire(&mut self, pool_name: &str) -> Option<&mut Sprite> {
+        let pool = self.pools.get_mut(pool_name)?;
+        // Skip over any sprites that the user removed from the game
+        let label = loop {
+            let label = pool.take()?;
+            if self.sprites.contains_key(&label) {
+                break label;
+            }
+        };
+        // Unwrap: Can't crash because we just checked that the sprite exists
+        let sprite = self.sprites.get_mut(&label).unwrap();
+        pool.reset(sprite);
+        Some(sprite)
+    }
+
+    /// Put the sprite with this label back into its pool, hiding it. Returns `false` if the sprite
+    /// doesn't belong to a pool or is already in its pool.
+    pub fn release(&mut self, label: &str) -> bool {
+        let pool = match self.pools.values_mut().find(|pool| pool.contains(label)) {
+            Some(pool) => pool,
+            None => return false,
+        };
+        match self.sprites.get_mut(label) {
+            Some(sprite) => {
+                sprite.visible = false;
+                pool.put_back(label)
+            }
+            None => false,
+        }
+    }
+
     #[must_use]
     #[inline]
     /// Create and add a [`Text`] to the game. Use the `&mut Text` that is returned to adjust the
@@ -148,6 +297,174 @@ impl Engine {
         self.texts.get_mut(&label).unwrap()
     }
 
+    #[must_use]
+    #[inline]
+    /// Create and add a [`BitmapText`] to the game, drawn with a [`BitmapFont`]. `font` may be the
+    /// path of a BMFont `.fnt` file relative to the `assets/` directory, or a
+    /// [`BitmapFont::Grid`]. Use the `&mut BitmapText` that is returned to adjust the translation,
+    /// scale, etc. Adding a bitmap text with the same label as an existing one replaces it.
+    pub fn add_bitmap_text<T, F, S>(&mut self, label: T, font: F, value: S) -> &mut BitmapText
+    where
+        T: Into<String>,
+        F: Into<BitmapFont>,
+        S: Into<String>,
+    {
+        let label = label.into();
+        self.bitmap_texts
+            .insert(label.clone(), BitmapText::new(label.clone(), font, value));
+        // Unwrap: Can't crash because we just inserted the bitmap text
+        self.bitmap_texts.get_mut(&label).unwrap()
+    }
+
+    #[must_use]
+    #[inline]
+    /// Create and add a [`TextInput`] to the game, which the player can type into. Use the `&mut
+    /// TextInput` that is returned to set the max length, adjust the look of its `text`, etc. The
+    /// input is displayed with a [`Text`] with the same label, so use a label that no text has.
+    pub fn add_text_input<T: Into<String>>(&mut self, label: T) -> &mut TextInput {
+        let label = label.into();
+        self.text_inputs
+            .insert(label.clone(), TextInput::new(label.clone()));
+        // Unwrap: Can't crash because we just inserted the text input
+        self.text_inputs.get_mut(&label).unwrap()
+    }
+
+    /// The size in pixels that `value` would be if it were displayed in a [`Text`] with this
+    /// `font` and `font_size`, without any wrapping. Use this to lay things out before adding the
+    /// text (for example, to size a panel to go behind it). Once a text has been displayed, its
+    /// `size` field has the size Bevy actually laid it out at. Returns `Vec2::ZERO` if the font
+    /// can't be loaded.
+    pub fn measure_text<S: AsRef<str>, F: AsRef<str>>(
+        &self,
+        value: S,
+        font: F,
+        font_size: f32,
+    ) -> Vec2 {
+        measure_text(value.as_ref(), font.as_ref(), font_size)
+    }
+
+    #[must_use]
+    #[inline]
+    /// Create and add a [`Shape`] to the game. Use the `&mut Shape` that is returned to adjust the
+    /// colors, translation, rotation, etc. A collider matching the shape is derived automatically,
+    /// so you only need to set `collision` to `true` to get collision events. Use a *unique* label
+    /// for each shape.
+    pub fn add_shape<T: Into<String>>(&mut self, label: T, kind: ShapeKind) -> &mut Shape {
+        let label = label.into();
+        self.shapes
+            .insert(label.clone(), Shape::new(label.clone(), kind));
+        // Unwrap: Can't crash because we just inserted the shape
+        self.shapes.get_mut(&label).unwrap()
+    }
+
+    #[must_use]
+    #[inline]
+    /// Create and add a [`ParallaxLayer`] to the game. Use the `&mut ParallaxLayer` that is
+    /// returned to adjust the scroll factor, tiling, layer, etc. `filepath` is the path of an image
+    /// file relative to the `assets/` directory. Adding a layer with the same label as an existing
+    /// layer replaces it.
+    pub fn add_parallax_layer<T: Into<String>, P: Into<PathBuf>>(
+        &mut self,
+        label: T,
+        filepath: P,
+    ) -> &mut ParallaxLayer {
+        let label = label.into();
+        self.parallax_layers
+            .insert(label.clone(), ParallaxLayer::new(label.clone(), filepath));
+        // Unwrap: Can't crash because we just inserted the layer
+        self.parallax_layers.get_mut(&label).unwrap()
+    }
+
+    #[must_use]
+    #[inline]
+    /// Create and add a [`ParticleEmitter`] to the game. Use the `&mut ParticleEmitter` that is
+    /// returned to adjust the rate, lifetime, colors, etc. `appearance` may be a [`ShapeKind`], a
+    /// [`SpritePreset`](crate::prelude::SpritePreset), or the path of an image file relative to
+    /// the `assets/` directory. Adding an emitter with the same label as an existing emitter
+    /// replaces it.
+    pub fn add_particle_emitter<T: Into<String>, A: Into<ParticleAppearance>>(
+        &mut self,
+        label: T,
+        appearance: A,
+    ) -> &mut ParticleEmitter {
+        let label = label.into();
+        self.particle_emitters.insert(
+            label.clone(),
+            ParticleEmitter::new(label.clone(), appearance),
+        );
+        // Unwrap: Can't crash because we just inserted the emitter
+        self.particle_emitters.get_mut(&label).unwrap()
+    }
+
+    #[inline]
+    /// Create and add an empty [`Tilemap`] to the game which is `width` x `height` tiles large. Use
+    /// the `&mut Tilemap` that is returned to set tiles, adjust the translation, etc. Adding a
+    /// tilemap with the same label as an existing tilemap replaces it. If the tilemap has too many
+    /// tiles, an error is printed and `None` is returned.
+    pub fn add_tilemap<T: Into<String>>(
+        &mut self,
+        label: T,
+        tileset: Tileset,
+        width: u32,
+        height: u32,
+    ) -> Option<&mut Tilemap> {
+        let label = label.into();
+        let tilemap = Tilemap::new(label.clone(), tileset, width, height)?;
+        self.tilemaps.insert(label.clone(), tilemap);
+        self.tilemaps.get_mut(&label)
+    }
+
+    #[inline]
+    /// Load a [`Tilemap`] from a file relative to the `assets/` directory and add it to the game.
+    /// See [`Tilemap::load`] for the supported file formats. If loading fails, an error is printed
+    /// and `None` is returned.
+    pub fn load_tilemap<T: Into<String>, P: Into<PathBuf>>(
+        &mut self,
+        label: T,
+        filepath: P,
+    ) -> Option<&mut Tilemap> {
+        let label = label.into();
+        let tilemap = Tilemap::load(label.clone(), filepath)?;
+        self.tilemaps.insert(label.clone(), tilemap);
+        self.tilemaps.get_mut(&label)
+    }
+
+    /// Start a [`Tween`] which smoothly changes `property` of `target` to `to` over `duration`
+    /// seconds, following the `easing` curve. `target` may be the label of a sprite or text, or
+    /// [`TweenTarget::Camera`]. Use the `&mut Tween` that is returned to add a delay, repeat, or
+    /// more steps. When the tween finishes, a [`TweenEvent`] is added to
+    /// [`Engine::tween_events`].
+    pub fn tween<T: Into<TweenTarget>, V: Into<TweenValue>>(
+        &mut self,
+        target: T,
+        property: TweenProperty,
+        to: V,
+        duration: f32,
+        easing: Easing,
+    ) -> &mut Tween {
+        self.last_tween_id += 1;
+        let id = TweenId(self.last_tween_id);
+        self.tweens
+            .push(Tween::new(id, target, property, to, duration, easing));
+        // Unwrap: Can't crash because we just pushed the tween
+        self.tweens.last_mut().unwrap()
+    }
+
+    /// Stop all of the tweens that are changing `target`, leaving it wherever it is right now.
+    pub fn stop_tweens<T: Into<TweenTarget>>(&mut self, target: T) {
+        let target = target.into();
+        self.tweens.retain(|tween| tween.target != target);
+    }
+
+    /// The labels of everything which is currently colliding with something
+    fn colliding_labels(&self) -> HashSet<String> {
+        self.current_collisions
+            .iter()
+            .cloned()
+            .flat_map(CollisionPair::into_iter)
+            .collect()
+    }
+
     #[inline]
     /// Create and add a [`Sprite`] to the game. Use the `&mut Sprite` that is returned to adjust
     /// the translation, rotation, etc. Use a *unique* label for each sprite. Attempting to add two
@@ -178,29 +495,37 @@ impl Engine {
 pub fn setup(mut commands: Commands, asset_server: Res<AssetServer>, mut engine: ResMut<Engine>) {
     add_sprites(&mut commands, &asset_server, &mut engine);
     add_texts(&mut commands, &asset_server, &mut engine);
+    add_shapes(&mut commands, &mut engine);
 }
 
 /// Add visible lines representing a collider
-fn add_collider_lines(commands: &mut Commands, sprite: &mut Sprite) {
+fn add_collider_lines(
+    commands: &mut Commands,
+    sprite: &mut Sprite,
+    settings: &ColliderDebugSettings,
+    colliding: bool,
+    window_dimensions: Vec2,
+) {
     // Add the collider lines, a visual representation of the sprite's collider
-    let points = sprite.collider.points(); // will be empty vector if NoCollider
-    if points.len() >= 2 {
-        let mut path_builder = PathBuilder::new();
-        path_builder.move_to(points[0]);
-        for point in &points[1..] {
-            path_builder.line_to(*point);
-        }
-        path_builder.close(); // draws the line from the last point to the first point
-        let line = path_builder.build();
-        let transform = sprite.bevy_transform();
+    let settings = sprite.collider_debug.as_ref().unwrap_or(settings);
+    if let Some(line) = settings.path(&sprite.collider) {
+        let transform = place(
+            sprite.bevy_transform(),
+            sprite.space,
+            sprite.anchor,
+            window_dimensions,
+        );
         commands
             .spawn_bundle(GeometryBuilder::build_as(
                 &line,
-                DrawMode::Stroke(StrokeMode::new(Color::WHITE, 1.0 / transform.scale.x)),
+                settings.draw_mode(colliding, transform.scale.x),
                 transform,
             ))
+            .insert(sprite.space.render_layers())
             .insert(ColliderLines {
                 sprite_label: sprite.label.clone(),
+                space: sprite.space,
+                settings: settings.clone(),
             });
     }
     sprite.collider_dirty = false;
@@ -209,15 +534,33 @@ fn add_collider_lines(commands: &mut Commands, sprite: &mut Sprite) {
 /// helper function: Add Bevy components for all the sprites in engine.sprites
 #[doc(hidden)]
 pub fn add_sprites(commands: &mut Commands, asset_server: &Res<AssetServer>, engine: &mut Engine) {
+    let window_dimensions = engine.window_dimensions;
     for sprite in engine.sprites.drain_all() {
         // Create the sprite
-        let transform = sprite.bevy_transform();
+        let transform = place(
+            sprite.bevy_transform(),
+            sprite.space,
+            sprite.anchor,
+            window_dimensions,
+        );
         let texture_path = sprite.filepath.clone();
-        commands.spawn().insert(sprite).insert_bundle(SpriteBundle {
-            texture: asset_server.load(texture_path),
-            transform,
-            ..Default::default()
-        });
+        let render_layers = sprite.space.render_layers();
+        let color = sprite.color;
+        let is_visible = sprite.visible;
+        commands
+            .spawn()
+            .insert(sprite)
+            .insert_bundle(SpriteBundle {
+                texture: asset_server.load(texture_path),
+                sprite: BevySprite {
+                    color,
+                    ..Default::default()
+                },
+                visibility: Visibility { is_visible },
+                transform,
+                ..Default::default()
+            })
+            .insert(render_layers);
     }
 }
 
@@ -225,27 +568,56 @@ pub fn add_sprites(commands: &mut Commands, asset_server: &Res<AssetServer>, eng
 /// `engine.texts`
 #[doc(hidden)]
 pub fn add_texts(commands: &mut Commands, asset_server: &Res<AssetServer>, engine: &mut Engine) {
+    let window_dimensions = engine.window_dimensions;
     for text in engine.texts.drain_all() {
-        let transform = text.bevy_transform();
-        let font_size = text.font_size;
-        let text_string = text.value.clone();
-        let font_path = text.font.clone();
-        commands.spawn().insert(text).insert_bundle(Text2dBundle {
-            text: BevyText::from_section(
-                text_string,
-                TextStyle {
-                    font: asset_server.load(font_path.as_str()),
-                    font_size,
-                    color: Color::WHITE,
-                },
-            )
-            .with_alignment(TextAlignment {
-                vertical: VerticalAlign::Center,
-                horizontal: HorizontalAlign::Center,
-            }),
-            transform,
-            ..Default::default()
-        });
+        let transform = place(
+            text.bevy_transform(),
+            text.space,
+            text.anchor,
+            window_dimensions,
+        );
+        let text_2d_bounds = text.bevy_text_2d_bounds();
+        let bevy_text = BevyText {
+            sections: text.bevy_sections(asset_server),
+            alignment: TextAlignment {
+                vertical: text.vertical_align,
+                horizontal: text.horizontal_align,
+            },
+        };
+        let render_layers = text.space.render_layers();
+        commands
+            .spawn()
+            .insert(text)
+            .insert_bundle(Text2dBundle {
+                text: bevy_text,
+                text_2d_bounds,
+                transform,
+                ..Default::default()
+            })
+            .insert(render_layers);
+    }
+}
+
+/// helper function: Add Bevy components for all the shapes in engine.shapes
+#[doc(hidden)]
+pub fn add_shapes(commands: &mut Commands, engine: &mut Engine) {
+    let window_dimensions = engine.window_dimensions;
+    for shape in engine.shapes.drain_all() {
+        let transform = place(
+            shape.bevy_transform(),
+            shape.space,
+            shape.anchor,
+            window_dimensions,
+        );
+        let render_layers = shape.space.render_layers();
+        commands
+            .spawn_bundle(GeometryBuilder::build_as(
+                &shape.path(),
+                shape.draw_mode(),
+                transform,
+            ))
+            .insert(render_layers)
+            .insert(shape);
     }
 }
 
@@ -255,20 +627,59 @@ pub fn update_window_dimensions(windows: Res<Windows>, mut engine: ResMut<Engine
     // It's possible to not have window dimensions for the first frame or two
     if let Some(window) = windows.get_primary() {
         let screen_dimensions = Vec2::new(window.width(), window.height());
-        if screen_dimensions != engine.window_dimensions {
-            engine.window_dimensions = screen_dimensions;
+        engine.screen_mapping = ScreenMapping::new(
+            engine.virtual_resolution.as_ref(),
+            screen_dimensions,
+            window.scale_factor() as f32,
+        );
+        let game_dimensions = engine.screen_mapping.game_dimensions;
+        if game_dimensions != engine.window_dimensions {
+            engine.window_dimensions = game_dimensions;
             debug!("Set window dimensions: {}", engine.window_dimensions);
         }
     }
 }
 
+/// system - close the focused window when Escape is pressed, like Bevy's `close_on_esc`, except
+/// while the player is typing into a text input, where Escape cancels the input instead
+#[doc(hidden)]
+pub fn close_on_esc(
+    mut focused: Local<Option<WindowId>>,
+    mut focused_events: EventReader<WindowFocused>,
+    mut windows: ResMut<Windows>,
+    input: Res<Input<KeyCode>>,
+    engine: Res<Engine>,
+) {
+    for event in focused_events.iter() {
+        *focused = event.focused.then_some(event.id);
+    }
+    let typing = engine
+        .text_inputs
+        .values()
+        .any(|text_input| text_input.focused);
+    if let Some(focused) = &*focused {
+        if input.just_pressed(KeyCode::Escape) && !typing {
+            if let Some(window) = windows.get_mut(*focused) {
+                window.close();
+            }
+        }
+    }
+}
+
 /// Component to add to the collider lines visualizations to link them to the sprite they represent
 #[derive(Component)]
 #[doc(hidden)]
 pub struct ColliderLines {
     sprite_label: String,
+    space: Space,
+    settings: ColliderDebugSettings,
 }
 
+/// Component to mark the bounding boxes drawn around colliders, which are redrawn every frame
+#[derive(Component)]
+#[doc(hidden)]
+pub struct ColliderBoundingBox;
+
 /// A [`Game`] represents the entire game and its data.
 /// By default the game will spawn an empty window, and exit upon Esc or closing of the window.
 /// Under the hood, Rusty Engine syncs the game data to [Bevy](https://bevyengine.org/) to power
@@ -298,13 +709,34 @@ impl<S: Send + Sync + 'static> Default for Game<S> {
 }
 
 impl<S: Send + Sync + 'static> Game<S> {
-    /// Create an new, empty [`Game`] with an empty [`Engine`]
+    /// Create an new, empty [`Game`] with an empty [`Engine`], which reads its assets from the
+    /// `assets/` directory. If that directory can't be found, an error is printed (and images,
+    /// sounds, and fonts will fail to load). Use [`Game::try_new`] to handle the error yourself.
     pub fn new() -> Self {
-        if std::fs::read_dir("assets").is_err() {
-            println!("FATAL: Could not find assets directory. Have you downloaded the assets?\nhttps://github.com/CleanCut/rusty_engine#you-must-download-the-assets-separately");
-            std::process::exit(1);
+        Self::try_new().unwrap_or_else(|e| {
+            eprintln!("error: {}", e);
+            Default::default()
+        })
+    }
+
+    /// Like [`Game::new`], but returns an [`AssetRootError`] if the `assets/` directory can't be
+    /// found.
+    pub fn try_new() -> Result<Self, AssetRootError> {
+        Self::with_asset_root("assets")
+    }
+
+    /// Create a new, empty [`Game`] which reads its assets from `asset_root` instead of the
+    /// `assets/` directory. A relative path is relative to the current working directory. Returns
+    /// an [`AssetRootError`] if the directory can't be found, unless the `embedded_assets` feature
+    /// is enabled (in which case the embedded assets are used).
+    pub fn with_asset_root<P: Into<PathBuf>>(asset_root: P) -> Result<Self, AssetRootError> {
+        let asset_root = absolute_asset_root(asset_root.into());
+        // Leave the current asset root alone if the new one is no good
+        if !asset_root.is_dir() && !cfg!(feature = "embedded_assets") {
+            return Err(AssetRootError { asset_root });
         }
-        Default::default()
+        set_asset_root(asset_root);
+        Ok(Default::default())
     }
 
     /// Use this to set properties of the native OS window before running the game. See the
@@ -321,17 +753,42 @@ impl<S: Send + Sync + 'static> Game<S> {
             .insert_resource::<WindowDescriptor>(self.window_descriptor.clone())
             .insert_resource(ImageSettings::default_nearest())
             .insert_resource::<S>(initial_game_state);
+        let asset_root = asset_root();
+        self.app.insert_resource(AssetServerSettings {
+            asset_folder: asset_root.to_string_lossy().into_owned(),
+            watch_for_changes: cfg!(feature = "hot_reload"),
+        });
+        // Bevy only creates its own asset server if there isn't one already
+        #[cfg(feature = "embedded_assets")]
+        self.app
+            .insert_resource(AssetServer::new(EmbeddedAssetIo::new(
+                &asset_root,
+                cfg!(feature = "hot_reload"),
+            )));
         self.app
             // Built-ins
             .add_plugins(DefaultPlugins)
-            .add_system(close_on_esc)
+            // Before the game logic, which might unfocus a text input that Escape just cancelled
+            .add_system(close_on_esc.before("game_logic_sync"))
             // External Plugins
-            .add_plugin(ShapePlugin) // bevy_prototype_lyon, for displaying sprite colliders
+            .add_plugin(ShapePlugin) // bevy_prototype_lyon, for shapes, debug drawing and colliders
             // Rusty Engine Plugins
             .add_plugin(AudioManagerPlugin)
+            .add_plugin(BitmapTextPlugin)
+            .add_plugin(CameraPlugin)
+            .add_plugin(ColliderAssetPlugin)
+            .add_plugin(HudPlugin)
             .add_plugin(KeyboardPlugin)
             .add_plugin(MousePlugin)
+            .add_plugin(ParallaxPlugin)
+            .add_plugin(ParticlePlugin)
             .add_plugin(PhysicsPlugin)
+            .add_plugin(ResolutionPlugin)
+            .add_plugin(TextEffectPlugin)
+            .add_plugin(TilemapPlugin);
+        #[cfg(feature = "debug_draw")]
+        self.app.add_plugin(DebugDrawPlugin);
+        self.app
             //.insert_resource(ReportExecutionOrderAmbiguities) // for debugging
             .add_system(
                 update_window_dimensions
@@ -340,10 +797,6 @@ impl<S: Send + Sync + 'static> Game<S> {
             )
             .add_system(game_logic_sync::<S>.label("game_logic_sync"))
             .add_startup_system(setup);
-        self.app
-            .world
-            .spawn()
-            .insert_bundle(Camera2dBundle::default());
         let engine = std::mem::take(&mut self.engine);
         self.app.insert_resource(engine);
         let logic_functions = std::mem::take(&mut self.logic_functions);
@@ -375,10 +828,25 @@ fn game_logic_sync<S: Send + Sync + 'static>(
     mut app_exit_events: EventWriter<AppExit>,
     mut collision_events: EventReader<CollisionEvent>,
     mut query_set: ParamSet<(
-        Query<(Entity, &mut Sprite, &mut Transform)>,
-        Query<(Entity, &mut Text, &mut Transform, &mut BevyText)>,
-        Query<(Entity, &mut DrawMode, &mut Transform, &ColliderLines)>,
+        Query<(
+            Entity,
+            &mut Sprite,
+            &mut Transform,
+            &mut BevySprite,
+            &mut Visibility,
+        )>,
+        Query<(
+            Entity,
+            &mut Text,
+            &mut Transform,
+            &mut BevyText,
+            &mut Text2dBounds,
+            &Text2dSize,
+        )>,
+        Query<(Entity, &mut DrawMode, &mut Transform, &mut ColliderLines)>,
+        Query<(Entity, &mut Shape, &mut Transform, &mut Path, &mut DrawMode)>,
     )>,
+    bounding_box_query: Query<Entity, With<ColliderBoundingBox>>,
 ) {
     // Update this frame's timing info
     engine.delta = time.delta();
@@ -396,41 +864,130 @@ fn game_logic_sync<S: Send + Sync + 'static>(
     engine.collision_events.clear();
     for collision_event in collision_events.iter() {
         engine.collision_events.push(collision_event.clone());
+        match collision_event.state {
+            CollisionState::Begin => {
+                engine
+                    .current_collisions
+                    .insert(collision_event.pair.clone());
+            }
+            CollisionState::End => {
+                engine.current_collisions.remove(&collision_event.pair);
+            }
+        }
     }
 
+    // Copy all audio events over to the engine to give to users
+    engine.audio_events = engine.audio_manager.take_events();
+
     // Copy all sprites over to the engine to give to users
     engine.sprites.clear();
-    query_set.p0().iter().for_each(|(_, sprite, _)| {
+    query_set.p0().iter().for_each(|(_, sprite, _, _, _)| {
         engine.add_sprite_clod(sprite.clone());
     });
 
-    // Copy all texts over to the engine to give to users
+    // Copy all texts over to the engine to give to users, along with the size Bevy laid them out at
     engine.texts.clear();
-    query_set.p1().iter().for_each(|(_, text, _, _)| {
-        engine.add_text_clod(text.clone());
-    });
+    query_set
+        .p1()
+        .iter()
+        .for_each(|(_, text, transform, _, _, text_2d_size)| {
+            let mut text = text.clone();
+            text.update_layout(text_2d_size.size, transform);
+            engine.add_text_clod(text);
+        });
+
+    // Copy all shapes over to the engine to give to users
+    engine.shapes.clear();
+    for (_, shape, _, _, _) in query_set.p3().iter() {
+        engine.shapes.insert(shape.label.clone(), shape.clone());
+    }
+
+    // Reveal more characters of any texts that are being typed out
+    engine.text_events.clear();
+    let delta_f32 = engine.delta_f32;
+    let mut finished_labels = Vec::new();
+    for text in engine.texts.values_mut() {
+        let character_count = text.character_count();
+        if let Some(reveal) = text.reveal.as_mut() {
+            if reveal.advance(delta_f32, character_count) {
+                finished_labels.push(text.label.clone());
+            }
+        }
+    }
+    for bitmap_text in engine.bitmap_texts.values_mut() {
+        let character_count = bitmap_text.character_count();
+        if let Some(reveal) = bitmap_text.reveal.as_mut() {
+            if reveal.advance(delta_f32, character_count) {
+                finished_labels.push(bitmap_text.label.clone());
+            }
+        }
+    }
+    engine.text_events.extend(
+        finished_labels
+            .into_iter()
+            .map(|label| TextEvent::RevealFinished { label }),
+    );
+
+    // Apply the keys the player typed to the focused text inputs
+    update_text_inputs(&mut engine);
+
+    // Advance all the tweens, so that the user sees (and can override) the tweened values
+    engine.tween_events.clear();
+    update_tweens(&mut engine, delta_f32);
 
     // Perform all the user's game logic for this frame
     for func in logic_functions.iter() {
         func(&mut engine, &mut game_state);
     }
 
+    // Show the text inputs as they are after the user's logic
+    sync_text_input_texts(&mut engine);
+
+    let window_dimensions = engine.window_dimensions;
+    let collider_debug = engine.collider_debug.clone();
+    let colliding_labels = engine.colliding_labels();
+    let colliding = |sprite: &Sprite| sprite.collision && colliding_labels.contains(&sprite.label);
     if !engine.last_show_colliders && engine.show_colliders {
-        // Just turned on show_colliders -- create collider lines for all sprites
+        // Just turned on show_colliders -- create collider lines for all visible sprites
         engine
             .sprites
-            .for_each_mut(|sprite| add_collider_lines(&mut commands, sprite))
+            .filter_mut(|sprite| sprite.visible)
+            .for_each(|sprite| {
+                let colliding = colliding(sprite);
+                add_collider_lines(
+                    &mut commands,
+                    sprite,
+                    &collider_debug,
+                    colliding,
+                    window_dimensions,
+                )
+            })
     } else if engine.last_show_colliders && !engine.show_colliders {
         // Just turned off show_colliders -- delete collider lines for all sprites
         for (entity, _, _, _) in query_set.p2().iter_mut() {
             commands.entity(entity).despawn();
         }
     }
+    // Bounding boxes depend on the rotation of the sprite, so we redraw them every frame
+    for entity in bounding_box_query.iter() {
+        commands.entity(entity).despawn();
+    }
     // Update transform & line width of all collider lines
     if engine.show_colliders {
-        // Delete collider lines for sprites which are missing, or whose colliders are dirty
+        // Hidden sprites don't show their colliders. Marking them dirty removes their collider
+        // lines, and adds them back once the sprite is visible again.
+        engine
+            .sprites
+            .filter_mut(|sprite| !sprite.visible)
+            .for_each(|sprite| sprite.collider_dirty = true);
+        // Delete collider lines for sprites which are missing, or whose colliders are dirty, or
+        // whose settings changed shape
         for (entity, _, _, collider_lines) in query_set.p2().iter_mut() {
-            if let Some(sprite) = engine.sprites.get(&collider_lines.sprite_label) {
+            if let Some(sprite) = engine.sprites.get_mut(&collider_lines.sprite_label) {
+                let settings = sprite.collider_debug.as_ref().unwrap_or(&collider_debug);
+                if !settings.same_geometry(&collider_lines.settings) {
+                    sprite.collider_dirty = true;
+                }
                 if sprite.collider_dirty {
                     commands.entity(entity).despawn();
                 }
@@ -438,36 +995,104 @@ fn game_logic_sync<S: Send + Sync + 'static>(
                 commands.entity(entity).despawn();
             }
         }
-        // Add collider lines for sprites whose colliders are dirty
+        // Add collider lines for visible sprites whose colliders are dirty
 
         engine
             .sprites
-            .filter_mut(|sprite| sprite.collider_dirty)
-            .for_each(|sprite| add_collider_lines(&mut commands, sprite));
+            .filter_mut(|sprite| sprite.collider_dirty && sprite.visible)
+            .for_each(|sprite| {
+                let colliding = colliding(sprite);
+                add_collider_lines(
+                    &mut commands,
+                    sprite,
+                    &collider_debug,
+                    colliding,
+                    window_dimensions,
+                )
+            });
 
-        // Update transform & line width
-        for (_, mut draw_mode, mut transform, collider_lines) in query_set.p2().iter_mut() {
+        // Update transform, space, colors & line width
+        for (entity, mut draw_mode, mut transform, mut collider_lines) in query_set.p2().iter_mut()
+        {
             if let Some(sprite) = engine.sprites.get(&collider_lines.sprite_label) {
-                *transform = sprite.bevy_transform();
+                let settings = sprite.collider_debug.as_ref().unwrap_or(&collider_debug);
+                *transform = place(
+                    sprite.bevy_transform(),
+                    sprite.space,
+                    sprite.anchor,
+                    window_dimensions,
+                );
+                if collider_lines.space != sprite.space {
+                    collider_lines.space = sprite.space;
+                    commands.entity(entity).insert(sprite.space.render_layers());
+                }
                 // We want collider lines to appear on top of the sprite they are for, so they need a
                 // slightly higher z value. We tell users to only use up to 999.0.
-                transform.translation.z = (transform.translation.z + 0.1).clamp(0.0, 999.1);
+                transform.translation.z =
+                    (transform.translation.z + settings.z_offset).clamp(0.0, 999.9);
+                *draw_mode = settings.draw_mode(colliding(sprite), transform.scale.x);
+                collider_lines.settings = settings.clone();
+            }
+        }
+
+        // Draw bounding boxes around colliders, in 2D game space
+        for sprite in engine.sprites.values() {
+            let settings = sprite.collider_debug.as_ref().unwrap_or(&collider_debug);
+            if !sprite.visible || !settings.show_bounding_box {
+                continue;
             }
-            // Stroke line width gets scaled with the transform, but we want it to appear to be the same
-            // regardless of scale, so we have to counter the scale.
-            if let DrawMode::Stroke(ref mut stroke_mode) = *draw_mode {
-                let line_width = 1.0 / transform.scale.x;
-                *stroke_mode = StrokeMode::new(Color::WHITE, line_width);
+            let transform = place(
+                sprite.bevy_transform(),
+                sprite.space,
+                sprite.anchor,
+                window_dimensions,
+            );
+            let points = sprite.collider.transformed(
+                transform.translation.truncate(),
+                sprite.rotation,
+                sprite.scale,
+            );
+            if let Some(bounding_box) = bounding_box_path(&points) {
+                let z = (transform.translation.z + settings.z_offset).clamp(0.0, 999.9);
+                commands
+                    .spawn_bundle(GeometryBuilder::build_as(
+                        &bounding_box,
+                        DrawMode::Stroke(StrokeMode::new(
+                            settings.outline_color(colliding(sprite)),
+                            settings.thickness,
+                        )),
+                        Transform::from_xyz(0.0, 0.0, z),
+                    ))
+                    .insert(sprite.space.render_layers())
+                    .insert(ColliderBoundingBox);
             }
         }
     }
     engine.last_show_colliders = engine.show_colliders;
 
     // Transfer any changes in the user's Sprite copies to the Bevy Sprite and Transform components
-    for (entity, mut sprite, mut transform) in query_set.p0().iter_mut() {
+    for (entity, mut sprite, mut transform, mut bevy_sprite, mut visibility) in
+        query_set.p0().iter_mut()
+    {
         if let Some(sprite_copy) = engine.sprites.remove(&sprite.label) {
+            if sprite_copy.space != sprite.space {
+                commands
+                    .entity(entity)
+                    .insert(sprite_copy.space.render_layers());
+            }
             *sprite = sprite_copy;
-            *transform = sprite.bevy_transform();
+            *transform = place(
+                sprite.bevy_transform(),
+                sprite.space,
+                sprite.anchor,
+                window_dimensions,
+            );
+            if sprite.color != bevy_sprite.color {
+                bevy_sprite.color = sprite.color;
+            }
+            if sprite.visible != visibility.is_visible {
+                visibility.is_visible = sprite.visible;
+            }
         } else {
             commands.entity(entity).despawn();
         }
@@ -477,20 +1102,52 @@ fn game_logic_sync<S: Send + Sync + 'static>(
     add_sprites(&mut commands, &asset_server, &mut engine);
 
     // Transfer any changes in the user's Texts to the Bevy Text and Transform components
-    for (entity, mut text, mut transform, mut bevy_text_component) in query_set.p1().iter_mut() {
+    for (entity, mut text, mut transform, mut bevy_text_component, mut text_2d_bounds, _) in
+        query_set.p1().iter_mut()
+    {
         if let Some(text_copy) = engine.texts.remove(&text.label) {
+            if text_copy.space != text.space {
+                commands
+                    .entity(entity)
+                    .insert(text_copy.space.render_layers());
+            }
             *text = text_copy;
-            *transform = text.bevy_transform();
-            if text.value != bevy_text_component.sections[0].value {
-                bevy_text_component.sections[0].value = text.value.clone();
+            *transform = place(
+                text.bevy_transform(),
+                text.space,
+                text.anchor,
+                window_dimensions,
+            );
+            // Only touch the Bevy text when something changed, since any change makes Bevy lay
+            // out all of its glyphs again
+            let sections = text.bevy_sections(&asset_server);
+            if sections.len() != bevy_text_component.sections.len() {
+                bevy_text_component.sections = sections;
+            } else {
+                for (index, section) in sections.into_iter().enumerate() {
+                    let current = &bevy_text_component.sections[index];
+                    #[allow(clippy::float_cmp)]
+                    let changed = current.value != section.value
+                        || current.style.font != section.style.font
+                        || current.style.font_size != section.style.font_size
+                        || current.style.color != section.style.color;
+                    if changed {
+                        bevy_text_component.sections[index] = section;
+                    }
+                }
             }
-            #[allow(clippy::float_cmp)]
-            if text.font_size != bevy_text_component.sections[0].style.font_size {
-                bevy_text_component.sections[0].style.font_size = text.font_size;
+            let bevy_text_2d_bounds = text.bevy_text_2d_bounds();
+            if text_2d_bounds.size != bevy_text_2d_bounds.size {
+                *text_2d_bounds = bevy_text_2d_bounds;
             }
-            let font = asset_server.load(text.font.as_str());
-            if bevy_text_component.sections[0].style.font != font {
-                bevy_text_component.sections[0].style.font = font;
+            let alignment = bevy_text_component.alignment;
+            if alignment.horizontal != text.horizontal_align
+                || alignment.vertical != text.vertical_align
+            {
+                bevy_text_component.alignment = TextAlignment {
+                    vertical: text.vertical_align,
+                    horizontal: text.horizontal_align,
+                };
             }
         } else {
             commands.entity(entity).despawn();
@@ -500,6 +1157,40 @@ fn game_logic_sync<S: Send + Sync + 'static>(
     // Add Bevy components for any new texts remaining in engine.texts
     add_texts(&mut commands, &asset_server, &mut engine);
 
+    // Transfer any changes in the user's Shapes to the Bevy Path, DrawMode and Transform components
+    for (entity, mut shape, mut transform, mut path, mut draw_mode) in query_set.p3().iter_mut() {
+        if let Some(shape_copy) = engine.shapes.remove(&shape.label) {
+            if shape_copy.space != shape.space {
+                commands
+                    .entity(entity)
+                    .insert(shape_copy.space.render_layers());
+            }
+            // Rebuilding the geometry is expensive, so only do it when it changed
+            if shape_copy.kind != shape.kind {
+                *path = shape_copy.path();
+            }
+            #[allow(clippy::float_cmp)]
+            if shape_copy.fill_color != shape.fill_color
+                || shape_copy.stroke_color != shape.stroke_color
+                || shape_copy.stroke_width != shape.stroke_width
+            {
+                *draw_mode = shape_copy.draw_mode();
+            }
+            *shape = shape_copy;
+            *transform = place(
+                shape.bevy_transform(),
+                shape.space,
+                shape.anchor,
+                window_dimensions,
+            );
+        } else {
+            commands.entity(entity).despawn();
+        }
+    }
+
+    // Add Bevy components for any new shapes remaining in engine.shapes
+    add_shapes(&mut commands, &mut engine);
+
     if engine.should_exit {
         app_exit_events.send(AppExit);
     }
@@ -0,0 +1,150 @@
+//! Facilities for placing sprites and texts on the screen (a "heads-up display") instead of in the
+//! game world. Things in screen space ignore the [`Camera`](crate::prelude::Camera), always draw
+//! on top of the game world, and stay anchored to the edges of the window when it is resized.
+
+use crate::prelude::Engine;
+use bevy::{
+    core_pipeline::clear_color::ClearColorConfig,
+    prelude::{
+        App, Camera2d, Camera2dBundle, Commands, Component, OrthographicProjection,
+        ParallelSystemDescriptorCoercion, Plugin, Query, Res, Transform, Vec2, With,
+    },
+    render::{camera::Camera as BevyCamera, view::RenderLayers},
+};
+
+/// The render layer used by the HUD camera and everything in screen space
+const HUD_RENDER_LAYER: u8 = 1;
+
+pub(crate) struct HudPlugin;
+
+impl Plugin for HudPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_startup_system(spawn_hud_camera)
+            .add_system(sync_hud_camera.after("game_logic_sync"));
+    }
+}
+
+/// Component to mark the Bevy camera that renders things in screen space
+#[derive(Component)]
+#[doc(hidden)]
+pub struct HudCamera;
+
+/// Which space a [`Sprite`](crate::prelude::Sprite) or [`Text`](crate::prelude::Text) lives in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Space {
+    /// The default. The `translation` is a location in 2D game space, and what you see depends on
+    /// where the [`Camera`](crate::prelude::Camera) is.
+    World,
+    /// The `translation` is an offset (in logical pixels, positive y is up) from the `anchor`
+    /// point on the edge of the window. Useful for scores, health bars, and other HUD elements.
+    /// Things in screen space are always drawn on top of things in world space, and ignore the
+    /// camera entirely. Note that collisions are still calculated using `translation` as-is.
+    Screen,
+}
+
+impl Default for Space {
+    fn default() -> Self {
+        Space::World
+    }
+}
+
+impl Space {
+    /// The render layers that the camera for this space can see
+    #[doc(hidden)]
+    pub fn render_layers(&self) -> RenderLayers {
+        match self {
+            Space::World => RenderLayers::default(),
+            Space::Screen => RenderLayers::layer(HUD_RENDER_LAYER),
+        }
+    }
+}
+
+/// The point on the window that something in [`Space::Screen`] is positioned relative to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ScreenAnchor {
+    TopLeft,
+    TopCenter,
+    TopRight,
+    CenterLeft,
+    Center,
+    CenterRight,
+    BottomLeft,
+    BottomCenter,
+    BottomRight,
+}
+
+impl Default for ScreenAnchor {
+    fn default() -> Self {
+        ScreenAnchor::Center
+    }
+}
+
+impl ScreenAnchor {
+    /// The location of the anchor relative to the center of a window with the given dimensions
+    /// (positive y is up).
+    pub fn location(&self, window_dimensions: Vec2) -> Vec2 {
+        let half = window_dimensions * 0.5;
+        let x = match self {
+            ScreenAnchor::TopLeft | ScreenAnchor::CenterLeft | ScreenAnchor::BottomLeft => -half.x,
+            ScreenAnchor::TopCenter | ScreenAnchor::Center | ScreenAnchor::BottomCenter => 0.0,
+            ScreenAnchor::TopRight | ScreenAnchor::CenterRight | ScreenAnchor::BottomRight => {
+                half.x
+            }
+        };
+        let y = match self {
+            ScreenAnchor::TopLeft | ScreenAnchor::TopCenter | ScreenAnchor::TopRight => half.y,
+            ScreenAnchor::CenterLeft | ScreenAnchor::Center | ScreenAnchor::CenterRight => 0.0,
+            ScreenAnchor::BottomLeft | ScreenAnchor::BottomCenter | ScreenAnchor::BottomRight => {
+                -half.y
+            }
+        };
+        Vec2::new(x, y)
+    }
+}
+
+/// Move a transform produced from a `translation` so that it is relative to the anchor if it is in
+/// screen space. Transforms in world space are returned unchanged.
+#[doc(hidden)]
+pub fn place(
+    mut transform: Transform,
+    space: Space,
+    anchor: ScreenAnchor,
+    window_dimensions: Vec2,
+) -> Transform {
+    if space == Space::Screen {
+        let anchor_location = anchor.location(window_dimensions);
+        transform.translation.x += anchor_location.x;
+        transform.translation.y += anchor_location.y;
+    }
+    transform
+}
+
+/// startup system - spawn the camera which renders everything in screen space on top of the world
+fn spawn_hud_camera(mut commands: Commands) {
+    commands
+        .spawn_bundle(Camera2dBundle {
+            camera: BevyCamera {
+                // Render after the world camera...
+                priority: 1,
+                ..Default::default()
+            },
+            camera_2d: Camera2d {
+                // ...without clearing what the world camera rendered
+                clear_color: ClearColorConfig::None,
+            },
+            ..Default::default()
+        })
+        .insert(Space::Screen.render_layers())
+        .insert(HudCamera);
+}
+
+/// system - keep screen space measured in the same units as the game, even with a virtual
+/// resolution
+fn sync_hud_camera(
+    engine: Res<Engine>,
+    mut camera_query: Query<&mut OrthographicProjection, With<HudCamera>>,
+) {
+    for mut projection in camera_query.iter_mut() {
+        engine.screen_mapping.apply_to(&mut projection);
+    }
+}
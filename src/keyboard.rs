@@ -5,6 +5,7 @@ use bevy::{prelude::*, utils::HashMap};
 
 // Re-export some Bevy types to use
 pub use bevy::input::keyboard::{KeyCode, KeyboardInput};
+pub use bevy::window::ReceivedCharacter;
 
 pub(crate) struct KeyboardPlugin;
 
@@ -20,14 +21,19 @@ impl Plugin for KeyboardPlugin {
 fn sync_keyboard_events(
     mut engine: ResMut<Engine>,
     mut keyboard_input_events: EventReader<KeyboardInput>,
+    mut received_character_events: EventReader<ReceivedCharacter>,
 ) {
     // Clear any events that weren't used last frame
     engine.keyboard_events.clear();
+    engine.received_character_events.clear();
 
     // Populate this frame's events
     for event in keyboard_input_events.iter() {
         engine.keyboard_events.push(event.clone());
     }
+    for event in received_character_events.iter() {
+        engine.received_character_events.push(event.clone());
+    }
 }
 
 pub struct KeyboardStateChain(KeyboardState);
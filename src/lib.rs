@@ -64,14 +64,29 @@
 //! [this `README.md` file](https://github.com/CleanCut/rusty_engine/tree/main/assets/audio/music)
 //! for more information.
 //!
+pub mod assets;
 pub mod audio;
+pub mod bitmap_text;
+pub mod camera;
+pub mod collider_asset;
+pub mod collider_debug;
+pub mod debug_draw;
 pub mod game;
+pub mod hud;
 pub mod keyboard;
 pub mod mouse;
+pub mod parallax;
+pub mod particles;
 pub mod physics;
+pub mod pool;
+pub mod resolution;
+pub mod shape;
 pub mod sprite;
 pub mod text;
+pub mod text_input;
+pub mod tilemap;
 pub mod traits;
+pub mod tween;
 
 #[macro_use]
 pub mod macros;
@@ -79,7 +94,10 @@ pub mod macros;
 // Public prelude
 pub mod prelude {
     pub use crate::{
-        audio::*, game::*, keyboard::*, mouse::*, physics::*, sprite::*, text::*, traits::*,
+        assets::*, audio::*, bitmap_text::*, camera::*, collider_asset::*, collider_debug::*,
+        debug_draw::*, game::*, hud::*, keyboard::*, mouse::*, parallax::*, particles::*,
+        physics::*, pool::*, resolution::*, shape::*, sprite::*, text::*, text_input::*,
+        tilemap::*, traits::*, tween::*,
     };
     pub use crate::{
         DOWN, EAST, LEFT, NORTH, NORTH_EAST, NORTH_WEST, RIGHT, SOUTH, SOUTH_EAST, SOUTH_WEST, UP,
@@ -87,7 +105,7 @@ pub mod prelude {
     };
     pub use bevy::{
         self,
-        prelude::{Time, Timer, Vec2},
+        prelude::{Color, Time, Timer, Vec2},
     };
 }
 
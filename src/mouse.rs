@@ -105,6 +105,7 @@ impl MouseStateChain {
 #[derive(Clone, Debug, Default)]
 pub struct MouseState {
     location: Option<Vec2>,
+    screen_location: Option<Vec2>,
     motion: Vec2,
     wheel: MouseWheelState,
     pressed: HashSet<MouseButton>,
@@ -130,12 +131,20 @@ pub struct MouseWheelState {
 }
 
 impl MouseState {
-    /// Final location of the mouse this frame. If you want to process _all_ the locations the mouse
+    /// Final location of the mouse this frame in game space, as seen through the
+    /// [`Camera`](crate::prelude::Camera). If you want to process _all_ the locations the mouse
     /// was at during this frame, see
     /// [`Engine::mouse_location_events`](crate::prelude::Engine::mouse_location_events) instead.
     pub fn location(&self) -> Option<Vec2> {
         self.location
     }
+    /// Final location of the mouse this frame relative to the center of the window (positive y is
+    /// up), ignoring the camera. Useful for things that don't move with the camera. If
+    /// [`Engine::virtual_resolution`](crate::prelude::Engine::virtual_resolution) is set, this is
+    /// measured in virtual pixels.
+    pub fn screen_location(&self) -> Option<Vec2> {
+        self.screen_location
+    }
     /// The cumulative relative motion of the mouse this frame. If you want to process _all_ the
     /// individual relative motions, see
     /// [`Engine::mouse_motion_events](crate::prelude::Engine::mouse_motion_events) instead.
@@ -199,7 +208,10 @@ fn sync_mouse_events(
         let mut new_event = ev.clone();
         // Convert from screen space to game space
         // TODO: Check to see if this needs to be adjusted for different DPIs
-        new_event.position -= game_state.window_dimensions * 0.5;
+        let mapping = game_state.screen_mapping;
+        new_event.position = game_state.camera.screen_to_world(
+            mapping.window_to_virtual(new_event.position - mapping.window_dimensions * 0.5),
+        );
         game_state.mouse_location_events.push(new_event);
     }
     for ev in mouse_motion_events.iter() {
@@ -224,10 +236,17 @@ fn sync_mouse_state(
     // Sync the current mouse location, which will be the last cursor_moved event that occurred.
     // Only changes when we get a new event, otherwise we preserve the last location.
     if let Some(event) = cursor_moved_events.iter().last() {
-        // Convert from bevy's window space to our game space
-        let location = event.position - game_state.window_dimensions * 0.5;
-        mouse_state.location = Some(location);
+        // Convert from bevy's window space (origin in the bottom-left corner) to the center of the
+        // window, in virtual pixels if there is a virtual resolution
+        let mapping = game_state.screen_mapping;
+        mouse_state.screen_location =
+            Some(mapping.window_to_virtual(event.position - mapping.window_dimensions * 0.5));
     }
+    // Convert to game space as seen through the camera every frame, since the camera may have
+    // moved even though the mouse did not.
+    mouse_state.location = mouse_state
+        .screen_location
+        .map(|screen_location| game_state.camera.screen_to_world(screen_location));
     // Sync the relative mouse motion. This is the cumulative relative motion during the last frame.
     mouse_state.motion = Vec2::ZERO;
     for ev in mouse_motion_events.iter() {
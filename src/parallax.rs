@@ -0,0 +1,214 @@
+//! Facilities for parallax backgrounds: images which scroll slower (or faster) than the game world
+//! as the [`Camera`](crate::prelude::Camera) moves, and which can repeat forever.
+
+use crate::{prelude::Engine, traits::EngineEntity};
+use bevy::{
+    prelude::{
+        App, AssetServer, Assets, Commands, Component, Entity, Handle, Image, Local,
+        ParallelSystemDescriptorCoercion, Plugin, Query, Res, SpriteBundle, Transform, Vec2, Vec3,
+        With,
+    },
+    utils::HashMap,
+};
+use std::path::PathBuf;
+
+pub(crate) struct ParallaxPlugin;
+
+impl Plugin for ParallaxPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system(sync_parallax.after("sync_camera"));
+    }
+}
+
+impl EngineEntity for ParallaxLayer {
+    fn label(&self) -> &str {
+        &self.label
+    }
+}
+
+/// A [`ParallaxLayer`] is a background (or foreground) image that follows the camera around at its
+/// own speed, giving a sense of depth to side-scrolling and top-down games. Add one with
+/// [`Engine::add_parallax_layer`](crate::prelude::Engine::add_parallax_layer). The image is
+/// tiled automatically to fill the window in any direction that it repeats, so a small image is
+/// enough to make an endless background. At most [`MAX_PARALLAX_TILES`] copies of the image are
+/// drawn across (and down), so a camera zoomed very far out may see the edges of the layer.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ParallaxLayer {
+    /// READONLY: A way to identify the layer. This must be unique among parallax layers.
+    pub label: String,
+    /// SYNCED: The image file to use, relative to the `assets/` directory.
+    pub filepath: PathBuf,
+    /// SYNCED: How much the layer moves when the camera moves. `(1.0, 1.0)` moves exactly as much as
+    /// the game world (like a sprite would). `(0.0, 0.0)` doesn't move at all (like a distant sky).
+    /// Values in between look further away, and values above `1.0` look closer than the game
+    /// world. Defaults to `(0.5, 0.5)`.
+    pub scroll_factor: Vec2,
+    /// SYNCED: Whether to repeat the image forever horizontally. Defaults to `true`.
+    pub repeat_x: bool,
+    /// SYNCED: Whether to repeat the image forever vertically. Defaults to `false`.
+    pub repeat_y: bool,
+    /// SYNCED: Depth of the layer. 0.0 (back) to 999.0 (front). Defaults to `0.0`, behind
+    /// everything else.
+    pub layer: f32,
+    /// SYNCED: Where the image is placed when the camera is at `(0.0, 0.0)`.
+    pub offset: Vec2,
+    /// SYNCED: 1.0 is the normal 100%
+    pub scale: f32,
+}
+
+impl Default for ParallaxLayer {
+    fn default() -> Self {
+        Self {
+            label: String::default(),
+            filepath: PathBuf::default(),
+            scroll_factor: Vec2::splat(0.5),
+            repeat_x: true,
+            repeat_y: false,
+            layer: 0.0,
+            offset: Vec2::ZERO,
+            scale: 1.0,
+        }
+    }
+}
+
+impl ParallaxLayer {
+    /// `label` should be a unique string. `filepath` should be the path of an image file relative
+    /// to the `assets/` directory.
+    pub fn new<L: Into<String>, P: Into<PathBuf>>(label: L, filepath: P) -> Self {
+        Self {
+            label: label.into(),
+            filepath: filepath.into(),
+            ..Default::default()
+        }
+    }
+}
+
+/// Component to mark one of the sprites that make up a parallax layer
+#[derive(Component)]
+#[doc(hidden)]
+pub struct ParallaxTile;
+
+/// The Bevy side of a parallax layer
+struct ParallaxTiles {
+    filepath: PathBuf,
+    texture: Handle<Image>,
+    entities: Vec<Entity>,
+}
+
+/// The most copies of a parallax layer's image that are drawn along each axis. A camera zoomed
+/// far out (or a tiny image) would otherwise need an unlimited number of sprites.
+pub const MAX_PARALLAX_TILES: i32 = 64;
+
+/// The range of tile indices needed to cover `min..=max` along one axis, limited to the middle
+/// [`MAX_PARALLAX_TILES`] tiles
+fn tile_range(min: f32, max: f32, origin: f32, size: f32, repeat: bool) -> (i32, i32) {
+    if !repeat || size <= 0.0 {
+        return (0, 0);
+    }
+    // Tile `i` is centered on `origin + i * size`
+    let first = ((min - origin) / size + 0.5).floor();
+    let last = ((max - origin) / size + 0.5).floor().max(first);
+    if !first.is_finite() || !last.is_finite() {
+        return (0, 0);
+    }
+    if last - first < MAX_PARALLAX_TILES as f32 {
+        return (first as i32, last as i32);
+    }
+    let first = (((first + last) * 0.5).floor() - (MAX_PARALLAX_TILES / 2) as f32) as i32;
+    (first, first.saturating_add(MAX_PARALLAX_TILES - 1))
+}
+
+/// system - position (and add or remove) the sprites which make up each parallax layer
+fn sync_parallax(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    images: Res<Assets<Image>>,
+    engine: Res<Engine>,
+    mut layer_tiles: Local<HashMap<String, ParallaxTiles>>,
+    mut tile_query: Query<(&mut Transform, &mut Handle<Image>), With<ParallaxTile>>,
+) {
+    // Remove tiles for any layers that the user removed
+    layer_tiles.retain(|label, tiles| {
+        let keep = engine.parallax_layers.contains_key(label);
+        if !keep {
+            for entity in tiles.entities.drain(..) {
+                commands.entity(entity).despawn();
+            }
+        }
+        keep
+    });
+
+    // Figure out which part of the world is visible, taking zoom and rotation into account
+    let half_window = engine.window_dimensions * 0.5;
+    let corners = [
+        Vec2::new(-half_window.x, -half_window.y),
+        Vec2::new(half_window.x, -half_window.y),
+        Vec2::new(half_window.x, half_window.y),
+        Vec2::new(-half_window.x, half_window.y),
+    ]
+    .map(|corner| engine.camera.screen_to_world(corner));
+    let view_min = corners.iter().fold(corners[0], |a, b| a.min(*b));
+    let view_max = corners.iter().fold(corners[0], |a, b| a.max(*b));
+    let camera_center = engine.camera.screen_to_world(Vec2::ZERO);
+
+    for layer in engine.parallax_layers.values() {
+        let tiles = layer_tiles
+            .entry(layer.label.clone())
+            .or_insert_with(|| ParallaxTiles {
+                filepath: layer.filepath.clone(),
+                texture: asset_server.load(layer.filepath.clone()),
+                entities: Vec::new(),
+            });
+        if tiles.filepath != layer.filepath {
+            tiles.filepath = layer.filepath.clone();
+            tiles.texture = asset_server.load(layer.filepath.clone());
+        }
+        // We can't tile the image until we know how big it is
+        let size = match images.get(&tiles.texture) {
+            Some(image) => image.size() * layer.scale,
+            None => continue,
+        };
+
+        let origin = camera_center * (Vec2::ONE - layer.scroll_factor) + layer.offset;
+        let (first_x, last_x) =
+            tile_range(view_min.x, view_max.x, origin.x, size.x, layer.repeat_x);
+        let (first_y, last_y) =
+            tile_range(view_min.y, view_max.y, origin.y, size.y, layer.repeat_y);
+        let mut transforms = Vec::new();
+        for x in first_x..=last_x {
+            for y in first_y..=last_y {
+                let translation = origin + Vec2::new(x as f32, y as f32) * size;
+                transforms.push(Transform {
+                    translation: translation.extend(layer.layer),
+                    scale: Vec3::new(layer.scale, layer.scale, 1.0),
+                    ..Default::default()
+                });
+            }
+        }
+
+        // Spawn or despawn tiles so that there are exactly as many as we need
+        while tiles.entities.len() > transforms.len() {
+            // Unwrap: the loop condition guarantees there's at least one entity
+            commands.entity(tiles.entities.pop().unwrap()).despawn();
+        }
+        for transform in transforms.iter().skip(tiles.entities.len()) {
+            let entity = commands
+                .spawn_bundle(SpriteBundle {
+                    texture: tiles.texture.clone(),
+                    transform: *transform,
+                    ..Default::default()
+                })
+                .insert(ParallaxTile)
+                .id();
+            tiles.entities.push(entity);
+        }
+        for (entity, transform) in tiles.entities.iter().zip(transforms.iter()) {
+            if let Ok((mut tile_transform, mut texture)) = tile_query.get_mut(*entity) {
+                *tile_transform = *transform;
+                if *texture != tiles.texture {
+                    *texture = tiles.texture.clone();
+                }
+            }
+        }
+    }
+}
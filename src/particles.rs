@@ -0,0 +1,461 @@
+//! Facilities for particle effects like explosions, smoke, sparks, and trails. A
+//! [`ParticleEmitter`] spawns lots of small, short-lived images or shapes which are simulated by
+//! the engine, so you don't need to manage a sprite for each one.
+
+use crate::{
+    prelude::Engine,
+    shape::ShapeKind,
+    sprite::{Sprite, SpritePreset},
+    traits::EngineEntity,
+};
+use bevy::{
+    prelude::{
+        App, AssetServer, Color, Commands, Component, Entity, Local,
+        ParallelSystemDescriptorCoercion, Plugin, Quat, Query, Res, ResMut, Sprite as BevySprite,
+        SpriteBundle, Time, Transform, Vec2, Vec3,
+    },
+    utils::HashMap,
+};
+use bevy_prototype_lyon::prelude::{DrawMode, FillMode, GeometryBuilder};
+use std::{
+    f32::consts::PI,
+    ops::RangeInclusive,
+    path::PathBuf,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+pub(crate) struct ParticlePlugin;
+
+impl Plugin for ParticlePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system(sync_particles.after("game_logic_sync"));
+    }
+}
+
+impl EngineEntity for ParticleEmitter {
+    fn label(&self) -> &str {
+        &self.label
+    }
+}
+
+/// What each particle looks like
+#[derive(Clone, Debug, PartialEq)]
+pub enum ParticleAppearance {
+    /// An image file, relative to the `assets/` directory
+    Image(PathBuf),
+    /// A vector shape, which is filled in with the particle's color
+    Shape(ShapeKind),
+}
+
+impl From<ShapeKind> for ParticleAppearance {
+    fn from(kind: ShapeKind) -> Self {
+        Self::Shape(kind)
+    }
+}
+
+impl From<SpritePreset> for ParticleAppearance {
+    fn from(sprite_preset: SpritePreset) -> Self {
+        Self::Image(sprite_preset.filepath())
+    }
+}
+
+impl From<PathBuf> for ParticleAppearance {
+    fn from(filepath: PathBuf) -> Self {
+        Self::Image(filepath)
+    }
+}
+
+impl From<&str> for ParticleAppearance {
+    fn from(filepath: &str) -> Self {
+        Self::Image(filepath.into())
+    }
+}
+
+/// Which space particles move in once they have been emitted
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ParticleSpace {
+    /// Particles are left behind in the game world when the emitter moves, like smoke or a trail.
+    World,
+    /// Particles move and rotate along with the emitter, like a flame on a jetpack.
+    Local,
+}
+
+/// A [`ParticleEmitter`] continuously emits particles at `rate` particles per second, and can also
+/// emit many particles at once with [`ParticleEmitter::burst`]. Each particle lives for a random
+/// amount of time within `lifetime`, starts moving in a random direction within `angle` at a random
+/// speed within `speed`, and is pulled by `gravity`. Over its life, a particle changes from the
+/// `start_` to the `end_` color, alpha, and scale. Add one with
+/// [`Engine::add_particle_emitter`](crate::prelude::Engine::add_particle_emitter).
+///
+/// Ranges are inclusive, so use the same value on both ends (e.g. `1.0..=1.0`) if you don't want
+/// any randomness.
+///
+/// ```rust,no_run
+/// # use rusty_engine::prelude::*;
+/// #
+/// # fn main() {
+/// # let mut game = Game::new();
+/// // A one-time explosion
+/// let explosion = game.add_particle_emitter("explosion", ShapeKind::Circle { radius: 4.0 });
+/// explosion.rate = 0.0;
+/// explosion.speed = 100.0..=300.0;
+/// explosion.start_color = Color::YELLOW;
+/// explosion.end_color = Color::RED;
+/// explosion.burst(100);
+/// # game.run(());
+/// # }
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct ParticleEmitter {
+    /// READONLY: A way to identify the emitter. This must be unique among particle emitters.
+    pub label: String,
+    /// SYNCED: What each newly emitted particle looks like
+    pub appearance: ParticleAppearance,
+    /// SYNCED: Where the emitter is in 2D game space. If `attach_to` is set, this is instead an
+    /// offset from the sprite it is attached to, which rotates along with the sprite.
+    pub translation: Vec2,
+    /// SYNCED: Depth of the particles. 0.0 (back) to 999.0 (front)
+    pub layer: f32,
+    /// SYNCED: Direction the emitter faces in radians. `angle` is relative to this.
+    pub rotation: f32,
+    /// SYNCED: The label of a sprite to follow, or `None` to stay put. While the sprite doesn't
+    /// exist, no new particles are emitted.
+    pub attach_to: Option<String>,
+    /// SYNCED: Whether particles are left behind in the world or move along with the emitter.
+    /// Defaults to [`ParticleSpace::World`].
+    pub space: ParticleSpace,
+    /// SYNCED: Whether to emit particles at `rate`. Bursts are emitted either way. Defaults to
+    /// `true`.
+    pub emitting: bool,
+    /// SYNCED: How many particles to emit per second. Defaults to `20.0`.
+    pub rate: f32,
+    /// SYNCED: The most particles this emitter may have alive at once. Defaults to `500`.
+    pub max_particles: usize,
+    /// SYNCED: How long each particle lives, in seconds. Defaults to `1.0..=1.0`.
+    pub lifetime: RangeInclusive<f32>,
+    /// SYNCED: How fast particles are moving when they are emitted. Defaults to `50.0..=100.0`.
+    pub speed: RangeInclusive<f32>,
+    /// SYNCED: The direction particles are emitted in, in radians relative to `rotation`. Defaults
+    /// to every direction (`-PI..=PI`).
+    pub angle: RangeInclusive<f32>,
+    /// SYNCED: How fast particles spin, in radians per second. Defaults to `0.0..=0.0`.
+    pub spin: RangeInclusive<f32>,
+    /// SYNCED: Acceleration applied to every particle. Use a negative y to make particles fall.
+    /// Defaults to `(0.0, 0.0)`.
+    pub gravity: Vec2,
+    /// SYNCED: Color of new particles. Defaults to white.
+    pub start_color: Color,
+    /// SYNCED: Color of particles at the end of their life. Defaults to white.
+    pub end_color: Color,
+    /// SYNCED: Opacity of new particles. Multiplied by the alpha of the color. Defaults to `1.0`.
+    pub start_alpha: f32,
+    /// SYNCED: Opacity of particles at the end of their life. Defaults to `0.0`, so particles
+    /// fade out.
+    pub end_alpha: f32,
+    /// SYNCED: Scale of new particles. Defaults to `1.0`.
+    pub start_scale: f32,
+    /// SYNCED: Scale of particles at the end of their life. Defaults to `1.0`.
+    pub end_scale: f32,
+    // particles requested with `burst` that haven't been emitted yet
+    pending_burst: usize,
+    // how many of this emitter's particles are alive, updated every frame
+    particle_count: usize,
+}
+
+impl Default for ParticleEmitter {
+    fn default() -> Self {
+        Self {
+            label: String::default(),
+            appearance: ParticleAppearance::Shape(ShapeKind::Circle { radius: 4.0 }),
+            translation: Vec2::ZERO,
+            layer: 0.0,
+            rotation: 0.0,
+            attach_to: None,
+            space: ParticleSpace::World,
+            emitting: true,
+            rate: 20.0,
+            max_particles: 500,
+            lifetime: 1.0..=1.0,
+            speed: 50.0..=100.0,
+            angle: -PI..=PI,
+            spin: 0.0..=0.0,
+            gravity: Vec2::ZERO,
+            start_color: Color::WHITE,
+            end_color: Color::WHITE,
+            start_alpha: 1.0,
+            end_alpha: 0.0,
+            start_scale: 1.0,
+            end_scale: 1.0,
+            pending_burst: 0,
+            particle_count: 0,
+        }
+    }
+}
+
+impl ParticleEmitter {
+    /// `label` should be a unique string. `appearance` may be a [`ShapeKind`], a [`SpritePreset`],
+    /// or the path of an image file relative to the `assets/` directory.
+    pub fn new<L: Into<String>, A: Into<ParticleAppearance>>(label: L, appearance: A) -> Self {
+        Self {
+            label: label.into(),
+            appearance: appearance.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Emit `count` particles all at once on the next frame, in addition to any emitted at `rate`.
+    /// Bursts still respect `max_particles`.
+    pub fn burst(&mut self, count: usize) {
+        self.pending_burst += count;
+    }
+
+    /// How many of this emitter's particles are currently alive
+    pub fn particle_count(&self) -> usize {
+        self.particle_count
+    }
+
+    /// Where the emitter is and which way it faces in 2D game space, or `None` if the sprite it is
+    /// attached to doesn't exist.
+    fn placement(&self, sprite_query: &Query<&Sprite>) -> Option<(Vec2, f32)> {
+        match &self.attach_to {
+            Some(sprite_label) => {
+                // The sprites are back in the Bevy world (not in `Engine::sprites`) by now
+                let sprite = sprite_query
+                    .iter()
+                    .find(|sprite| &sprite.label == sprite_label)?;
+                let offset = Vec2::from_angle(sprite.rotation).rotate(self.translation);
+                Some((sprite.translation + offset, sprite.rotation + self.rotation))
+            }
+            None => Some((self.translation, self.rotation)),
+        }
+    }
+}
+
+/// Component for a single particle. Its position and velocity are in 2D game space, or relative to
+/// the emitter if it was emitted in [`ParticleSpace::Local`].
+#[derive(Component)]
+#[doc(hidden)]
+pub struct Particle {
+    emitter_label: String,
+    space: ParticleSpace,
+    age: f32,
+    lifetime: f32,
+    position: Vec2,
+    velocity: Vec2,
+    rotation: f32,
+    spin: f32,
+}
+
+/// A small, fast xorshift random number generator. Particles don't need anything fancier.
+struct ParticleRng(u64);
+
+impl Default for ParticleRng {
+    fn default() -> Self {
+        let seed = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_nanos() as u64)
+            .unwrap_or_default();
+        // Xorshift gets stuck on zero
+        Self(seed | 1)
+    }
+}
+
+impl ParticleRng {
+    /// A random number from `0.0` to `1.0`
+    fn next_f32(&mut self) -> f32 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        (self.0 >> 40) as f32 / (1u64 << 24) as f32
+    }
+
+    /// A random number within `range`
+    fn range(&mut self, range: &RangeInclusive<f32>) -> f32 {
+        range.start() + (range.end() - range.start()) * self.next_f32()
+    }
+}
+
+/// The color of a particle `t` of the way (`0.0` to `1.0`) through its life
+fn color_at(emitter: &ParticleEmitter, t: f32) -> Color {
+    let start = emitter.start_color.as_rgba_f32();
+    let end = emitter.end_color.as_rgba_f32();
+    let lerp = |i: usize| start[i] + (end[i] - start[i]) * t;
+    let alpha = emitter.start_alpha + (emitter.end_alpha - emitter.start_alpha) * t;
+    Color::rgba(lerp(0), lerp(1), lerp(2), (lerp(3) * alpha).clamp(0.0, 1.0))
+}
+
+/// Where a particle should be drawn, given the emitter's current placement
+fn particle_transform(
+    particle: &Particle,
+    emitter: &ParticleEmitter,
+    placement: (Vec2, f32),
+    scale: f32,
+) -> Transform {
+    let (position, rotation) = match particle.space {
+        ParticleSpace::World => (particle.position, particle.rotation),
+        ParticleSpace::Local => (
+            placement.0 + Vec2::from_angle(placement.1).rotate(particle.position),
+            placement.1 + particle.rotation,
+        ),
+    };
+    let mut transform = Transform::from_translation(position.extend(emitter.layer));
+    transform.rotation = Quat::from_axis_angle(Vec3::Z, rotation);
+    transform.scale = Vec3::splat(scale);
+    transform
+}
+
+/// system - move, age, and emit particles
+#[allow(clippy::type_complexity, clippy::too_many_arguments)]
+fn sync_particles(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    time: Res<Time>,
+    mut engine: ResMut<Engine>,
+    mut rng: Local<ParticleRng>,
+    mut last_placements: Local<HashMap<String, (Vec2, f32)>>,
+    sprite_query: Query<&Sprite>,
+    mut particle_query: Query<(
+        Entity,
+        &mut Particle,
+        &mut Transform,
+        Option<&mut BevySprite>,
+        Option<&mut DrawMode>,
+    )>,
+) {
+    let delta = time.delta_seconds();
+
+    // Where each emitter is this frame. Emitters attached to missing sprites stay where they were
+    // last seen, so that their local particles don't jump around.
+    let mut placements: HashMap<String, Option<(Vec2, f32)>> = HashMap::default();
+    for emitter in engine.particle_emitters.values() {
+        let placement = emitter.placement(&sprite_query);
+        if let Some(placement) = placement {
+            last_placements.insert(emitter.label.clone(), placement);
+        }
+        placements.insert(emitter.label.clone(), placement);
+    }
+    last_placements.retain(|label, _| placements.contains_key(label));
+
+    // Age and move existing particles, and remove the ones that are done
+    let mut particle_counts: HashMap<String, usize> = HashMap::default();
+    for (entity, mut particle, mut transform, sprite, draw_mode) in particle_query.iter_mut() {
+        let emitter = match engine.particle_emitters.get(&particle.emitter_label) {
+            Some(emitter) => emitter,
+            None => {
+                // The user removed the emitter, so its particles go away too
+                commands.entity(entity).despawn();
+                continue;
+            }
+        };
+        particle.age += delta;
+        if particle.age >= particle.lifetime {
+            commands.entity(entity).despawn();
+            continue;
+        }
+        particle.velocity += emitter.gravity * delta;
+        let velocity = particle.velocity;
+        particle.position += velocity * delta;
+        particle.rotation += particle.spin * delta;
+
+        let t = particle.age / particle.lifetime;
+        let scale = emitter.start_scale + (emitter.end_scale - emitter.start_scale) * t;
+        let placement = last_placements
+            .get(&emitter.label)
+            .copied()
+            .unwrap_or((emitter.translation, emitter.rotation));
+        *transform = particle_transform(&particle, emitter, placement, scale);
+        let color = color_at(emitter, t);
+        // Only write the color when it changed, because writing it makes the shape's mesh get
+        // rebuilt
+        if let Some(mut sprite) = sprite {
+            if sprite.color != color {
+                sprite.color = color;
+            }
+        }
+        if let Some(mut draw_mode) = draw_mode {
+            if !matches!(*draw_mode, DrawMode::Fill(ref fill) if fill.color == color) {
+                *draw_mode = DrawMode::Fill(FillMode::color(color));
+            }
+        }
+        *particle_counts
+            .entry(particle.emitter_label.clone())
+            .or_default() += 1;
+    }
+
+    // Emit new particles
+    for emitter in engine.particle_emitters.values_mut() {
+        emitter.particle_count = particle_counts.get(&emitter.label).copied().unwrap_or(0);
+        let placement = match placements[&emitter.label] {
+            Some(placement) => placement,
+            None => {
+                // Nothing to emit from
+                emitter.pending_burst = 0;
+                continue;
+            }
+        };
+        let mut count = std::mem::take(&mut emitter.pending_burst);
+        if emitter.emitting && emitter.rate > 0.0 {
+            // Emit whole particles on average at `rate`, no matter the frame rate
+            let expected = emitter.rate * delta;
+            count += expected.floor() as usize;
+            if rng.next_f32() < expected.fract() {
+                count += 1;
+            }
+        }
+        let count = count.min(emitter.max_particles.saturating_sub(emitter.particle_count));
+        for _ in 0..count {
+            let angle = rng.range(&emitter.angle);
+            let speed = rng.range(&emitter.speed);
+            let spin = rng.range(&emitter.spin);
+            let particle = match emitter.space {
+                ParticleSpace::World => Particle {
+                    emitter_label: emitter.label.clone(),
+                    space: emitter.space,
+                    age: 0.0,
+                    lifetime: rng.range(&emitter.lifetime).max(f32::EPSILON),
+                    position: placement.0,
+                    velocity: Vec2::from_angle(placement.1 + angle) * speed,
+                    rotation: placement.1 + angle,
+                    spin,
+                },
+                ParticleSpace::Local => Particle {
+                    emitter_label: emitter.label.clone(),
+                    space: emitter.space,
+                    age: 0.0,
+                    lifetime: rng.range(&emitter.lifetime).max(f32::EPSILON),
+                    position: Vec2::ZERO,
+                    velocity: Vec2::from_angle(angle) * speed,
+                    rotation: angle,
+                    spin,
+                },
+            };
+            let transform = particle_transform(&particle, emitter, placement, emitter.start_scale);
+            let color = color_at(emitter, 0.0);
+            match &emitter.appearance {
+                ParticleAppearance::Image(filepath) => {
+                    commands
+                        .spawn_bundle(SpriteBundle {
+                            texture: asset_server.load(filepath.clone()),
+                            sprite: BevySprite {
+                                color,
+                                ..Default::default()
+                            },
+                            transform,
+                            ..Default::default()
+                        })
+                        .insert(particle);
+                }
+                ParticleAppearance::Shape(kind) => {
+                    commands
+                        .spawn_bundle(GeometryBuilder::build_as(
+                            &kind.path(),
+                            DrawMode::Fill(FillMode::color(color)),
+                            transform,
+                        ))
+                        .insert(particle);
+                }
+            }
+        }
+        emitter.particle_count += count;
+    }
+}
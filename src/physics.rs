@@ -1,12 +1,19 @@
 //! Rusty Engine's custom collision detection implementation.
 
-use crate::sprite::Sprite;
+use crate::{
+    assets::{asset_exists, asset_path, read_asset},
+    prelude::Engine,
+    shape::Shape,
+    sprite::Sprite,
+};
 use bevy::prelude::*;
 use serde::{Deserialize, Serialize};
 use std::{
-    collections::HashSet,
+    collections::{HashMap, HashSet},
     f32::consts::{PI, TAU},
     hash::Hash,
+    path::{Path, PathBuf},
+    sync::{Mutex, PoisonError},
 };
 
 pub(crate) struct PhysicsPlugin;
@@ -22,8 +29,9 @@ impl Plugin for PhysicsPlugin {
 // can now be multiline.
 
 /// This is the struct that is generated when a collision occurs. Collisions only occur between two
-/// [Sprite]s which:
-/// - have colliders (you can use the `collider` example to create your own colliders)
+/// [Sprite]s, [`Shape`]s, or [`Tilemap`](crate::prelude::Tilemap)s (but not two tilemaps) which:
+/// - have colliders (you can use the `collider` example to create your own colliders, shapes derive
+///   their colliders from their geometry, and the solid tiles of a tilemap make up its collider)
 /// - have their `collision` flags set to `true`.
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct CollisionEvent {
@@ -55,7 +63,7 @@ impl CollisionState {
     }
 }
 
-/// Contains the labels of the two sprites involved in the collision. As the labels are unordered,
+/// Contains the labels of the two sprites (or shapes, or tilemaps) involved in the collision. As the labels are unordered,
 /// several convenience methods are provided for searching the values.
 #[derive(Debug, Default, Eq, Clone)]
 pub struct CollisionPair(pub String, pub String);
@@ -120,22 +128,89 @@ impl Hash for CollisionPair {
     }
 }
 
+/// The collider polygons of a tilemap, and what they were built from
+struct TilemapColliderCache {
+    solid_version: u64,
+    tile_size: Vec2,
+    translation: Vec2,
+    scale: f32,
+    polygons: Vec<Vec<Vec2>>,
+}
+
 /// system - detect collisions and generate the collision events
 fn collision_detection(
     mut existing_collisions: Local<HashSet<CollisionPair>>,
+    mut tilemap_colliders: Local<HashMap<String, TilemapColliderCache>>,
     mut collision_events: EventWriter<CollisionEvent>,
-    query: Query<&Sprite>,
+    sprite_query: Query<&Sprite>,
+    shape_query: Query<&Shape>,
+    engine: Res<Engine>,
 ) {
+    // Gather the colliders of all the sprites and shapes that want collisions, in 2D game space
+    let bodies: Vec<(&str, Vec<Vec2>)> = sprite_query
+        .iter()
+        .filter(|sprite| sprite.visible && sprite.collision && sprite.collider.is_poly())
+        .map(|sprite| (sprite.label.as_str(), sprite.collider.relative_to(sprite)))
+        .chain(
+            shape_query
+                .iter()
+                .filter(|shape| shape.collision && shape.collider.is_poly())
+                .map(|shape| {
+                    (
+                        shape.label.as_str(),
+                        shape
+                            .collider
+                            .transformed(shape.translation, shape.rotation, shape.scale),
+                    )
+                }),
+        )
+        .collect();
+
     let mut current_collisions = HashSet::<CollisionPair>::new();
-    'outer: for sprite1 in query.iter().filter(|a| a.collision) {
-        for sprite2 in query.iter().filter(|a| a.collision) {
-            if sprite1.label == sprite2.label {
-                // We only need to compare one half of the matrix triangle
-                continue 'outer;
+    for (idx, (label1, poly1)) in bodies.iter().enumerate() {
+        // We only need to compare one half of the matrix triangle
+        for (label2, poly2) in bodies.iter().skip(idx + 1) {
+            if polygons_colliding(poly1, poly2) {
+                current_collisions.insert(CollisionPair(label1.to_string(), label2.to_string()));
             }
-            if Collider::colliding(sprite1, sprite2) {
-                current_collisions
-                    .insert(CollisionPair(sprite1.label.clone(), sprite2.label.clone()));
+        }
+    }
+
+    // Tilemaps don't move on their own, so we only check them against sprites and shapes. Their
+    // colliders are only rebuilt when the tilemap changes, since big maps have lots of tiles.
+    tilemap_colliders.retain(|label, _| engine.tilemaps.contains_key(label));
+    for tilemap in engine.tilemaps.values().filter(|a| a.collision) {
+        let up_to_date = |cache: &TilemapColliderCache| {
+            cache.solid_version == tilemap.solid_version()
+                && cache.tile_size == tilemap.tileset.tile_size
+                && cache.translation == tilemap.translation
+                && cache.scale == tilemap.scale
+        };
+        if !tilemap_colliders
+            .get(&tilemap.label)
+            .map_or(false, up_to_date)
+        {
+            tilemap_colliders.insert(
+                tilemap.label.clone(),
+                TilemapColliderCache {
+                    solid_version: tilemap.solid_version(),
+                    tile_size: tilemap.tileset.tile_size,
+                    translation: tilemap.translation,
+                    scale: tilemap.scale,
+                    polygons: tilemap.collider_polygons(),
+                },
+            );
+        }
+        let tilemap_polys = &tilemap_colliders[&tilemap.label].polygons;
+        if tilemap_polys.is_empty() {
+            continue;
+        }
+        for (label, poly) in bodies.iter() {
+            if tilemap_polys
+                .iter()
+                .any(|tilemap_poly| polygons_colliding(poly, tilemap_poly))
+            {
+                current_collisions.insert(CollisionPair(label.to_string(), tilemap.label.clone()));
             }
         }
     }
@@ -184,7 +259,88 @@ impl Default for Collider {
     }
 }
 
+/// Colliders which have already been read from `.collider` files, by path (relative to the asset
+/// root), so that creating many sprites from the same image only reads the file
+/// once. Missing and invalid files are remembered as `Collider::NoCollider`, so that their warning
+/// is only printed once.
+static COLLIDER_CACHE: Mutex<Option<HashMap<PathBuf, Collider>>> = Mutex::new(None);
+
+/// Reads a collider file, printing a warning and returning `Collider::NoCollider` if it fails
+fn read_collider_from_file(collider_filepath: &Path) -> Collider {
+    // Bevy's asset system is relative from the asset root, so we must be too
+    if !asset_exists(collider_filepath) {
+        eprintln!(
+            "warning: could not find collider file {} -- consider creating one with the `collider` example.",
+            asset_path(collider_filepath).to_string_lossy()
+        );
+        return Collider::NoCollider;
+    }
+    match read_asset(collider_filepath) {
+        Ok(bytes) => match ron::de::from_bytes::<Collider>(&bytes) {
+            Ok(collider) => collider,
+            Err(e) => {
+                eprintln!("failed deserializing collider from file: {}", e);
+                Collider::NoCollider
+            }
+        },
+        Err(e) => {
+            eprintln!("failed to open collider file: {}", e);
+            Collider::NoCollider
+        }
+    }
+}
+
 impl Collider {
+    /// Load a collider from a `.collider` file, relative to the asset root (`assets/` by default).
+    /// Each file is only read from disk the first time it is loaded. After that, the collider comes
+    /// from a cache shared by the whole program. If the file is missing or invalid, a warning is printed
+    /// (once per file) and `Collider::NoCollider` is returned. [`Sprite::new`] takes the collider
+    /// that goes with the sprite's image from the same cache, once it has been loaded.
+    pub fn load<P: AsRef<Path>>(collider_filepath: P) -> Self {
+        let collider_filepath = collider_filepath.as_ref();
+        let mut cache = COLLIDER_CACHE
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        let cache = cache.get_or_insert_with(HashMap::new);
+        if let Some(collider) = cache.get(collider_filepath) {
+            return collider.clone();
+        }
+        let collider = read_collider_from_file(collider_filepath);
+        cache.insert(collider_filepath.to_path_buf(), collider.clone());
+        collider
+    }
+    /// Like [`Collider::load`], but always reads the file from disk, and updates the cache with
+    /// what it finds. Sprites which already exist keep their old collider.
+    pub fn reload<P: AsRef<Path>>(collider_filepath: P) -> Self {
+        let collider_filepath = collider_filepath.as_ref();
+        let collider = read_collider_from_file(collider_filepath);
+        Self::cache(collider_filepath, collider.clone());
+        collider
+    }
+    /// Forget all of the colliders which have been loaded, so that each file is read from disk
+    /// again the next time it is loaded.
+    pub fn clear_cache() {
+        *COLLIDER_CACHE
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner) = None;
+    }
+    /// The collider that was loaded from `collider_filepath`, if it has been loaded already
+    pub(crate) fn cached<P: AsRef<Path>>(collider_filepath: P) -> Option<Collider> {
+        COLLIDER_CACHE
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .as_ref()?
+            .get(collider_filepath.as_ref())
+            .cloned()
+    }
+    /// Remember `collider` as the contents of `collider_filepath`
+    pub(crate) fn cache<P: AsRef<Path>>(collider_filepath: P, collider: Collider) {
+        COLLIDER_CACHE
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get_or_insert_with(HashMap::new)
+            .insert(collider_filepath.as_ref().to_path_buf(), collider);
+    }
     /// Generate a rectangular collider based on top-left and bottom-right points
     pub fn rect<T: Into<Vec2>>(topleft: T, bottomright: T) -> Self {
         let topleft = topleft.into();
@@ -312,9 +468,14 @@ impl Collider {
     #[doc(hidden)]
     /// Used internally to scale colliders to match a sprite's current translation, rotation, and scale
     pub fn relative_to(&self, sprite: &Sprite) -> Vec<Vec2> {
-        self.rotated(sprite.rotation)
+        self.transformed(sprite.translation, sprite.rotation, sprite.scale)
+    }
+    #[doc(hidden)]
+    /// Used internally to move colliders to a translation, rotation, and scale in 2D game space
+    pub fn transformed(&self, translation: Vec2, rotation: f32, scale: f32) -> Vec<Vec2> {
+        self.rotated(rotation)
             .iter()
-            .map(|&v| v * sprite.scale + sprite.translation) // scale & translation
+            .map(|&v| v * scale + translation) // scale & translation
             .collect()
     }
     /// Returns a `Vec<Vec2>` containing the points of the collider, or an empty `Vec` if there is
@@ -339,44 +500,49 @@ impl Collider {
         if sprite1.collider.is_poly() && sprite2.collider.is_poly() {
             let poly1 = sprite1.collider.relative_to(sprite1);
             let poly2 = sprite2.collider.relative_to(sprite2);
-            // Polygon intersection algorithm adapted from
-            // https://stackoverflow.com/questions/10962379/how-to-check-intersection-between-2-rotated-rectangles
-            for poly in vec![poly1.clone(), poly2.clone()] {
-                for (idx, &p1) in poly.iter().enumerate() {
-                    let p2 = poly[(idx + 1) % poly.len()];
-                    let normal = Vec2::new(p2.y - p1.y, p1.x - p2.x);
-
-                    let mut min_a = None;
-                    let mut max_a = None;
-                    for &p in poly1.iter() {
-                        let projected = normal.x * p.x + normal.y * p.y;
-                        if min_a.is_none() || projected < min_a.unwrap() {
-                            min_a = Some(projected);
-                        }
-                        if max_a.is_none() || projected > max_a.unwrap() {
-                            max_a = Some(projected);
-                        }
-                    }
+            return polygons_colliding(&poly1, &poly2);
+        }
+        false
+    }
+}
 
-                    let mut min_b = None;
-                    let mut max_b = None;
-                    for &p in poly2.iter() {
-                        let projected = normal.x * p.x + normal.y * p.y;
-                        if min_b.is_none() || projected < min_b.unwrap() {
-                            min_b = Some(projected);
-                        }
-                        if max_b.is_none() || projected > max_b.unwrap() {
-                            max_b = Some(projected);
-                        }
-                    }
+/// Whether two convex polygons overlap
+fn polygons_colliding(poly1: &[Vec2], poly2: &[Vec2]) -> bool {
+    // Polygon intersection algorithm adapted from
+    // https://stackoverflow.com/questions/10962379/how-to-check-intersection-between-2-rotated-rectangles
+    for poly in [poly1, poly2] {
+        for (idx, &p1) in poly.iter().enumerate() {
+            let p2 = poly[(idx + 1) % poly.len()];
+            let normal = Vec2::new(p2.y - p1.y, p1.x - p2.x);
 
-                    if max_a < min_b || max_b < min_a {
-                        return false;
-                    }
+            let mut min_a = None;
+            let mut max_a = None;
+            for &p in poly1.iter() {
+                let projected = normal.x * p.x + normal.y * p.y;
+                if min_a.is_none() || projected < min_a.unwrap() {
+                    min_a = Some(projected);
+                }
+                if max_a.is_none() || projected > max_a.unwrap() {
+                    max_a = Some(projected);
+                }
+            }
+
+            let mut min_b = None;
+            let mut max_b = None;
+            for &p in poly2.iter() {
+                let projected = normal.x * p.x + normal.y * p.y;
+                if min_b.is_none() || projected < min_b.unwrap() {
+                    min_b = Some(projected);
+                }
+                if max_b.is_none() || projected > max_b.unwrap() {
+                    max_b = Some(projected);
                 }
             }
-            return true;
+
+            if max_a < min_b || max_b < min_a {
+                return false;
+            }
         }
-        false
     }
+    true
 }
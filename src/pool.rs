@@ -0,0 +1,130 @@
+//! Facilities for pooling sprites, so that games which constantly create and remove lots of the
+//! same sprite (like bullets) can reuse them instead.
+
+use crate::{sprite::Sprite, traits::EngineEntity};
+
+impl EngineEntity for SpritePool {
+    fn label(&self) -> &str {
+        &self.name
+    }
+}
+
+/// A [`SpritePool`] is a fixed number of identical sprites which are created once and then hidden
+/// and shown again as needed, instead of being created and removed. Create a pool with
+/// [`Engine::pool`](crate::prelude::Engine::pool), take a sprite out of it with
+/// [`Engine::acquire`](crate::prelude::Engine::acquire), and put it back with
+/// [`Engine::release`](crate::prelude::Engine::release).
+///
+/// The sprites in a pool are regular sprites in [`Engine::sprites`](crate::prelude::Engine), with
+/// labels made from the name of the pool and a number (e.g. `bullet#0`, `bullet#1`, ...). While a
+/// sprite is in the pool, its `visible` field is `false`.
+///
+/// ```rust,no_run
+/// # use rusty_engine::prelude::*;
+/// #
+/// # fn main() {
+/// # let mut game = Game::new();
+/// game.pool("bullet", SpritePreset::RollingBallRed, 100);
+/// # game.add_logic(logic);
+/// # game.run(());
+/// # }
+///
+/// fn logic(engine: &mut Engine, _: &mut ()) {
+///     if engine.keyboard_state.just_pressed(KeyCode::Space) {
+///         if let Some(bullet) = engine.acquire("bullet") {
+///             bullet.translation = Vec2::new(0.0, -300.0);
+///         }
+///     }
+///     // Move the bullets that are in use, and put them back in the pool when they are off screen
+///     let mut done = Vec::new();
+///     let bullets = engine
+///         .sprites
+///         .values_mut()
+///         .filter(|sprite| sprite.visible && sprite.label.starts_with("bullet#"));
+///     for bullet in bullets {
+///         bullet.translation.y += 500.0 * engine.delta_f32;
+///         if bullet.translation.y > 400.0 {
+///             done.push(bullet.label.clone());
+///         }
+///     }
+///     for label in done {
+///         engine.release(&label);
+///     }
+/// }
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct SpritePool {
+    /// READONLY: The name of the pool. The labels of the sprites in the pool start with it.
+    pub name: String,
+    /// READONLY: How many sprites are in the pool, whether they are in use or not
+    pub capacity: usize,
+    /// SYNCED: What each sprite is reset to when it is acquired (apart from its label). Change it
+    /// to adjust the scale, layer, collision, etc. of the pool's sprites.
+    pub template: Sprite,
+    // labels of the sprites which are ready to be acquired
+    available: Vec<String>,
+}
+
+impl SpritePool {
+    /// Create a pool named `name` of `capacity` copies of `template`, and return it along with the
+    /// sprites to add to the game. Usually you will want to use
+    /// [`Engine::pool`](crate::prelude::Engine::pool) instead.
+    pub fn new<N: Into<String>>(name: N, template: Sprite, capacity: usize) -> (Self, Vec<Sprite>) {
+        let name = name.into();
+        let mut template = template;
+        template.visible = false;
+        let sprites: Vec<Sprite> = (0..capacity)
+            .map(|index| Sprite {
+                label: format!("{}#{}", name, index),
+                ..template.clone()
+            })
+            .collect();
+        let pool = Self {
+            name,
+            capacity,
+            template,
+            // Acquire the lowest-numbered sprites first
+            available: sprites.iter().rev().map(|s| s.label.clone()).collect(),
+        };
+        (pool, sprites)
+    }
+
+    /// How many sprites are ready to be acquired
+    pub fn available(&self) -> usize {
+        self.available.len()
+    }
+
+    /// Whether the sprite with this label belongs to this pool
+    pub fn contains(&self, label: &str) -> bool {
+        label
+            .strip_prefix(&self.name)
+            .and_then(|rest| rest.strip_prefix('#'))
+            .and_then(|index| index.parse::<usize>().ok())
+            .map_or(false, |index| index < self.capacity)
+    }
+
+    /// Take the label of an available sprite out of the pool
+    pub(crate) fn take(&mut self) -> Option<String> {
+        self.available.pop()
+    }
+
+    /// Put the label of a sprite back into the pool. Returns `false` if it was already there.
+    pub(crate) fn put_back(&mut self, label: &str) -> bool {
+        if self.available.iter().any(|available| available == label) {
+            return false;
+        }
+        self.available.push(label.to_string());
+        true
+    }
+
+    /// Reset a sprite from the pool to look like the pool's template, ready to be used again
+    pub(crate) fn reset(&self, sprite: &mut Sprite) {
+        let collider_dirty = sprite.collider_dirty || sprite.collider != self.template.collider;
+        *sprite = Sprite {
+            label: std::mem::take(&mut sprite.label),
+            visible: true,
+            collider_dirty,
+            ..self.template.clone()
+        };
+    }
+}
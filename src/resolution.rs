@@ -0,0 +1,276 @@
+//! Facilities for running a game at a fixed virtual resolution, so that it lays out the same way no
+//! matter how big the window is.
+
+use crate::{hud::Space, prelude::Engine};
+use bevy::{
+    prelude::{
+        App, Color, Commands, Component, Entity, OrthographicProjection,
+        ParallelSystemDescriptorCoercion, Plugin, Query, Res, Sprite as BevySprite, SpriteBundle,
+        Transform, Vec2, Visibility, With,
+    },
+    render::camera::ScalingMode,
+};
+
+pub(crate) struct ResolutionPlugin;
+
+impl Plugin for ResolutionPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system(sync_letterbox.after("game_logic_sync"));
+    }
+}
+
+/// How a [`VirtualResolution`] is fit into a window that has a different size or shape.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ResolutionMode {
+    /// The default. Scale the game as large as possible while keeping its aspect ratio and
+    /// showing all of it. Any leftover space in the window is covered by borders (letterboxing).
+    Fit,
+    /// Scale the game so that it fills the entire window while keeping its aspect ratio. If the
+    /// window is a different shape than the game, the edges of the game are cut off.
+    Fill,
+    /// Stretch the game to fill the entire window exactly. If the window is a different shape than
+    /// the game, everything will look squashed or stretched.
+    Stretch,
+    /// Like [`Fit`](ResolutionMode::Fit), but only ever scale by a whole number of physical
+    /// pixels, so that pixel art stays crisp. There will usually be borders.
+    PixelPerfect,
+}
+
+impl Default for ResolutionMode {
+    fn default() -> Self {
+        ResolutionMode::Fit
+    }
+}
+
+/// A fixed resolution for your game, independent of the size of the window. Set
+/// [`Engine::virtual_resolution`](crate::prelude::Engine::virtual_resolution) to use one. Game
+/// space, [`Engine::window_dimensions`](crate::prelude::Engine::window_dimensions), and the mouse
+/// location are then all measured in virtual pixels, and the game is scaled to the window
+/// according to the [`ResolutionMode`].
+///
+/// ```rust,no_run
+/// # use rusty_engine::prelude::*;
+/// #
+/// # fn main() {
+/// # let mut game = Game::new();
+/// // Always lay the game out as if the window were 640x360, with black bars if it isn't that shape
+/// game.virtual_resolution = Some(VirtualResolution::new(640.0, 360.0, ResolutionMode::Fit));
+/// # game.run(());
+/// # }
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct VirtualResolution {
+    /// SYNCED: The width and height of the game, in virtual pixels.
+    pub dimensions: Vec2,
+    /// SYNCED: How to fit the game into the window. Defaults to [`ResolutionMode::Fit`].
+    pub mode: ResolutionMode,
+    /// SYNCED: The color of the borders around the game when it doesn't fill the window. Defaults
+    /// to black.
+    pub border_color: Color,
+}
+
+impl Default for VirtualResolution {
+    fn default() -> Self {
+        Self {
+            dimensions: Vec2::new(1280.0, 720.0),
+            mode: ResolutionMode::Fit,
+            border_color: Color::BLACK,
+        }
+    }
+}
+
+impl VirtualResolution {
+    /// Create a virtual resolution of `width` x `height` virtual pixels, fit into the window
+    /// according to `mode`.
+    pub fn new(width: f32, height: f32, mode: ResolutionMode) -> Self {
+        Self {
+            dimensions: Vec2::new(width, height),
+            mode,
+            ..Default::default()
+        }
+    }
+    /// How many logical pixels in a window of the given dimensions (in logical pixels) and scale
+    /// factor (physical pixels per logical pixel) each virtual pixel takes up.
+    fn scale(&self, window_dimensions: Vec2, scale_factor: f32) -> Vec2 {
+        let dimensions = self.dimensions.max(Vec2::ONE);
+        let ratio = window_dimensions / dimensions;
+        match self.mode {
+            ResolutionMode::Fit => Vec2::splat(ratio.min_element()),
+            ResolutionMode::Fill => Vec2::splat(ratio.max_element()),
+            ResolutionMode::Stretch => ratio,
+            ResolutionMode::PixelPerfect => {
+                let physical_ratio = (ratio * scale_factor).min_element();
+                Vec2::splat(physical_ratio.floor().max(1.0) / scale_factor)
+            }
+        }
+        .max(Vec2::splat(f32::EPSILON))
+    }
+}
+
+/// How the window maps onto game space this frame
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) struct ScreenMapping {
+    /// The actual dimensions of the window, in logical pixels
+    pub window_dimensions: Vec2,
+    /// How many logical pixels each virtual pixel takes up
+    pub scale: Vec2,
+    /// The dimensions of the area of the window that the game is shown in, in virtual pixels
+    pub game_dimensions: Vec2,
+    /// The color of the borders around the game area
+    pub border_color: Color,
+}
+
+impl Default for ScreenMapping {
+    fn default() -> Self {
+        Self {
+            window_dimensions: Vec2::ZERO,
+            scale: Vec2::ONE,
+            game_dimensions: Vec2::ZERO,
+            border_color: Color::BLACK,
+        }
+    }
+}
+
+impl ScreenMapping {
+    /// Figure out the mapping for a window with the given dimensions (in logical pixels) and scale
+    /// factor (physical pixels per logical pixel).
+    pub fn new(
+        virtual_resolution: Option<&VirtualResolution>,
+        window_dimensions: Vec2,
+        scale_factor: f32,
+    ) -> Self {
+        match virtual_resolution {
+            Some(virtual_resolution) => {
+                let scale = virtual_resolution.scale(window_dimensions, scale_factor);
+                Self {
+                    window_dimensions,
+                    scale,
+                    game_dimensions: (window_dimensions / scale).min(virtual_resolution.dimensions),
+                    border_color: virtual_resolution.border_color,
+                }
+            }
+            None => Self {
+                window_dimensions,
+                game_dimensions: window_dimensions,
+                ..Default::default()
+            },
+        }
+    }
+    /// The dimensions of the whole window, in virtual pixels. This is larger than the game area
+    /// when there are borders.
+    pub fn visible_dimensions(&self) -> Vec2 {
+        self.window_dimensions / self.scale
+    }
+    /// Convert a location relative to the center of the window in logical pixels into virtual
+    /// pixels.
+    pub fn window_to_virtual(&self, window_location: Vec2) -> Vec2 {
+        window_location / self.scale
+    }
+    /// Make a camera's projection show the whole window, in virtual pixels
+    pub fn apply_to(&self, projection: &mut OrthographicProjection) {
+        // It's possible to not have window dimensions for the first frame or two
+        if self.window_dimensions.min_element() <= 0.0 {
+            return;
+        }
+        let half = self.visible_dimensions() * 0.5;
+        projection.scaling_mode = ScalingMode::None;
+        projection.left = -half.x;
+        projection.right = half.x;
+        projection.bottom = -half.y;
+        projection.top = half.y;
+    }
+}
+
+/// Component to mark the border sprites that cover the parts of the window outside the game area
+#[derive(Component)]
+#[doc(hidden)]
+pub struct LetterboxBar;
+
+/// system - cover everything outside of the game area with borders
+fn sync_letterbox(
+    mut commands: Commands,
+    engine: Res<Engine>,
+    mut bar_query: Query<
+        (Entity, &mut Transform, &mut BevySprite, &mut Visibility),
+        With<LetterboxBar>,
+    >,
+) {
+    let mapping = engine.screen_mapping;
+    let half_visible = mapping.visible_dimensions() * 0.5;
+    let half_game = mapping.game_dimensions * 0.5;
+    // Left, right, bottom, and top bars as (center, size). The side bars cover the corners.
+    let side_width = (half_visible.x - half_game.x).max(0.0);
+    let cap_height = (half_visible.y - half_game.y).max(0.0);
+    let bars = [
+        (
+            Vec2::new(-half_game.x - side_width * 0.5, 0.0),
+            Vec2::new(side_width, half_visible.y * 2.0),
+        ),
+        (
+            Vec2::new(half_game.x + side_width * 0.5, 0.0),
+            Vec2::new(side_width, half_visible.y * 2.0),
+        ),
+        (
+            Vec2::new(0.0, -half_game.y - cap_height * 0.5),
+            Vec2::new(half_game.x * 2.0, cap_height),
+        ),
+        (
+            Vec2::new(0.0, half_game.y + cap_height * 0.5),
+            Vec2::new(half_game.x * 2.0, cap_height),
+        ),
+    ]
+    .map(|(center, size)| {
+        (
+            // On top of everything else in screen space
+            Transform::from_translation(center.extend(999.9)),
+            BevySprite {
+                color: mapping.border_color,
+                custom_size: Some(size),
+                ..Default::default()
+            },
+            Visibility {
+                is_visible: size.x > 0.0 && size.y > 0.0,
+            },
+        )
+    });
+
+    // Don't spawn any bars until the game area doesn't fill the window (with `ResolutionMode::Stretch`
+    // or a window of the right shape, it never needs them)
+    if bar_query.is_empty() && bars.iter().all(|(_, _, visibility)| !visibility.is_visible) {
+        return;
+    }
+
+    let mut existing = bar_query.iter_mut();
+    for (transform, sprite, visibility) in bars {
+        if let Some((_, mut bar_transform, mut bar_sprite, mut bar_visibility)) = existing.next() {
+            // Only touch the bars when they change, so they aren't marked as changed every frame
+            let is_visible = visibility.is_visible;
+            if bar_visibility.is_visible != is_visible {
+                *bar_visibility = visibility;
+            }
+            if !is_visible {
+                continue;
+            }
+            if *bar_transform != transform {
+                *bar_transform = transform;
+            }
+            if bar_sprite.color != sprite.color || bar_sprite.custom_size != sprite.custom_size {
+                *bar_sprite = sprite;
+            }
+        } else {
+            commands
+                .spawn_bundle(SpriteBundle {
+                    sprite,
+                    transform,
+                    visibility,
+                    ..Default::default()
+                })
+                .insert(Space::Screen.render_layers())
+                .insert(LetterboxBar);
+        }
+    }
+    // There should only ever be four bars
+    for (entity, _, _, _) in existing {
+        commands.entity(entity).despawn();
+    }
+}
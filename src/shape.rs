@@ -0,0 +1,264 @@
+//! Facilities for drawing vector shapes: circles, rectangles, polygons, lines, and paths. Shapes
+//! don't need any image files, which makes them handy for prototypes.
+
+use bevy::prelude::{Color, Component, Quat, Transform, Vec2, Vec3};
+use bevy_prototype_lyon::prelude::{DrawMode, FillMode, Path, PathBuilder, StrokeMode};
+use std::f32::consts::{FRAC_PI_2, TAU};
+
+use crate::{
+    hud::{ScreenAnchor, Space},
+    physics::Collider,
+    traits::EngineEntity,
+};
+
+impl EngineEntity for Shape {
+    fn label(&self) -> &str {
+        &self.label
+    }
+}
+
+/// The geometry of a [`Shape`]. All points are relative to the shape's `translation`, before
+/// rotation and scale are applied.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ShapeKind {
+    /// A circle centered on the shape's `translation`
+    Circle { radius: f32 },
+    /// A rectangle centered on the shape's `translation`
+    Rect { size: Vec2 },
+    /// A rectangle with rounded corners centered on the shape's `translation`
+    RoundedRect { size: Vec2, radius: f32 },
+    /// A closed polygon. For collisions to work correctly, the polygon needs to be convex.
+    Polygon { points: Vec<Vec2> },
+    /// A single straight line. Lines are only drawn if the shape has a `stroke_color`.
+    Line { start: Vec2, end: Vec2 },
+    /// A series of connected straight lines, which may be `closed` back to the first point.
+    Path { points: Vec<Vec2>, closed: bool },
+}
+
+impl ShapeKind {
+    /// Build the path that lyon draws
+    pub(crate) fn path(&self) -> Path {
+        let mut path_builder = PathBuilder::new();
+        match self {
+            ShapeKind::Circle { radius } => {
+                path_builder.move_to(Vec2::new(*radius, 0.0));
+                path_builder.arc(Vec2::ZERO, Vec2::splat(*radius), TAU, 0.0);
+                path_builder.close();
+            }
+            ShapeKind::Rect { size } => {
+                let half = *size * 0.5;
+                path_builder.move_to(Vec2::new(-half.x, half.y));
+                path_builder.line_to(Vec2::new(half.x, half.y));
+                path_builder.line_to(Vec2::new(half.x, -half.y));
+                path_builder.line_to(Vec2::new(-half.x, -half.y));
+                path_builder.close();
+            }
+            ShapeKind::RoundedRect { size, radius } => {
+                let half = *size * 0.5;
+                let radius = radius.clamp(0.0, half.min_element());
+                let radii = Vec2::splat(radius);
+                // Go clockwise around the rectangle, starting at the top-left, drawing a quarter
+                // circle at each corner
+                path_builder.move_to(Vec2::new(-half.x + radius, half.y));
+                path_builder.line_to(Vec2::new(half.x - radius, half.y));
+                path_builder.arc(
+                    Vec2::new(half.x - radius, half.y - radius),
+                    radii,
+                    -FRAC_PI_2,
+                    0.0,
+                );
+                path_builder.line_to(Vec2::new(half.x, -half.y + radius));
+                path_builder.arc(
+                    Vec2::new(half.x - radius, -half.y + radius),
+                    radii,
+                    -FRAC_PI_2,
+                    0.0,
+                );
+                path_builder.line_to(Vec2::new(-half.x + radius, -half.y));
+                path_builder.arc(
+                    Vec2::new(-half.x + radius, -half.y + radius),
+                    radii,
+                    -FRAC_PI_2,
+                    0.0,
+                );
+                path_builder.line_to(Vec2::new(-half.x, half.y - radius));
+                path_builder.arc(
+                    Vec2::new(-half.x + radius, half.y - radius),
+                    radii,
+                    -FRAC_PI_2,
+                    0.0,
+                );
+                path_builder.close();
+            }
+            ShapeKind::Polygon { points } => {
+                if let Some((first, rest)) = points.split_first() {
+                    path_builder.move_to(*first);
+                    for point in rest {
+                        path_builder.line_to(*point);
+                    }
+                    path_builder.close();
+                }
+            }
+            ShapeKind::Line { start, end } => {
+                path_builder.move_to(*start);
+                path_builder.line_to(*end);
+            }
+            ShapeKind::Path { points, closed } => {
+                if let Some((first, rest)) = points.split_first() {
+                    path_builder.move_to(*first);
+                    for point in rest {
+                        path_builder.line_to(*point);
+                    }
+                    if *closed {
+                        path_builder.close();
+                    }
+                }
+            }
+        }
+        path_builder.build()
+    }
+
+    /// A collider which matches this shape. Circles and rounded rectangles are approximated with
+    /// polygons, and lines and open paths don't have colliders.
+    pub fn collider(&self) -> Collider {
+        match self {
+            ShapeKind::Circle { radius } => Collider::circle(*radius),
+            ShapeKind::Rect { size } => {
+                let half = *size * 0.5;
+                Collider::rect(Vec2::new(-half.x, half.y), Vec2::new(half.x, -half.y))
+            }
+            ShapeKind::RoundedRect { size, radius } => {
+                let half = *size * 0.5;
+                let radius = radius.clamp(0.0, half.min_element());
+                // Four points around each corner
+                let corners = [
+                    (Vec2::new(half.x - radius, half.y - radius), 0.0),
+                    (Vec2::new(-half.x + radius, half.y - radius), FRAC_PI_2),
+                    (
+                        Vec2::new(-half.x + radius, -half.y + radius),
+                        FRAC_PI_2 * 2.0,
+                    ),
+                    (
+                        Vec2::new(half.x - radius, -half.y + radius),
+                        FRAC_PI_2 * 3.0,
+                    ),
+                ];
+                let mut points = Vec::new();
+                for (center, start_angle) in corners {
+                    for step in 0..4 {
+                        let angle = start_angle + FRAC_PI_2 * step as f32 / 3.0;
+                        let point = center + Vec2::new(angle.cos(), angle.sin()) * radius;
+                        if points.last() != Some(&point) {
+                            points.push(point);
+                        }
+                    }
+                }
+                Collider::Poly(points)
+            }
+            ShapeKind::Polygon { points } => Collider::poly(points),
+            ShapeKind::Path {
+                points,
+                closed: true,
+            } => Collider::poly(points),
+            ShapeKind::Line { .. } | ShapeKind::Path { .. } => Collider::NoCollider,
+        }
+    }
+}
+
+/// A [`Shape`] is a vector shape (a circle, rectangle, polygon, line, etc.) which is drawn with
+/// a fill color and/or an outline. Shapes work just like [`Sprite`](crate::prelude::Sprite)s, but
+/// don't need an image file. Add one with
+/// [`Engine::add_shape`](crate::prelude::Engine::add_shape).
+#[derive(Clone, Component, Debug, PartialEq)]
+pub struct Shape {
+    /// READONLY: A way to identify a shape. This must be unique among shapes.
+    pub label: String,
+    /// SYNCED: The geometry of the shape
+    pub kind: ShapeKind,
+    /// SYNCED: The color to fill the inside of the shape with, or `None` to leave it empty.
+    /// Defaults to white.
+    pub fill_color: Option<Color>,
+    /// SYNCED: The color of the outline of the shape, or `None` for no outline. Defaults to `None`.
+    pub stroke_color: Option<Color>,
+    /// SYNCED: The width of the outline of the shape. Defaults to `2.0`.
+    pub stroke_width: f32,
+    /// SYNCED: Where you are in 2D game space. Positive x is right. Positive y is up. (0.0, 0.0) is
+    /// the center of the screen. If `space` is [`Space::Screen`], this is instead an offset from
+    /// the `anchor` point on the window.
+    pub translation: Vec2,
+    /// SYNCED: Depth of the shape. 0.0 (back) to 999.0 (front)
+    pub layer: f32,
+    /// SYNCED: Direction you face in radians. See constants UP, DOWN, LEFT, RIGHT
+    pub rotation: f32,
+    /// SYNCED: 1.0 is the normal 100%
+    pub scale: f32,
+    /// SYNCED: Whether the shape lives in the game world (the default) or is fixed to the screen
+    /// like a HUD element. See [`Space`].
+    pub space: Space,
+    /// SYNCED: The point on the window that `translation` is relative to. Only used when `space` is
+    /// [`Space::Screen`]. Defaults to [`ScreenAnchor::Center`].
+    pub anchor: ScreenAnchor,
+    /// Whether or not to calculate collisions
+    pub collision: bool,
+    /// The collider for this shape. By default, this is derived from `kind` when the shape is
+    /// created. If you change `kind`, call [`Shape::derive_collider`] to update it.
+    pub collider: Collider,
+}
+
+impl Shape {
+    /// `label` should be a unique string (it will be used as a key in the hashmap
+    /// [`Engine::shapes`](crate::prelude::Engine)). A matching collider is derived from `kind`.
+    pub fn new<L: Into<String>>(label: L, kind: ShapeKind) -> Self {
+        let collider = kind.collider();
+        Self {
+            label: label.into(),
+            kind,
+            fill_color: Some(Color::WHITE),
+            stroke_color: None,
+            stroke_width: 2.0,
+            translation: Vec2::default(),
+            layer: f32::default(),
+            rotation: f32::default(),
+            scale: 1.0,
+            space: Space::World,
+            anchor: ScreenAnchor::Center,
+            collision: false,
+            collider,
+        }
+    }
+
+    /// Replace the collider with one that matches the shape's current `kind`
+    pub fn derive_collider(&mut self) {
+        self.collider = self.kind.collider();
+    }
+
+    /// Do the math to convert from Rusty Engine translation+rotation+scale+layer to Bevy's Transform
+    #[doc(hidden)]
+    pub fn bevy_transform(&self) -> Transform {
+        let mut transform = Transform::from_translation(self.translation.extend(self.layer));
+        transform.rotation = Quat::from_axis_angle(Vec3::Z, self.rotation);
+        transform.scale = Vec3::splat(self.scale);
+        transform
+    }
+
+    /// The lyon path for the shape's geometry
+    #[doc(hidden)]
+    pub fn path(&self) -> Path {
+        self.kind.path()
+    }
+
+    /// How lyon should draw the shape
+    #[doc(hidden)]
+    pub fn draw_mode(&self) -> DrawMode {
+        let stroke_mode = |color| StrokeMode::new(color, self.stroke_width);
+        match (self.fill_color, self.stroke_color) {
+            (Some(fill), Some(stroke)) => DrawMode::Outlined {
+                fill_mode: FillMode::color(fill),
+                outline_mode: stroke_mode(stroke),
+            },
+            (Some(fill), None) => DrawMode::Fill(FillMode::color(fill)),
+            (None, Some(stroke)) => DrawMode::Stroke(stroke_mode(stroke)),
+            (None, None) => DrawMode::Fill(FillMode::color(Color::NONE)),
+        }
+    }
+}
@@ -1,7 +1,13 @@
 /// Sprites are the images that make up a game
-use bevy::prelude::{Component, Quat, Transform, Vec2, Vec3};
+use bevy::prelude::{Color, Component, Quat, Transform, Vec2, Vec3};
 
-use crate::{physics::Collider, traits::EngineEntity};
+use crate::{
+    assets::asset_path,
+    collider_debug::ColliderDebugSettings,
+    hud::{ScreenAnchor, Space},
+    physics::Collider,
+    traits::EngineEntity,
+};
 
 impl EngineEntity for Sprite {
     fn label(&self) -> &str {
@@ -22,7 +28,8 @@ pub struct Sprite {
     /// call the `write_collider` method, the file will be written for you!
     pub collider_filepath: PathBuf,
     /// SYNCED: Where you are in 2D game space. Positive x is right. Positive y is up. (0.0, 0.0) is the
-    /// center of the screen.
+    /// center of the screen. If `space` is [`Space::Screen`], this is instead an offset from the
+    /// `anchor` point on the window.
     pub translation: Vec2,
     /// SYNCED: Depth of the sprite. 0.0 (back) to 999.0 (front)
     pub layer: f32,
@@ -30,6 +37,18 @@ pub struct Sprite {
     pub rotation: f32,
     /// SYNCED: 1.0 is the normal 100%
     pub scale: f32,
+    /// SYNCED: Whether the sprite lives in the game world (the default) or is fixed to the screen
+    /// like a HUD element. See [`Space`].
+    pub space: Space,
+    /// SYNCED: The point on the window that `translation` is relative to. Only used when `space` is
+    /// [`Space::Screen`]. Defaults to [`ScreenAnchor::Center`].
+    pub anchor: ScreenAnchor,
+    /// SYNCED: Whether the sprite is drawn. Hidden sprites don't take part in collisions. Defaults
+    /// to `true`.
+    pub visible: bool,
+    /// SYNCED: The color to tint the image with. White (the default) leaves the image unchanged,
+    /// and a lower alpha makes the sprite translucent.
+    pub color: Color,
     /// Whether or not to calculate collisions
     pub collision: bool,
     /// The actual collider for this sprite
@@ -39,23 +58,10 @@ pub struct Sprite {
     /// if you manually replace a `Sprite`'s [`Collider`] in a game logic function, then you need to
     /// set this to true.
     pub collider_dirty: bool,
-}
-
-/// Reads the collider file and creates the collider
-fn read_collider_from_file(filepath: &Path) -> Collider {
-    match File::open(filepath) {
-        Ok(fh) => match ron::de::from_reader::<_, Collider>(fh) {
-            Ok(collider) => collider,
-            Err(e) => {
-                eprintln!("failed deserializing collider from file: {}", e);
-                Collider::NoCollider
-            }
-        },
-        Err(e) => {
-            eprintln!("failed to open collider file: {}", e);
-            Collider::NoCollider
-        }
-    }
+    /// How to draw this sprite's collider when
+    /// [`Engine.show_colliders`](crate::prelude::Engine) is on, or `None` (the default) to use the
+    /// global [`Engine.collider_debug`](crate::prelude::Engine) settings.
+    pub collider_debug: Option<ColliderDebugSettings>,
 }
 
 impl Sprite {
@@ -63,7 +69,9 @@ impl Sprite {
     /// [`Engine::sprites`](crate::prelude::Engine)). `file_or_preset` should either be a
     /// [`SpritePreset`] variant, or a relative path to an image file inside the `assets/`
     /// directory. If a collider definition exists in a file with the same name as the image file,
-    /// but with the `.collider` extension, then the collider will be loaded automatically. To
+    /// but with the `.collider` extension, then the collider will be loaded automatically. Each
+    /// collider file is only loaded once, in the background: until it has loaded (usually a frame
+    /// or two), the sprite has no collider. Use [`Collider::load`] to read one right away. To
     /// create a collider file you can either run the `collider` example, or
     /// programmatically create a [`Collider`], set the sprite's `.collider` field to it, and call
     /// the sprite's `.write_collider()` method.  All presets have collider files already.
@@ -72,16 +80,8 @@ impl Sprite {
         let filepath = file_or_preset.into();
         let mut collider_filepath = filepath.clone();
         collider_filepath.set_extension("collider");
-        let actual_collider_filepath = PathBuf::from("assets").join(&collider_filepath);
-        let collider = if actual_collider_filepath.exists() {
-            read_collider_from_file(actual_collider_filepath.as_path())
-        } else {
-            eprintln!(
-                "warning: could not find collider file {} -- consider creating one with the `collider` example.",
-                actual_collider_filepath.to_string_lossy()
-            );
-            Collider::NoCollider
-        };
+        // The asset system fills the collider in once the file has loaded
+        let collider = Collider::cached(&collider_filepath).unwrap_or_default();
         Self {
             label,
             filepath,
@@ -90,9 +90,14 @@ impl Sprite {
             layer: f32::default(),
             rotation: f32::default(),
             scale: 1.0,
+            space: Space::World,
+            anchor: ScreenAnchor::Center,
+            visible: true,
+            color: Color::WHITE,
             collision: false,
             collider,
             collider_dirty: true,
+            collider_debug: None,
         }
     }
 
@@ -111,8 +116,8 @@ impl Sprite {
         if self.collider == Collider::NoCollider {
             return false;
         }
-        // Bevy's asset system is relative from the assets/ subdirectory, so we must be too
-        let filepath = PathBuf::from("assets").join(self.collider_filepath.clone());
+        // Bevy's asset system is relative from the asset root, so we must be too
+        let filepath = asset_path(&self.collider_filepath);
         let mut fh = match File::create(filepath) {
             Ok(fh) => fh,
             Err(e) => {
@@ -129,7 +134,11 @@ impl Sprite {
             }
         };
         match fh.write_all(collider_ron.as_bytes()) {
-            Ok(_) => true,
+            Ok(_) => {
+                // Sprites created from now on should get the new collider
+                Collider::cache(&self.collider_filepath, self.collider.clone());
+                true
+            }
             Err(e) => {
                 eprintln!("failed writing collider file: {}", e);
                 false
@@ -188,12 +197,7 @@ impl Sprite {
     }
 }
 
-use std::{
-    array::IntoIter,
-    fs::File,
-    io::Write,
-    path::{Path, PathBuf},
-};
+use std::{array::IntoIter, fs::File, io::Write, path::PathBuf};
 
 /// Sprite presets using the asset pack all have colliders
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
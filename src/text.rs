@@ -1,7 +1,47 @@
 /// Facilities for dealing with text
-use bevy::prelude::{Component, Quat, Transform, Vec2, Vec3};
+use ab_glyph::{Font, FontArc, PxScale, ScaleFont};
+use bevy::{
+    prelude::{
+        App, AssetServer, Color, Commands, Component, CoreStage, Entity, Handle, Local,
+        ParallelSystemDescriptorCoercion, Plugin, Quat, Query, Res, Text as BevyText, Time,
+        Transform, Vec2, Vec3, Visibility, Windows, With, Without,
+    },
+    render::view::RenderLayers,
+    sprite::{SpriteSheetBundle, TextureAtlas, TextureAtlasSprite},
+    text::{
+        update_text2d_layout, DefaultTextPipeline, Text2dBounds, Text2dSize,
+        TextSection as BevyTextSection, TextStyle,
+    },
+    transform::TransformSystem,
+    window::WindowId,
+};
+use std::{
+    collections::HashMap,
+    f32::consts::TAU,
+    sync::{Mutex, PoisonError},
+};
 
-use crate::traits::EngineEntity;
+pub use bevy::text::{HorizontalAlign, VerticalAlign};
+
+use crate::{
+    assets::read_asset,
+    hud::{ScreenAnchor, Space},
+    traits::EngineEntity,
+};
+
+pub(crate) struct TextEffectPlugin;
+
+impl Plugin for TextEffectPlugin {
+    fn build(&self, app: &mut App) {
+        // After Bevy lays out the texts, so the glyphs are where they are this frame
+        app.add_system_to_stage(
+            CoreStage::PostUpdate,
+            sync_text_effects
+                .after(update_text2d_layout)
+                .before(TransformSystem::TransformPropagate),
+        );
+    }
+}
 
 /// Default depth of the text, positioned so it will be on top of other default layers. Depth
 /// can range from `0.0` (back) to `999.0` (front)
@@ -16,6 +56,24 @@ impl EngineEntity for Text {
 }
 
 /// A [`Text`] is a bit of text that exists on the screen.
+///
+/// The text shows `value` in the `font`, `font_size` and `color` of the [`Text`], followed by each
+/// of its `sections`, which can have their own styles. That way, part of the text can stand out:
+///
+/// ```rust,no_run
+/// # use rusty_engine::prelude::*;
+/// #
+/// # fn main() {
+/// # let mut game = Game::new();
+/// let score = game.add_text("score", "Score: ");
+/// score.sections.push(TextSection {
+///     color: Some(Color::YELLOW),
+///     font_size: Some(40.0),
+///     ..TextSection::new("42")
+/// });
+/// # game.run(());
+/// # }
+/// ```
 #[derive(Clone, Component, Debug, PartialEq)]
 pub struct Text {
     /// READONLY: A label to identify the text. This is not the text that is displayed! This is the
@@ -32,8 +90,20 @@ pub struct Text {
     /// the sprites we generate for them get slower to create. Very large sizes will crash. The
     /// default font size is `30.0`.
     pub font_size: f32,
+    /// SYNCED: The color of the text. Defaults to white.
+    pub color: Color,
+    /// SYNCED: More text, displayed right after `value`. Each [`TextSection`] can have its own
+    /// font, font size, and color. Empty by default.
+    pub sections: Vec<TextSection>,
+    /// SYNCED: How the lines of the text are lined up horizontally, and which side of the text
+    /// `translation` is on. Defaults to [`HorizontalAlign::Center`].
+    pub horizontal_align: HorizontalAlign,
+    /// SYNCED: Which side of the text `translation` is on vertically. Defaults to
+    /// [`VerticalAlign::Center`].
+    pub vertical_align: VerticalAlign,
     /// SYNCED: Where you are in 2D game space. Positive x is right. Positive y is up. (0.0, 0.0)
-    /// is the center of the screen.
+    /// is the center of the screen. If `space` is [`Space::Screen`], this is instead an offset from
+    /// the `anchor` point on the window.
     pub translation: Vec2,
     /// SYNCED: Depth of the text. 0.0 (back) to 999.0 (front)  Defaults to [`TEXT_DEFAULT_LAYER`]
     pub layer: f32,
@@ -42,6 +112,32 @@ pub struct Text {
     pub rotation: f32,
     /// SYNCED: `1.0` is the normal 100%.
     pub scale: f32,
+    /// SYNCED: Whether the text lives in the game world (the default) or is fixed to the screen
+    /// like a HUD element. See [`Space`].
+    pub space: Space,
+    /// SYNCED: The point on the window that `translation` is relative to. Only used when `space` is
+    /// [`Space::Screen`]. Defaults to [`ScreenAnchor::Center`].
+    pub anchor: ScreenAnchor,
+    /// SYNCED: If set, lines which are wider than this many pixels are wrapped onto the next line
+    /// (between words, when possible). Defaults to `None`, meaning lines are never wrapped.
+    pub max_width: Option<f32>,
+    /// INFO: The size of the text in pixels, before `scale` is applied, as laid out by Bevy. This
+    /// is `Vec2::ZERO` until the text has been displayed for the first time (usually the frame
+    /// after it is added), and lags a frame behind changes. To know the size of some text before
+    /// adding it, use [`Engine::measure_text`](crate::prelude::Engine::measure_text).
+    pub size: Vec2,
+    /// INFO: The rectangle the text covers, including `scale` but ignoring `rotation`. For texts in
+    /// [`Space::Screen`] it is relative to the center of the window. Like `size`, it lags a frame
+    /// behind changes.
+    pub bounds: TextBounds,
+    /// SYNCED: Set this to reveal the text a few characters at a time, like a typewriter, instead
+    /// of all at once. The characters which haven't been revealed yet are invisible, but still take
+    /// up space, so the text doesn't move around as it is revealed. Defaults to `None`, meaning the
+    /// whole text is shown. See [`TextReveal`].
+    pub reveal: Option<TextReveal>,
+    /// SYNCED: An animation which moves each character on its own, like a wave. Defaults to
+    /// [`TextEffect::None`].
+    pub effect: TextEffect,
 }
 
 impl Default for Text {
@@ -51,10 +147,21 @@ impl Default for Text {
             value: String::default(),
             font: "font/FiraSans-Bold.ttf".to_string(),
             font_size: TEXT_DEFAULT_FONT_SIZE,
+            color: Color::WHITE,
+            sections: Vec::new(),
+            horizontal_align: HorizontalAlign::Center,
+            vertical_align: VerticalAlign::Center,
             translation: Vec2::default(),
             layer: TEXT_DEFAULT_LAYER,
             rotation: f32::default(),
             scale: 1.0,
+            space: Space::World,
+            anchor: ScreenAnchor::Center,
+            max_width: None,
+            size: Vec2::ZERO,
+            bounds: TextBounds::default(),
+            reveal: None,
+            effect: TextEffect::None,
         }
     }
 }
@@ -70,6 +177,110 @@ impl Text {
             ..Default::default()
         }
     }
+    /// How many characters there are in `value` and all of the `sections`
+    pub fn character_count(&self) -> usize {
+        self.value.chars().count()
+            + self
+                .sections
+                .iter()
+                .map(|section| section.value.chars().count())
+                .sum::<usize>()
+    }
+    /// Reveal the rest of the text right away, if it is being revealed
+    pub fn skip_reveal(&mut self) {
+        if let Some(reveal) = self.reveal.as_mut() {
+            reveal.skip();
+        }
+    }
+    /// The Bevy text sections for `value` and `sections`, with the styles of the sections filled in
+    /// from the text's own style. Characters which haven't been revealed yet go in sections of
+    /// their own, which are transparent.
+    #[doc(hidden)]
+    pub fn bevy_sections(&self, asset_server: &AssetServer) -> Vec<BevyTextSection> {
+        let mut styled_values = vec![(
+            &self.value,
+            TextStyle {
+                font: asset_server.load(self.font.as_str()),
+                font_size: self.font_size,
+                color: self.color,
+            },
+        )];
+        for section in &self.sections {
+            let font = section.font.as_ref().unwrap_or(&self.font);
+            styled_values.push((
+                &section.value,
+                TextStyle {
+                    font: asset_server.load(font.as_str()),
+                    font_size: section.font_size.unwrap_or(self.font_size),
+                    color: section.color.unwrap_or(self.color),
+                },
+            ));
+        }
+
+        let mut visible = self
+            .reveal
+            .as_ref()
+            .map_or(usize::MAX, TextReveal::visible_characters);
+        let mut bevy_sections = Vec::new();
+        for (value, style) in styled_values {
+            let count = value.chars().count();
+            if visible >= count {
+                visible -= count;
+                bevy_sections.push(BevyTextSection {
+                    value: value.clone(),
+                    style,
+                });
+                continue;
+            }
+            let split = value
+                .char_indices()
+                .nth(visible)
+                .map_or(value.len(), |(index, _)| index);
+            visible = 0;
+            let mut hidden_style = style.clone();
+            hidden_style.color.set_a(0.0);
+            bevy_sections.push(BevyTextSection {
+                value: value[..split].to_string(),
+                style,
+            });
+            bevy_sections.push(BevyTextSection {
+                value: value[split..].to_string(),
+                style: hidden_style,
+            });
+        }
+        bevy_sections
+    }
+    /// The Bevy bounds that make the text wrap at `max_width`
+    #[doc(hidden)]
+    pub fn bevy_text_2d_bounds(&self) -> Text2dBounds {
+        Text2dBounds {
+            size: Vec2::new(self.max_width.unwrap_or(f32::MAX), f32::MAX),
+        }
+    }
+    /// Update `size` and `bounds` from the size Bevy laid the text out at, and the transform it is
+    /// displayed with
+    pub(crate) fn update_layout(&mut self, size: Vec2, transform: &Transform) {
+        self.size = size;
+        let scaled_size = size * transform.scale.truncate();
+        let translation = transform.translation.truncate();
+        // How far the text extends to the left of and below its translation
+        let offset = Vec2::new(
+            match self.horizontal_align {
+                HorizontalAlign::Left => 0.0,
+                HorizontalAlign::Center => 0.5,
+                HorizontalAlign::Right => 1.0,
+            },
+            match self.vertical_align {
+                VerticalAlign::Top => 1.0,
+                VerticalAlign::Center => 0.5,
+                VerticalAlign::Bottom => 0.0,
+            },
+        ) * scaled_size;
+        self.bounds = TextBounds {
+            min: translation - offset,
+            max: translation - offset + scaled_size,
+        };
+    }
     #[doc(hidden)]
     pub fn bevy_transform(&self) -> Transform {
         let mut transform = Transform::from_translation(self.translation.extend(self.layer));
@@ -78,3 +289,413 @@ impl Text {
         transform
     }
 }
+
+/// A [`TextSection`] is a part of a [`Text`] with its own style. Any style that is `None` is the
+/// same as the [`Text`] it is in.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TextSection {
+    /// SYNCED: The text value of this section
+    pub value: String,
+    /// SYNCED: The font of this section, if it is different from the [`Text`]'s font
+    pub font: Option<String>,
+    /// SYNCED: The font size of this section, if it is different from the [`Text`]'s font size
+    pub font_size: Option<f32>,
+    /// SYNCED: The color of this section, if it is different from the [`Text`]'s color
+    pub color: Option<Color>,
+}
+
+impl TextSection {
+    /// Create a section with the same style as the [`Text`] it is added to
+    pub fn new<S: Into<String>>(value: S) -> Self {
+        Self {
+            value: value.into(),
+            ..Default::default()
+        }
+    }
+}
+
+impl From<&str> for TextSection {
+    fn from(value: &str) -> Self {
+        Self::new(value)
+    }
+}
+
+impl From<String> for TextSection {
+    fn from(value: String) -> Self {
+        Self::new(value)
+    }
+}
+
+/// The rectangle covered by a [`Text`]. See [`Text::bounds`].
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct TextBounds {
+    /// The bottom-left corner
+    pub min: Vec2,
+    /// The top-right corner
+    pub max: Vec2,
+}
+
+impl TextBounds {
+    /// The width and height of the rectangle
+    pub fn size(&self) -> Vec2 {
+        self.max - self.min
+    }
+    /// The center of the rectangle
+    pub fn center(&self) -> Vec2 {
+        (self.min + self.max) * 0.5
+    }
+    /// Whether `point` is inside the rectangle, e.g. to check if the mouse is over the text
+    pub fn contains(&self, point: Vec2) -> bool {
+        point.cmpge(self.min).all() && point.cmple(self.max).all()
+    }
+}
+
+/// Fonts which have already been read for measuring text, by path. Fonts which fail to load are
+/// remembered as `None`, so that their error is only printed once.
+static FONT_CACHE: Mutex<Option<HashMap<String, Option<FontArc>>>> = Mutex::new(None);
+
+/// The size in pixels that `value` would have if it were displayed in `font` at `font_size`,
+/// without any wrapping. Used by [`Engine::measure_text`](crate::prelude::Engine::measure_text).
+pub(crate) fn measure_text(value: &str, font: &str, font_size: f32) -> Vec2 {
+    let mut cache = FONT_CACHE.lock().unwrap_or_else(PoisonError::into_inner);
+    let font_arc = cache
+        .get_or_insert_with(HashMap::new)
+        .entry(font.to_string())
+        .or_insert_with(|| {
+            let loaded = read_asset(font)
+                .map_err(|e| e.to_string())
+                .and_then(|bytes| FontArc::try_from_vec(bytes).map_err(|e| e.to_string()));
+            match loaded {
+                Ok(font_arc) => Some(font_arc),
+                Err(e) => {
+                    eprintln!("failed to load font {} for measuring text: {}", font, e);
+                    None
+                }
+            }
+        });
+    let scaled_font = match font_arc {
+        Some(font_arc) => font_arc.as_scaled(PxScale::from(font_size)),
+        None => return Vec2::ZERO,
+    };
+
+    // Lay the text out the same way Bevy does: each line is as wide as the advances of its glyphs,
+    // and lines are stacked `line_gap` apart.
+    let mut width: f32 = 0.0;
+    let mut line_count = 0;
+    for line in value.lines() {
+        let mut line_width = 0.0;
+        let mut previous = None;
+        for character in line.chars() {
+            let glyph_id = scaled_font.glyph_id(character);
+            if let Some(previous) = previous {
+                line_width += scaled_font.kern(previous, glyph_id);
+            }
+            line_width += scaled_font.h_advance(glyph_id);
+            previous = Some(glyph_id);
+        }
+        width = width.max(line_width);
+        line_count += 1;
+    }
+    if line_count == 0 {
+        return Vec2::ZERO;
+    }
+    let height =
+        scaled_font.height() * line_count as f32 + scaled_font.line_gap() * (line_count - 1) as f32;
+    Vec2::new(width, height)
+}
+
+/// Reveals a [`Text`] or [`BitmapText`](crate::prelude::BitmapText) a few characters at a time,
+/// like a typewriter. When all of the characters have been revealed, a
+/// [`TextEvent::RevealFinished`] is added to
+/// [`Engine::text_events`](crate::prelude::Engine::text_events).
+///
+/// If characters are added to the end of a text that has been revealed, they are revealed too, so
+/// you can keep adding lines to the same text. To reveal a new value from the beginning, set
+/// `reveal` to a new [`TextReveal`].
+///
+/// ```rust,no_run
+/// # use rusty_engine::prelude::*;
+/// #
+/// # fn main() {
+/// # let mut game = Game::new();
+/// let dialog = game.add_text("dialog", "It was a dark and stormy night...");
+/// dialog.reveal = Some(TextReveal::new(20.0));
+/// # game.add_logic(logic);
+/// # game.run(());
+/// # }
+///
+/// fn logic(engine: &mut Engine, _: &mut ()) {
+///     if engine.keyboard_state.just_pressed(KeyCode::Space) {
+///         engine.texts.get_mut("dialog").unwrap().skip_reveal();
+///     }
+/// }
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct TextReveal {
+    /// SYNCED: How many characters are revealed each second
+    pub rate: f32,
+    /// SYNCED: How many characters have been revealed so far. This goes up by `rate` every second.
+    /// Change it to jump ahead or go back.
+    pub revealed: f32,
+    /// SYNCED: While `true`, no more characters are revealed
+    pub paused: bool,
+    // whether the completion event has been sent for the current text
+    finished: bool,
+}
+
+impl TextReveal {
+    /// Start revealing a text from the beginning, `rate` characters per second
+    pub fn new(rate: f32) -> Self {
+        Self {
+            rate,
+            revealed: 0.0,
+            paused: false,
+            finished: false,
+        }
+    }
+    /// Reveal all the characters right away. The [`TextEvent::RevealFinished`] event is still sent.
+    pub fn skip(&mut self) {
+        self.revealed = f32::INFINITY;
+    }
+    /// How many whole characters have been revealed
+    pub fn visible_characters(&self) -> usize {
+        // Saturates, so `skip` reveals everything
+        self.revealed.max(0.0) as usize
+    }
+    /// Whether all the characters of the text were revealed, as of the start of this frame
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+    /// Reveal more characters of a text with `character_count` characters. Returns `true` on the
+    /// frame that the whole text has been revealed.
+    pub(crate) fn advance(&mut self, delta: f32, character_count: usize) -> bool {
+        if !self.paused {
+            self.revealed += self.rate * delta;
+        }
+        let all_revealed = self.revealed >= character_count as f32;
+        let just_finished = all_revealed && !self.finished;
+        self.finished = all_revealed;
+        just_finished
+    }
+}
+
+/// An animation of the characters of a [`Text`] or [`BitmapText`](crate::prelude::BitmapText),
+/// which is driven by the time since the game started. Offsets are in pixels before the text's
+/// `scale` is applied, so they grow and turn with the text.
+///
+/// A [`Text`] with an effect draws each of its characters as a sprite of its own, instead of
+/// letting Bevy draw the whole text at once.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TextEffect {
+    /// The characters stay where they are
+    None,
+    /// The characters bob up and down `amplitude` pixels, `speed` times a second, each a little
+    /// behind the one before it
+    Wave { amplitude: f32, speed: f32 },
+    /// The characters jitter up to `intensity` pixels in random directions
+    Shake { intensity: f32 },
+}
+
+impl Default for TextEffect {
+    fn default() -> Self {
+        Self::None
+    }
+}
+
+impl TextEffect {
+    /// How far the character at `character_index` is moved at `time` seconds since startup
+    pub(crate) fn offset(&self, character_index: usize, time: f32) -> Vec2 {
+        match *self {
+            TextEffect::None => Vec2::ZERO,
+            TextEffect::Wave { amplitude, speed } => {
+                let phase = TAU * speed * time - character_index as f32 * 0.5;
+                Vec2::new(0.0, amplitude * phase.sin())
+            }
+            TextEffect::Shake { intensity } => {
+                // A new random offset for each character, 30 times a second
+                let seed =
+                    (character_index as u32).wrapping_mul(0x9e37_79b9) ^ (time * 30.0) as u32;
+                Vec2::new(noise(seed), noise(seed ^ 0x68e3_1da4)) * intensity
+            }
+        }
+    }
+}
+
+/// A cheap pseudo-random number from -1.0 to 1.0, which is always the same for the same `seed`
+fn noise(seed: u32) -> f32 {
+    let mut x = seed;
+    x ^= x >> 16;
+    x = x.wrapping_mul(0x7feb_352d);
+    x ^= x >> 15;
+    x = x.wrapping_mul(0x846c_a68b);
+    x ^= x >> 16;
+    x as f32 / u32::MAX as f32 * 2.0 - 1.0
+}
+
+/// Marks the sprite of one glyph of a [`Text`] with a [`TextEffect`]
+#[derive(Component)]
+#[doc(hidden)]
+pub struct TextGlyph;
+
+/// The glyph sprites of a text with an effect, and the render layers they were put on
+struct TextEffectGlyphs {
+    entities: Vec<Entity>,
+    render_layers: RenderLayers,
+}
+
+/// system - draw the glyphs of the texts with an effect as sprites of their own, moved by the
+/// effect. The glyphs come from the layout and font atlas Bevy made for the text, which is hidden.
+#[allow(clippy::type_complexity, clippy::too_many_arguments)]
+fn sync_text_effects(
+    mut commands: Commands,
+    time: Res<Time>,
+    windows: Res<Windows>,
+    text_pipeline: Res<DefaultTextPipeline>,
+    mut text_effect_glyphs: Local<HashMap<Entity, TextEffectGlyphs>>,
+    mut text_query: Query<(
+        Entity,
+        &Text,
+        &BevyText,
+        &Text2dSize,
+        &Transform,
+        &mut Visibility,
+        &RenderLayers,
+    )>,
+    mut glyph_query: Query<
+        (
+            &mut Transform,
+            &mut TextureAtlasSprite,
+            &mut Handle<TextureAtlas>,
+        ),
+        (With<TextGlyph>, Without<Text>),
+    >,
+) {
+    let time = time.seconds_since_startup() as f32;
+    let scale_factor = windows.scale_factor(WindowId::primary()) as f32;
+
+    // Remove the glyphs of texts which were removed, or which no longer have an effect
+    text_effect_glyphs.retain(|entity, glyphs| {
+        let keep = matches!(
+            text_query.get(*entity),
+            Ok((_, text, ..)) if text.effect != TextEffect::None
+        );
+        if !keep {
+            for glyph_entity in glyphs.entities.drain(..) {
+                commands.entity(glyph_entity).despawn();
+            }
+        }
+        keep
+    });
+
+    for (entity, text, bevy_text, text_2d_size, transform, mut visibility, render_layers) in
+        text_query.iter_mut()
+    {
+        // Bevy only draws the text itself when its glyphs aren't being moved
+        let is_visible = text.effect == TextEffect::None;
+        if visibility.is_visible != is_visible {
+            visibility.is_visible = is_visible;
+        }
+        if is_visible {
+            continue;
+        }
+        // Nothing to draw until the font has loaded and the text has been laid out
+        let layout = match text_pipeline.get_glyphs(&entity) {
+            Some(layout) => layout,
+            None => continue,
+        };
+        let glyphs = text_effect_glyphs
+            .entry(entity)
+            .or_insert_with(|| TextEffectGlyphs {
+                entities: Vec::new(),
+                render_layers: *render_layers,
+            });
+        if glyphs.render_layers != *render_layers {
+            for &glyph_entity in &glyphs.entities {
+                commands.entity(glyph_entity).insert(*render_layers);
+            }
+            glyphs.render_layers = *render_layers;
+        }
+        // The text may have fewer glyphs than last frame
+        let glyph_count = layout.glyphs.len().min(glyphs.entities.len());
+        for glyph_entity in glyphs.entities.drain(glyph_count..) {
+            commands.entity(glyph_entity).despawn();
+        }
+
+        // Place the glyphs the same way Bevy does when it draws the text: the layout is in physical
+        // pixels, relative to the corner of the text that its alignment puts at `translation`
+        let size = text_2d_size.size;
+        let alignment_offset = Vec2::new(
+            match text.horizontal_align {
+                HorizontalAlign::Left => 0.0,
+                HorizontalAlign::Center => -0.5 * size.x,
+                HorizontalAlign::Right => -size.x,
+            },
+            match text.vertical_align {
+                VerticalAlign::Top => -size.y,
+                VerticalAlign::Center => -0.5 * size.y,
+                VerticalAlign::Bottom => 0.0,
+            },
+        );
+        let physical_transform =
+            transform.mul_transform(Transform::from_scale(Vec3::splat(scale_factor.recip())));
+        for (character_index, positioned_glyph) in layout.glyphs.iter().enumerate() {
+            let offset = alignment_offset + text.effect.offset(character_index, time);
+            let glyph_transform = physical_transform.mul_transform(Transform::from_translation(
+                (offset * scale_factor + positioned_glyph.position).extend(0.0),
+            ));
+            let index = positioned_glyph.atlas_info.glyph_index;
+            let color = bevy_text.sections[positioned_glyph.section_index]
+                .style
+                .color;
+            let texture_atlas = &positioned_glyph.atlas_info.texture_atlas;
+            match glyphs.entities.get(character_index) {
+                Some(&glyph_entity) => {
+                    if let Ok((mut transform, mut sprite, mut atlas)) =
+                        glyph_query.get_mut(glyph_entity)
+                    {
+                        *transform = glyph_transform;
+                        sprite.index = index;
+                        sprite.color = color;
+                        if *atlas != *texture_atlas {
+                            *atlas = texture_atlas.clone();
+                        }
+                    }
+                }
+                None => {
+                    let glyph_entity = commands
+                        .spawn_bundle(SpriteSheetBundle {
+                            sprite: TextureAtlasSprite {
+                                index,
+                                color,
+                                ..Default::default()
+                            },
+                            texture_atlas: texture_atlas.clone(),
+                            transform: glyph_transform,
+                            // Transforms have already been propagated by the time new entities
+                            // appear, so start out in the right place
+                            global_transform: glyph_transform.into(),
+                            ..Default::default()
+                        })
+                        .insert(TextGlyph)
+                        .insert(*render_layers)
+                        .id();
+                    glyphs.entities.push(glyph_entity);
+                }
+            }
+        }
+    }
+}
+
+/// Events about texts, which are found in
+/// [`Engine::text_events`](crate::prelude::Engine::text_events)
+#[derive(Clone, Debug, PartialEq)]
+pub enum TextEvent {
+    /// All of the characters of the [`Text`] or [`BitmapText`](crate::prelude::BitmapText) with
+    /// this label have been revealed. See [`TextReveal`].
+    RevealFinished { label: String },
+    /// The player pressed `Enter` in the [`TextInput`](crate::prelude::TextInput) with this label.
+    /// `value` is what they typed.
+    Submitted { label: String, value: String },
+    /// The player pressed `Escape` in the [`TextInput`](crate::prelude::TextInput) with this label
+    Cancelled { label: String },
+}
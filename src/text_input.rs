@@ -0,0 +1,368 @@
+//! Facilities for letting the player type text, like their name or high-score initials
+use bevy::{
+    input::ButtonState,
+    prelude::{Color, KeyCode},
+};
+use std::ops::Range;
+
+use crate::{
+    keyboard::{KeyboardInput, KeyboardState, ReceivedCharacter},
+    prelude::Engine,
+    text::{Text, TextEvent, TextSection},
+    traits::EngineEntity,
+};
+
+impl EngineEntity for TextInput {
+    fn label(&self) -> &str {
+        &self.label
+    }
+}
+
+/// A [`TextInput`] is a field that the player can type into. It is displayed with a [`Text`] which
+/// has the same label, which the engine keeps up to date with the `value`, the cursor, and the
+/// selection. Change the look of the field with the `text` field rather than the [`Text`] in
+/// [`Engine::texts`](crate::prelude::Engine::texts).
+///
+/// While the input is `focused`, typed characters are inserted at the cursor, and these keys work
+/// the way they do in other text fields:
+///
+/// - `Backspace` and `Delete` remove the selection, or the character before or after the cursor
+/// - `Left`, `Right`, `Home` and `End` move the cursor, and select text while `Shift` is held
+/// - `Ctrl+A` selects everything
+/// - `Enter` sends a [`TextEvent::Submitted`] and `Escape` sends a [`TextEvent::Cancelled`] to
+///   [`Engine::text_events`](crate::prelude::Engine::text_events). While any text input is focused,
+///   `Escape` doesn't close the game.
+///
+/// ```rust,no_run
+/// # use rusty_engine::prelude::*;
+/// #
+/// # fn main() {
+/// # let mut game = Game::new();
+/// let initials = game.add_text_input("initials");
+/// initials.max_length = Some(3);
+/// initials.filter = Some(|character| character.is_ascii_alphabetic());
+/// initials.placeholder = "AAA".to_string();
+/// initials.text.font_size = 60.0;
+/// # game.run(());
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct TextInput {
+    /// READONLY: A label to identify the text input. This is also the label of the [`Text`] it is
+    /// displayed with, so it must not be the same as the label of any other text.
+    pub label: String,
+    /// SYNCED: What has been typed into the field
+    pub value: String,
+    /// SYNCED: How the field looks: the font, font size, color, translation, etc. The `value` and
+    /// `sections` of this [`Text`] are replaced every frame to show the input's `value`. Its
+    /// INFO fields (`size` and `bounds`) are kept up to date, so you can use them to tell whether
+    /// the mouse is over the field.
+    pub text: Text,
+    /// SYNCED: Whether the field receives the keys the player presses. Only focused fields show
+    /// their cursor. Defaults to `true`.
+    pub focused: bool,
+    /// SYNCED: Where the cursor is, as the number of characters before it
+    pub cursor: usize,
+    /// SYNCED: If text is selected, the other end of the selection (the cursor is one end).
+    /// Defaults to `None`.
+    pub selection_anchor: Option<usize>,
+    /// SYNCED: The most characters the field can hold. Defaults to `None`, meaning no limit.
+    pub max_length: Option<usize>,
+    /// SYNCED: If set, only characters that this function returns `true` for can be typed into the
+    /// field. Defaults to `None`, meaning any character that isn't a control character.
+    pub filter: Option<fn(char) -> bool>,
+    /// SYNCED: What the field shows (faded out) while `value` is empty. Defaults to an empty
+    /// string.
+    pub placeholder: String,
+    /// SYNCED: The color of the selected text. Defaults to a light blue.
+    pub selection_color: Color,
+    // when the cursor last moved, so it is always visible right after typing
+    blink_start: f64,
+}
+
+impl TextInput {
+    pub fn new<L: Into<String>>(label: L) -> Self {
+        let label = label.into();
+        Self {
+            text: Text::new(label.clone(), ""),
+            label,
+            value: String::new(),
+            focused: true,
+            cursor: 0,
+            selection_anchor: None,
+            max_length: None,
+            filter: None,
+            placeholder: String::new(),
+            selection_color: Color::rgb(0.4, 0.7, 1.0),
+            blink_start: 0.0,
+        }
+    }
+    /// The range of characters that are selected, if any
+    pub fn selection(&self) -> Option<Range<usize>> {
+        let anchor = self.selection_anchor?;
+        let cursor = self.clamped_cursor();
+        let anchor = anchor.min(self.character_count());
+        match anchor.cmp(&cursor) {
+            std::cmp::Ordering::Less => Some(anchor..cursor),
+            std::cmp::Ordering::Equal => None,
+            std::cmp::Ordering::Greater => Some(cursor..anchor),
+        }
+    }
+    /// The text that is selected. Empty if nothing is selected.
+    pub fn selected_text(&self) -> &str {
+        match self.selection() {
+            Some(selection) => &self.value[self.byte_range(selection)],
+            None => "",
+        }
+    }
+    /// Select everything in the field, with the cursor at the end
+    pub fn select_all(&mut self) {
+        self.selection_anchor = Some(0);
+        self.cursor = self.character_count();
+    }
+    /// Replace everything in the field, moving the cursor to the end
+    pub fn set_value<S: Into<String>>(&mut self, value: S) {
+        self.value = value.into();
+        self.cursor = self.character_count();
+        self.selection_anchor = None;
+    }
+    /// Type `value` at the cursor, replacing the selection. Characters which don't pass the
+    /// `filter` are left out, as are any that don't fit in `max_length`.
+    pub fn insert(&mut self, value: &str) {
+        let filter = self.filter;
+        let allowed: Vec<char> = value
+            .chars()
+            .filter(|character| !character.is_control())
+            .filter(|&character| filter.map_or(true, |filter| filter(character)))
+            .collect();
+        // Typing a character that isn't allowed leaves the selection alone
+        if allowed.is_empty() {
+            return;
+        }
+        self.delete_selection();
+        let room = self.max_length.map_or(usize::MAX, |max_length| {
+            max_length.saturating_sub(self.character_count())
+        });
+        let inserted: String = allowed.into_iter().take(room).collect();
+        let index = self.byte_index(self.clamped_cursor());
+        self.value.insert_str(index, &inserted);
+        self.cursor = self.clamped_cursor() + inserted.chars().count();
+    }
+    /// Remove the selected text, if any. Returns whether anything was removed.
+    pub fn delete_selection(&mut self) -> bool {
+        let selection = self.selection();
+        self.selection_anchor = None;
+        match selection {
+            Some(selection) => {
+                self.cursor = selection.start;
+                let range = self.byte_range(selection);
+                self.value.replace_range(range, "");
+                true
+            }
+            None => false,
+        }
+    }
+    /// How many characters are in the field
+    fn character_count(&self) -> usize {
+        self.value.chars().count()
+    }
+    /// The cursor, kept inside the value in case the value was changed without moving it
+    fn clamped_cursor(&self) -> usize {
+        self.cursor.min(self.character_count())
+    }
+    /// The byte index in `value` of the character at `character_index`
+    fn byte_index(&self, character_index: usize) -> usize {
+        self.value
+            .char_indices()
+            .nth(character_index)
+            .map_or(self.value.len(), |(index, _)| index)
+    }
+    fn byte_range(&self, range: Range<usize>) -> Range<usize> {
+        self.byte_index(range.start)..self.byte_index(range.end)
+    }
+    /// Move the cursor, either selecting the text it moves over or clearing the selection
+    fn move_cursor(&mut self, to: usize, select: bool) {
+        if select {
+            if self.selection_anchor.is_none() {
+                self.selection_anchor = Some(self.clamped_cursor());
+            }
+        } else {
+            self.selection_anchor = None;
+        }
+        self.cursor = to.min(self.character_count());
+    }
+    /// Apply the keys and characters the player typed this frame, returning a submit or cancel
+    /// event if they pressed `Enter` or `Escape`
+    fn handle_input(
+        &mut self,
+        keyboard_events: &[KeyboardInput],
+        keyboard_state: &KeyboardState,
+        received_character_events: &[ReceivedCharacter],
+        time: f64,
+    ) -> Vec<TextEvent> {
+        let mut text_events = Vec::new();
+        let old = (self.value.clone(), self.cursor, self.selection_anchor);
+        let shift = keyboard_state.pressed_any(&[KeyCode::LShift, KeyCode::RShift]);
+        let control = keyboard_state.pressed_any(&[KeyCode::LControl, KeyCode::RControl]);
+
+        let typed: String = received_character_events
+            .iter()
+            .map(|event| event.char)
+            .collect();
+        if !typed.is_empty() && !control {
+            self.insert(&typed);
+        }
+
+        // Pressed events repeat while a key is held down, just like typed characters
+        let pressed_keys = keyboard_events
+            .iter()
+            .filter(|event| event.state == ButtonState::Pressed)
+            .filter_map(|event| event.key_code);
+        for key_code in pressed_keys {
+            let cursor = self.clamped_cursor();
+            match key_code {
+                KeyCode::Back => {
+                    if !self.delete_selection() && cursor > 0 {
+                        let range = self.byte_range(cursor - 1..cursor);
+                        self.value.replace_range(range, "");
+                        self.cursor = cursor - 1;
+                    }
+                }
+                KeyCode::Delete => {
+                    if !self.delete_selection() && cursor < self.character_count() {
+                        let range = self.byte_range(cursor..cursor + 1);
+                        self.value.replace_range(range, "");
+                    }
+                }
+                KeyCode::Left => match self.selection() {
+                    Some(selection) if !shift => self.move_cursor(selection.start, false),
+                    _ => self.move_cursor(cursor.saturating_sub(1), shift),
+                },
+                KeyCode::Right => match self.selection() {
+                    Some(selection) if !shift => self.move_cursor(selection.end, false),
+                    _ => self.move_cursor(cursor + 1, shift),
+                },
+                KeyCode::Home => self.move_cursor(0, shift),
+                KeyCode::End => self.move_cursor(self.character_count(), shift),
+                KeyCode::A if control => self.select_all(),
+                KeyCode::Return | KeyCode::NumpadEnter => {
+                    text_events.push(TextEvent::Submitted {
+                        label: self.label.clone(),
+                        value: self.value.clone(),
+                    });
+                }
+                KeyCode::Escape => {
+                    text_events.push(TextEvent::Cancelled {
+                        label: self.label.clone(),
+                    });
+                }
+                _ => {}
+            }
+        }
+
+        // Keep the cursor showing while the player is typing or moving it around
+        if (self.value.clone(), self.cursor, self.selection_anchor) != old {
+            self.blink_start = time;
+        }
+        text_events
+    }
+    /// The [`Text`] that shows the field, with its cursor and selection
+    fn display_text(&self, time: f64) -> Text {
+        let mut text = self.text.clone();
+        text.label = self.label.clone();
+        text.sections.clear();
+        // A cursor which is blinked off is transparent, so the text doesn't move around
+        let cursor_visible = ((time - self.blink_start) * 2.0) as u64 % 2 == 0;
+        let mut cursor_color = text.color;
+        if !cursor_visible {
+            cursor_color.set_a(0.0);
+        }
+        let cursor_section = TextSection {
+            color: Some(cursor_color),
+            ..TextSection::new("|")
+        };
+
+        if self.value.is_empty() {
+            text.value = String::new();
+            if self.focused {
+                text.sections.push(cursor_section);
+            }
+            let mut placeholder_color = text.color;
+            placeholder_color.set_a(text.color.a() * 0.5);
+            text.sections.push(TextSection {
+                color: Some(placeholder_color),
+                ..TextSection::new(self.placeholder.as_str())
+            });
+            return text;
+        }
+
+        let cursor = self.clamped_cursor();
+        let selection = self.selection().unwrap_or(cursor..cursor);
+        let range = self.byte_range(selection.clone());
+        text.value = self.value[..range.start].to_string();
+        let selected = TextSection {
+            color: Some(self.selection_color),
+            ..TextSection::new(&self.value[range.clone()])
+        };
+        if !self.focused {
+            text.sections.push(selected);
+        } else if cursor == selection.start {
+            text.sections.push(cursor_section);
+            text.sections.push(selected);
+        } else {
+            text.sections.push(selected);
+            text.sections.push(cursor_section);
+        }
+        text.sections
+            .push(TextSection::new(&self.value[range.end..]));
+        text
+    }
+}
+
+/// Apply this frame's keyboard input to the focused text inputs. Their `text` picks up the size
+/// and bounds that their [`Text`] was laid out at.
+pub(crate) fn update_text_inputs(engine: &mut Engine) {
+    let Engine {
+        text_inputs,
+        texts,
+        keyboard_events,
+        keyboard_state,
+        received_character_events,
+        text_events,
+        time_since_startup_f64,
+        ..
+    } = engine;
+    for text_input in text_inputs.values_mut() {
+        if let Some(text) = texts.get(&text_input.label) {
+            text_input.text.size = text.size;
+            text_input.text.bounds = text.bounds;
+        }
+        if text_input.focused {
+            text_events.extend(text_input.handle_input(
+                keyboard_events,
+                keyboard_state,
+                received_character_events,
+                *time_since_startup_f64,
+            ));
+        }
+    }
+}
+
+/// Replace the [`Text`]s of the text inputs with what they should show this frame, removing the
+/// ones whose text input was removed
+pub(crate) fn sync_text_input_texts(engine: &mut Engine) {
+    let mut text_input_labels = std::mem::take(&mut engine.text_input_labels);
+    for label in text_input_labels.drain(..) {
+        if !engine.text_inputs.contains_key(&label) {
+            engine.texts.remove(&label);
+        }
+    }
+    let time = engine.time_since_startup_f64;
+    for text_input in engine.text_inputs.values() {
+        engine
+            .texts
+            .insert(text_input.label.clone(), text_input.display_text(time));
+        text_input_labels.push(text_input.label.clone());
+    }
+    engine.text_input_labels = text_input_labels;
+}
@@ -0,0 +1,842 @@
+//! Facilities for tilemaps: big grids of tiles drawn from a single tileset image, which are drawn
+//! all at once and can take part in collisions.
+
+use crate::{
+    assets::{asset_path, read_asset},
+    physics::Collider,
+    prelude::Engine,
+    traits::EngineEntity,
+};
+use bevy::{
+    prelude::{
+        App, AssetServer, Assets, Color, ColorMaterial, Commands, Component, Entity, Handle, Local,
+        Mesh, ParallelSystemDescriptorCoercion, Plugin, Query, Res, ResMut, Transform, Vec2, Vec3,
+        With,
+    },
+    render::{mesh::Indices, render_resource::PrimitiveTopology},
+    sprite::{MaterialMesh2dBundle, Mesh2dHandle},
+    utils::HashMap,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    path::{Component as PathComponent, Path, PathBuf},
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+pub(crate) struct TilemapPlugin;
+
+/// Hands out a new number every time the solid tiles of any tilemap change, so that cached
+/// colliders can tell when they are out of date
+static NEXT_SOLID_VERSION: AtomicU64 = AtomicU64::new(0);
+
+fn next_solid_version() -> u64 {
+    NEXT_SOLID_VERSION.fetch_add(1, Ordering::Relaxed)
+}
+
+impl Plugin for TilemapPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system(sync_tilemaps.after("game_logic_sync"));
+    }
+}
+
+impl EngineEntity for Tilemap {
+    fn label(&self) -> &str {
+        &self.label
+    }
+}
+
+/// A [`Tileset`] is a single image which is divided up into a grid of equally-sized tiles. Tiles
+/// are numbered starting at `0` in the top-left corner, going left-to-right and then
+/// top-to-bottom.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct Tileset {
+    /// The image file to use, relative to the `assets/` directory.
+    pub filepath: PathBuf,
+    /// The size of a single tile in the image, in pixels.
+    pub tile_size: Vec2,
+    /// How many tiles across the image is.
+    pub columns: u32,
+    /// How many tiles down the image is.
+    pub rows: u32,
+}
+
+impl Tileset {
+    /// `filepath` should be the path of an image file relative to the `assets/` directory, which
+    /// contains `columns` x `rows` tiles that are each `tile_size` pixels large.
+    pub fn new<P: Into<PathBuf>>(filepath: P, tile_size: Vec2, columns: u32, rows: u32) -> Self {
+        Self {
+            filepath: filepath.into(),
+            tile_size,
+            columns: columns.max(1),
+            rows: rows.max(1),
+        }
+    }
+    /// The number of tiles in the tileset
+    pub fn tile_count(&self) -> u32 {
+        self.columns * self.rows
+    }
+}
+
+/// A [`Tilemap`] is a grid of tiles from a [`Tileset`], all drawn at once. Use a tilemap instead
+/// of lots of individual sprites for walls, floors, and levels. Tiles are addressed by `(x, y)`
+/// coordinates, with `(0, 0)` in the top-left corner of the map. Each tile can be solid, in which
+/// case it is part of the tilemap's collider. Neighboring solid tiles are merged together into
+/// as few rectangles as possible.
+///
+/// Add one with [`Engine::add_tilemap`](crate::prelude::Engine::add_tilemap) and set the tiles
+/// yourself, or load one from a file with
+/// [`Engine::load_tilemap`](crate::prelude::Engine::load_tilemap).
+#[derive(Clone, Debug, PartialEq)]
+pub struct Tilemap {
+    /// READONLY: A way to identify a tilemap. This must be unique among tilemaps. Collisions with
+    /// this tilemap will use this label.
+    pub label: String,
+    /// SYNCED: The tileset that the tiles come from
+    pub tileset: Tileset,
+    /// SYNCED: Where the center of the tilemap is in 2D game space
+    pub translation: Vec2,
+    /// SYNCED: Depth of the tilemap. 0.0 (back) to 999.0 (front)
+    pub layer: f32,
+    /// SYNCED: 1.0 is the normal 100%
+    pub scale: f32,
+    /// Whether or not to calculate collisions between the solid tiles and sprites
+    pub collision: bool,
+    width: u32,
+    height: u32,
+    tiles: Vec<Option<u32>>,
+    solid: Vec<bool>,
+    // so we know when to rebuild the mesh
+    dirty: bool,
+    // so we know when to rebuild the colliders
+    solid_version: u64,
+}
+
+impl Tilemap {
+    /// Create an empty tilemap `width` tiles wide and `height` tiles tall. `label` should be a
+    /// unique string. If the map has too many tiles to be addressed, an error is printed and
+    /// `None` is returned.
+    pub fn new<L: Into<String>>(
+        label: L,
+        tileset: Tileset,
+        width: u32,
+        height: u32,
+    ) -> Option<Self> {
+        let label = label.into();
+        match Self::try_new(label.clone(), tileset, width, height) {
+            Ok(tilemap) => Some(tilemap),
+            Err(e) => {
+                eprintln!("failed creating tilemap {}: {}", label, e);
+                None
+            }
+        }
+    }
+    fn try_new(label: String, tileset: Tileset, width: u32, height: u32) -> Result<Self, String> {
+        // Tiles are indexed with `y * width + x`, so the count has to fit in a `u32`
+        let count = width
+            .checked_mul(height)
+            .ok_or_else(|| format!("a {}x{} map has too many tiles", width, height))?
+            as usize;
+        Ok(Self {
+            label,
+            tileset,
+            translation: Vec2::ZERO,
+            layer: 0.0,
+            scale: 1.0,
+            collision: true,
+            width,
+            height,
+            tiles: vec![None; count],
+            solid: vec![false; count],
+            dirty: true,
+            solid_version: next_solid_version(),
+        })
+    }
+    /// How many tiles wide the map is
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+    /// How many tiles tall the map is
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+    /// The size of the whole map in 2D game space, taking `scale` into account
+    pub fn dimensions(&self) -> Vec2 {
+        Vec2::new(self.width as f32, self.height as f32) * self.tileset.tile_size * self.scale
+    }
+    fn index(&self, x: u32, y: u32) -> Option<usize> {
+        if x < self.width && y < self.height {
+            Some((y * self.width + x) as usize)
+        } else {
+            None
+        }
+    }
+    /// The tileset index of the tile at `(x, y)`, or `None` if the tile is empty or out of bounds.
+    pub fn tile(&self, x: u32, y: u32) -> Option<u32> {
+        self.index(x, y).and_then(|i| self.tiles[i])
+    }
+    /// Set the tile at `(x, y)` to the tile from the tileset with the index `tile` (or `None` to
+    /// make it empty). Returns `false` if `(x, y)` is out of bounds.
+    pub fn set_tile(&mut self, x: u32, y: u32, tile: Option<u32>) -> bool {
+        match self.index(x, y) {
+            Some(i) => {
+                if self.tiles[i] != tile {
+                    self.tiles[i] = tile;
+                    self.dirty = true;
+                }
+                true
+            }
+            None => false,
+        }
+    }
+    /// Whether the tile at `(x, y)` is solid (part of the collider). Out of bounds tiles are not
+    /// solid.
+    pub fn is_solid(&self, x: u32, y: u32) -> bool {
+        self.index(x, y).map_or(false, |i| self.solid[i])
+    }
+    /// Set whether the tile at `(x, y)` is solid (part of the collider). Returns `false` if
+    /// `(x, y)` is out of bounds.
+    pub fn set_solid(&mut self, x: u32, y: u32, solid: bool) -> bool {
+        match self.index(x, y) {
+            Some(i) => {
+                if self.solid[i] != solid {
+                    self.solid[i] = solid;
+                    self.solid_version = next_solid_version();
+                }
+                true
+            }
+            None => false,
+        }
+    }
+    /// Make every tile that uses one of the tileset indices in `tiles` solid, and every other tile
+    /// not solid.
+    pub fn set_solid_tiles(&mut self, tiles: &[u32]) {
+        for (solid, tile) in self.solid.iter_mut().zip(self.tiles.iter()) {
+            *solid = tile.map_or(false, |tile| tiles.contains(&tile));
+        }
+        self.solid_version = next_solid_version();
+    }
+    /// The location of the center of the tile at `(x, y)` in 2D game space
+    pub fn tile_location(&self, x: u32, y: u32) -> Vec2 {
+        let tile_size = self.tileset.tile_size * self.scale;
+        let top_left = self.translation + self.dimensions() * Vec2::new(-0.5, 0.5);
+        top_left + Vec2::new(x as f32 + 0.5, -(y as f32 + 0.5)) * tile_size
+    }
+    /// The `(x, y)` coordinates of the tile at `location` in 2D game space (the mouse location,
+    /// for example), or `None` if `location` is outside of the map.
+    pub fn tile_at(&self, location: Vec2) -> Option<(u32, u32)> {
+        let tile_size = self.tileset.tile_size * self.scale;
+        let top_left = self.translation + self.dimensions() * Vec2::new(-0.5, 0.5);
+        let offset = (location - top_left) / tile_size;
+        let (x, y) = (offset.x.floor(), (-offset.y).floor());
+        if x >= 0.0 && y >= 0.0 && (x as u32) < self.width && (y as u32) < self.height {
+            Some((x as u32, y as u32))
+        } else {
+            None
+        }
+    }
+    /// The rectangles that make up the solid part of the map as `(x, y, width, height)` in tiles,
+    /// with neighboring solid tiles merged together.
+    fn solid_rects(&self) -> Vec<(u32, u32, u32, u32)> {
+        let mut merged = vec![false; self.solid.len()];
+        let available = |merged: &[bool], x: u32, y: u32| {
+            let i = (y * self.width + x) as usize;
+            self.solid[i] && !merged[i]
+        };
+        let mut rects = Vec::new();
+        for y in 0..self.height {
+            for x in 0..self.width {
+                if !available(&merged, x, y) {
+                    continue;
+                }
+                // Grow to the right as far as possible, then grow downwards for as long as the
+                // whole row underneath is available.
+                let mut width = 1;
+                while x + width < self.width && available(&merged, x + width, y) {
+                    width += 1;
+                }
+                let mut height = 1;
+                while y + height < self.height
+                    && (x..x + width).all(|x2| available(&merged, x2, y + height))
+                {
+                    height += 1;
+                }
+                for y2 in y..y + height {
+                    for x2 in x..x + width {
+                        merged[(y2 * self.width + x2) as usize] = true;
+                    }
+                }
+                rects.push((x, y, width, height));
+            }
+        }
+        rects
+    }
+    /// The colliders for the solid part of the map, relative to the center of the map (and not
+    /// scaled). Neighboring solid tiles are merged into as few rectangles as possible.
+    pub fn colliders(&self) -> Vec<Collider> {
+        let tile_size = self.tileset.tile_size;
+        let top_left = Vec2::new(self.width as f32, self.height as f32) * tile_size * 0.5;
+        let top_left = Vec2::new(-top_left.x, top_left.y);
+        self.solid_rects()
+            .into_iter()
+            .map(|(x, y, width, height)| {
+                let rect_top_left = top_left + Vec2::new(x as f32, -(y as f32)) * tile_size;
+                let size = Vec2::new(width as f32, height as f32) * tile_size;
+                Collider::rect(rect_top_left, rect_top_left + Vec2::new(size.x, -size.y))
+            })
+            .collect()
+    }
+    /// A number which changes whenever the solid tiles change
+    pub(crate) fn solid_version(&self) -> u64 {
+        self.solid_version
+    }
+    /// The solid part of the map as polygons in 2D game space
+    #[doc(hidden)]
+    pub fn collider_polygons(&self) -> Vec<Vec<Vec2>> {
+        self.colliders()
+            .iter()
+            .map(|collider| {
+                collider
+                    .points()
+                    .into_iter()
+                    .map(|point| point * self.scale + self.translation)
+                    .collect()
+            })
+            .collect()
+    }
+    /// Do the math to convert from Rusty Engine translation+scale+layer to Bevy's Transform
+    #[doc(hidden)]
+    pub fn bevy_transform(&self) -> Transform {
+        let mut transform = Transform::from_translation(self.translation.extend(self.layer));
+        transform.scale = Vec3::splat(self.scale);
+        transform
+    }
+    /// Build a single mesh containing every tile in the map
+    fn mesh(&self) -> Mesh {
+        let tile_size = self.tileset.tile_size;
+        let half = Vec2::new(self.width as f32, self.height as f32) * tile_size * 0.5;
+        let uv_size = Vec2::new(
+            1.0 / self.tileset.columns as f32,
+            1.0 / self.tileset.rows as f32,
+        );
+        let mut positions = Vec::new();
+        let mut normals = Vec::new();
+        let mut uvs = Vec::new();
+        let mut indices = Vec::new();
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let tile = match self.tile(x, y) {
+                    Some(tile) if tile < self.tileset.tile_count() => tile,
+                    _ => continue,
+                };
+                let left = x as f32 * tile_size.x - half.x;
+                let top = half.y - y as f32 * tile_size.y;
+                let (right, bottom) = (left + tile_size.x, top - tile_size.y);
+                let uv_left = (tile % self.tileset.columns) as f32 * uv_size.x;
+                let uv_top = (tile / self.tileset.columns) as f32 * uv_size.y;
+                let (uv_right, uv_bottom) = (uv_left + uv_size.x, uv_top + uv_size.y);
+                let first = positions.len() as u32;
+                positions.extend([
+                    [left, top, 0.0],
+                    [right, top, 0.0],
+                    [right, bottom, 0.0],
+                    [left, bottom, 0.0],
+                ]);
+                normals.extend([[0.0, 0.0, 1.0]; 4]);
+                uvs.extend([
+                    [uv_left, uv_top],
+                    [uv_right, uv_top],
+                    [uv_right, uv_bottom],
+                    [uv_left, uv_bottom],
+                ]);
+                indices.extend([first, first + 2, first + 1, first, first + 3, first + 2]);
+            }
+        }
+        let mut mesh = Mesh::new(PrimitiveTopology::TriangleList);
+        mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, positions);
+        mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, normals);
+        mesh.insert_attribute(Mesh::ATTRIBUTE_UV_0, uvs);
+        mesh.set_indices(Some(Indices::U32(indices)));
+        mesh
+    }
+
+    /// Load a tilemap from a file relative to the `assets/` directory. The format is chosen based
+    /// on the file extension:
+    ///
+    /// - `.ron` - Rusty Engine's own simple format (see [`TilemapFile`])
+    /// - `.tmj` or `.json` - a map saved by the [Tiled](https://www.mapeditor.org/) editor in its
+    ///   JSON format
+    /// - `.tmx` - a map saved by Tiled in its XML format. Only the CSV layer encoding is supported.
+    ///
+    /// Only the first tile layer of a Tiled map is used, and its tileset must be embedded in the
+    /// map. Tiles with a boolean custom property named `solid` or `collision` set to `true` are
+    /// made solid. If loading fails, an error is printed and `None` is returned.
+    pub fn load<L: Into<String>, P: Into<PathBuf>>(label: L, filepath: P) -> Option<Self> {
+        let label = label.into();
+        let filepath = filepath.into();
+        // Bevy's asset system is relative from the asset root, so we must be too
+        let contents = match read_asset(&filepath) {
+            Ok(bytes) => String::from_utf8_lossy(&bytes).into_owned(),
+            Err(e) => {
+                eprintln!(
+                    "failed to open tilemap file {}: {}",
+                    asset_path(&filepath).to_string_lossy(),
+                    e
+                );
+                return None;
+            }
+        };
+        let extension = filepath
+            .extension()
+            .map(|extension| extension.to_string_lossy().to_lowercase());
+        let result = match extension.as_deref() {
+            Some("ron") => ron::from_str::<TilemapFile>(&contents)
+                .map_err(|e| e.to_string())
+                .and_then(|file| file.into_tilemap(label)),
+            Some("tmj") | Some("json") => tiled::from_json(label, &filepath, &contents),
+            Some("tmx") => tiled::from_xml(label, &filepath, &contents),
+            _ => Err("unknown tilemap file extension".into()),
+        };
+        match result {
+            Ok(tilemap) => Some(tilemap),
+            Err(e) => {
+                eprintln!(
+                    "failed loading tilemap from {}: {}",
+                    asset_path(&filepath).to_string_lossy(),
+                    e
+                );
+                None
+            }
+        }
+    }
+
+    /// Create a tilemap from comma-separated values, with one line per row of tiles. Each value
+    /// is a tileset index, and empty values or negative numbers are empty tiles. Tiles using any of
+    /// the tileset indices in `solid_tiles` are made solid. If the CSV can't be understood, an
+    /// error is printed and `None` is returned.
+    pub fn from_csv<L: Into<String>>(
+        label: L,
+        tileset: Tileset,
+        csv: &str,
+        solid_tiles: &[u32],
+    ) -> Option<Self> {
+        let label = label.into();
+        match parse_csv(csv) {
+            Ok(rows) => match Self::from_rows(label.clone(), tileset, &rows) {
+                Ok(mut tilemap) => {
+                    tilemap.set_solid_tiles(solid_tiles);
+                    Some(tilemap)
+                }
+                Err(e) => {
+                    eprintln!("failed creating tilemap {}: {}", label, e);
+                    None
+                }
+            },
+            Err(e) => {
+                eprintln!("failed parsing tilemap csv for {}: {}", label, e);
+                None
+            }
+        }
+    }
+
+    /// Create a tilemap from rows of tiles. The map is as wide as the longest row.
+    fn from_rows(
+        label: String,
+        tileset: Tileset,
+        rows: &[Vec<Option<u32>>],
+    ) -> Result<Self, String> {
+        let width = rows.iter().map(Vec::len).max().unwrap_or(0);
+        let too_big = || String::from("the map has too many tiles");
+        let width = u32::try_from(width).map_err(|_| too_big())?;
+        let height = u32::try_from(rows.len()).map_err(|_| too_big())?;
+        let mut tilemap = Self::try_new(label, tileset, width, height)?;
+        for (y, row) in rows.iter().enumerate() {
+            for (x, tile) in row.iter().enumerate() {
+                tilemap.set_tile(x as u32, y as u32, *tile);
+            }
+        }
+        Ok(tilemap)
+    }
+}
+
+/// Rusty Engine's own tilemap file format, used when loading `.ron` files with
+/// [`Tilemap::load`]. It looks like this:
+///
+/// ```text
+/// (
+///     tileset: (
+///         filepath: "tilemap/tiles.png",
+///         tile_size: (32.0, 32.0),
+///         columns: 4,
+///         rows: 1,
+///     ),
+///     tiles: "
+///         1,1,1,1
+///         1,0,0,1
+///         1,1,1,1
+///     ",
+///     solid_tiles: [1],
+/// )
+/// ```
+///
+/// `tiles` uses the same CSV format as [`Tilemap::from_csv`].
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct TilemapFile {
+    pub tileset: Tileset,
+    pub tiles: String,
+    #[serde(default)]
+    pub solid_tiles: Vec<u32>,
+}
+
+impl TilemapFile {
+    fn into_tilemap(self, label: String) -> Result<Tilemap, String> {
+        let rows = parse_csv(&self.tiles)?;
+        let mut tilemap = Tilemap::from_rows(label, self.tileset, &rows)?;
+        tilemap.set_solid_tiles(&self.solid_tiles);
+        Ok(tilemap)
+    }
+}
+
+/// Parse comma-separated tile indices, one line per row. Blank lines are skipped, and empty values
+/// or negative numbers are empty tiles.
+fn parse_csv(csv: &str) -> Result<Vec<Vec<Option<u32>>>, String> {
+    csv.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(|line| {
+            line.trim_end_matches(',')
+                .split(',')
+                .map(|value| {
+                    let value = value.trim();
+                    if value.is_empty() {
+                        return Ok(None);
+                    }
+                    match value.parse::<i64>() {
+                        Ok(tile) if tile >= 0 => Ok(Some(tile as u32)),
+                        Ok(_) => Ok(None),
+                        Err(_) => Err(format!("invalid tile index '{}'", value)),
+                    }
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Loaders for maps made with the [Tiled](https://www.mapeditor.org/) editor
+mod tiled {
+    use super::{normalize, parse_csv, Tilemap, Tileset};
+    use bevy::prelude::Vec2;
+    use serde::Deserialize;
+    use std::path::Path;
+
+    // Tiled stores flipping and rotation in the top bits of each tile id. We ignore them.
+    const FLAG_MASK: u32 = 0x1FFF_FFFF;
+
+    #[derive(Deserialize)]
+    struct Map {
+        width: u32,
+        height: u32,
+        layers: Vec<Layer>,
+        tilesets: Vec<TilesetJson>,
+    }
+
+    #[derive(Deserialize)]
+    struct Layer {
+        #[serde(rename = "type")]
+        kind: String,
+        #[serde(default)]
+        data: Vec<u32>,
+    }
+
+    #[derive(Deserialize)]
+    struct TilesetJson {
+        firstgid: u32,
+        image: Option<String>,
+        source: Option<String>,
+        #[serde(default)]
+        tilewidth: f32,
+        #[serde(default)]
+        tileheight: f32,
+        #[serde(default)]
+        columns: u32,
+        #[serde(default)]
+        tilecount: u32,
+        #[serde(default)]
+        tiles: Vec<TileJson>,
+    }
+
+    #[derive(Deserialize)]
+    struct TileJson {
+        id: u32,
+        #[serde(default)]
+        properties: Vec<PropertyJson>,
+    }
+
+    #[derive(Deserialize)]
+    struct PropertyJson {
+        name: String,
+        value: serde_json::Value,
+    }
+
+    /// Whether a custom property marks a tile as solid
+    fn is_solid_property(name: &str, value: bool) -> bool {
+        value && (name == "solid" || name == "collision")
+    }
+
+    /// Turn the pieces of a Tiled map into a tilemap
+    #[allow(clippy::too_many_arguments)]
+    fn build(
+        label: String,
+        map_path: &Path,
+        image: &str,
+        tile_size: Vec2,
+        columns: u32,
+        tilecount: u32,
+        firstgid: u32,
+        width: u32,
+        height: u32,
+        gids: &[u32],
+        solid_tiles: &[u32],
+    ) -> Result<Tilemap, String> {
+        let count = width
+            .checked_mul(height)
+            .ok_or("the map has too many tiles")?;
+        if gids.len() != count as usize {
+            return Err("the tile layer doesn't match the size of the map".into());
+        }
+        // Images in Tiled maps are relative to the map file
+        let image = normalize(&map_path.parent().unwrap_or(map_path).join(image));
+        let columns = columns.max(1);
+        let rows = ((tilecount + columns - 1) / columns).max(1);
+        let tileset = Tileset::new(image, tile_size, columns, rows);
+        let mut tilemap = Tilemap::try_new(label, tileset, width, height)?;
+        for (i, gid) in gids.iter().enumerate() {
+            let gid = gid & FLAG_MASK;
+            let tile = if gid >= firstgid && gid != 0 {
+                Some(gid - firstgid)
+            } else {
+                None
+            };
+            tilemap.set_tile(i as u32 % width, i as u32 / width, tile);
+        }
+        tilemap.set_solid_tiles(solid_tiles);
+        Ok(tilemap)
+    }
+
+    /// Load a map saved in Tiled's JSON format (`.tmj`)
+    pub(super) fn from_json(
+        label: String,
+        map_path: &Path,
+        contents: &str,
+    ) -> Result<Tilemap, String> {
+        let map: Map = serde_json::from_str(contents).map_err(|e| e.to_string())?;
+        let layer = map
+            .layers
+            .iter()
+            .find(|layer| layer.kind == "tilelayer")
+            .ok_or("the map doesn't have any tile layers")?;
+        let tileset = map
+            .tilesets
+            .first()
+            .ok_or("the map doesn't have a tileset")?;
+        if tileset.source.is_some() {
+            return Err("external tilesets are not supported, embed the tileset in the map".into());
+        }
+        let image = tileset.image.as_ref().ok_or("the tileset has no image")?;
+        let solid_tiles: Vec<u32> = tileset
+            .tiles
+            .iter()
+            .filter(|tile| {
+                tile.properties.iter().any(|property| {
+                    is_solid_property(&property.name, property.value.as_bool().unwrap_or(false))
+                })
+            })
+            .map(|tile| tile.id)
+            .collect();
+        build(
+            label,
+            map_path,
+            image,
+            Vec2::new(tileset.tilewidth, tileset.tileheight),
+            tileset.columns,
+            tileset.tilecount,
+            tileset.firstgid,
+            map.width,
+            map.height,
+            &layer.data,
+            &solid_tiles,
+        )
+    }
+
+    /// Load a map saved in Tiled's XML format (`.tmx`). Only CSV-encoded layers are supported.
+    pub(super) fn from_xml(
+        label: String,
+        map_path: &Path,
+        contents: &str,
+    ) -> Result<Tilemap, String> {
+        let document = roxmltree::Document::parse(contents).map_err(|e| e.to_string())?;
+        let map = document.root_element();
+        fn attribute(node: roxmltree::Node, name: &str) -> Result<u32, String> {
+            node.attribute(name)
+                .ok_or(format!("missing attribute '{}'", name))?
+                .parse::<u32>()
+                .map_err(|e| format!("invalid attribute '{}': {}", name, e))
+        }
+        let tileset = map
+            .children()
+            .find(|node| node.has_tag_name("tileset"))
+            .ok_or("the map doesn't have a tileset")?;
+        if tileset.attribute("source").is_some() {
+            return Err("external tilesets are not supported, embed the tileset in the map".into());
+        }
+        let image = tileset
+            .children()
+            .find(|node| node.has_tag_name("image"))
+            .and_then(|node| node.attribute("source"))
+            .ok_or("the tileset has no image")?;
+        let solid_tiles = tileset
+            .children()
+            .filter(|node| node.has_tag_name("tile"))
+            .filter(|tile| {
+                tile.descendants()
+                    .filter(|node| node.has_tag_name("property"))
+                    .any(|property| {
+                        is_solid_property(
+                            property.attribute("name").unwrap_or_default(),
+                            property.attribute("value") == Some("true"),
+                        )
+                    })
+            })
+            .map(|tile| attribute(tile, "id"))
+            .collect::<Result<Vec<_>, _>>()?;
+        let data = map
+            .children()
+            .find(|node| node.has_tag_name("layer"))
+            .and_then(|layer| layer.children().find(|node| node.has_tag_name("data")))
+            .ok_or("the map doesn't have any tile layers")?;
+        if data.attribute("encoding") != Some("csv") {
+            return Err("only the CSV tile layer encoding is supported".into());
+        }
+        let gids: Vec<u32> = parse_csv(data.text().unwrap_or_default())?
+            .into_iter()
+            .flatten()
+            .map(|tile| tile.unwrap_or(0))
+            .collect();
+        let tile_size = Vec2::new(
+            attribute(tileset, "tilewidth")? as f32,
+            attribute(tileset, "tileheight")? as f32,
+        );
+        build(
+            label,
+            map_path,
+            image,
+            tile_size,
+            attribute(tileset, "columns")?,
+            attribute(tileset, "tilecount")?,
+            attribute(tileset, "firstgid")?,
+            attribute(map, "width")?,
+            attribute(map, "height")?,
+            &gids,
+            &solid_tiles,
+        )
+    }
+}
+
+/// Resolve `.` and `..` in a relative path, since Bevy's asset server doesn't
+fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            PathComponent::CurDir => {}
+            PathComponent::ParentDir => {
+                normalized.pop();
+            }
+            other => normalized.push(other),
+        }
+    }
+    normalized
+}
+
+/// Component to mark the Bevy entity which draws a tilemap
+#[derive(Component)]
+#[doc(hidden)]
+pub struct TilemapMesh;
+
+/// The Bevy side of a tilemap
+struct TilemapEntity {
+    entity: Entity,
+    mesh: Handle<Mesh>,
+    material: Handle<ColorMaterial>,
+    tileset: Tileset,
+}
+
+/// system - rebuild the meshes of tilemaps whose tiles changed, and keep their transforms in sync
+fn sync_tilemaps(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    mut engine: ResMut<Engine>,
+    mut tilemap_entities: Local<HashMap<String, TilemapEntity>>,
+    mut transform_query: Query<&mut Transform, With<TilemapMesh>>,
+) {
+    // Remove the entities for any tilemaps that the user removed
+    tilemap_entities.retain(|label, tilemap_entity| {
+        let keep = engine.tilemaps.contains_key(label);
+        if !keep {
+            commands.entity(tilemap_entity.entity).despawn();
+        }
+        keep
+    });
+
+    for tilemap in engine.tilemaps.values_mut() {
+        let tilemap_entity = match tilemap_entities.get_mut(&tilemap.label) {
+            Some(tilemap_entity) => tilemap_entity,
+            None => {
+                let mesh = meshes.add(tilemap.mesh());
+                let material = materials.add(ColorMaterial {
+                    color: Color::WHITE,
+                    texture: Some(asset_server.load(tilemap.tileset.filepath.clone())),
+                });
+                let entity = commands
+                    .spawn_bundle(MaterialMesh2dBundle {
+                        mesh: Mesh2dHandle(mesh.clone()),
+                        material: material.clone(),
+                        transform: tilemap.bevy_transform(),
+                        ..Default::default()
+                    })
+                    .insert(TilemapMesh)
+                    .id();
+                tilemap.dirty = false;
+                tilemap_entities.insert(
+                    tilemap.label.clone(),
+                    TilemapEntity {
+                        entity,
+                        mesh,
+                        material,
+                        tileset: tilemap.tileset.clone(),
+                    },
+                );
+                continue;
+            }
+        };
+        if tilemap_entity.tileset != tilemap.tileset {
+            if tilemap_entity.tileset.filepath != tilemap.tileset.filepath {
+                if let Some(material) = materials.get_mut(&tilemap_entity.material) {
+                    material.texture = Some(asset_server.load(tilemap.tileset.filepath.clone()));
+                }
+            }
+            tilemap_entity.tileset = tilemap.tileset.clone();
+            // The tile size or layout of the tileset may have changed
+            tilemap.dirty = true;
+        }
+        if tilemap.dirty {
+            if let Some(mesh) = meshes.get_mut(&tilemap_entity.mesh) {
+                *mesh = tilemap.mesh();
+            }
+            tilemap.dirty = false;
+        }
+        if let Ok(mut transform) = transform_query.get_mut(tilemap_entity.entity) {
+            *transform = tilemap.bevy_transform();
+        }
+    }
+}
@@ -12,13 +12,23 @@ pub trait EngineEntity {
     fn label(&self) -> &str;
 }
 
-#[derive(Debug, Default)]
+#[derive(Debug)]
 pub struct EngineRepo<Entity, FromSource> {
     repo: HashMap<String, Entity>,
     /// This just for having FromSource generic type.
     _phantom: PhantomData<FromSource>,
 }
 
+// Written out by hand, because deriving it would require `Entity` and `FromSource` to be `Default`
+impl<Entity, FromSource> Default for EngineRepo<Entity, FromSource> {
+    fn default() -> Self {
+        Self {
+            repo: HashMap::default(),
+            _phantom: PhantomData,
+        }
+    }
+}
+
 // ! Combines Deref and DerefMut and our special functions for HashMap<String, Entity>.
 
 impl<Entity, FromSource> Deref for EngineRepo<Entity, FromSource> {
@@ -0,0 +1,593 @@
+//! Facilities for tweening: smoothly changing the translation, rotation, scale, layer, color, or
+//! alpha of a sprite, text, or the camera over time, with an easing curve.
+
+use crate::prelude::Engine;
+use bevy::prelude::{Color, Vec2};
+use std::f32::consts::PI;
+
+/// The standard easing curves. Each curve takes the fraction of the tween's duration that has
+/// passed (`0.0` to `1.0`) and returns how far along the change should be. `In` curves start
+/// slowly, `Out` curves end slowly, and `InOut` curves do both. See <https://easings.net> for
+/// pictures of each curve.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Easing {
+    Linear,
+    QuadIn,
+    QuadOut,
+    QuadInOut,
+    CubicIn,
+    CubicOut,
+    CubicInOut,
+    QuartIn,
+    QuartOut,
+    QuartInOut,
+    SineIn,
+    SineOut,
+    SineInOut,
+    ExpoIn,
+    ExpoOut,
+    ExpoInOut,
+    CircIn,
+    CircOut,
+    CircInOut,
+    /// Pulls back a little before going forward
+    BackIn,
+    /// Overshoots a little and then settles back
+    BackOut,
+    BackInOut,
+    ElasticIn,
+    /// Overshoots and springs back and forth a few times
+    ElasticOut,
+    ElasticInOut,
+    BounceIn,
+    /// Bounces like a ball dropped on the floor
+    BounceOut,
+    BounceInOut,
+}
+
+impl Default for Easing {
+    fn default() -> Self {
+        Self::Linear
+    }
+}
+
+impl Easing {
+    /// Apply the easing curve to `t`, which is clamped to `0.0..=1.0`. The result starts at `0.0`
+    /// and ends at `1.0`, but may go outside of that range in between (see `Back` and `Elastic`).
+    pub fn ease(&self, t: f32) -> f32 {
+        let t = t.clamp(0.0, 1.0);
+        // Build the `Out` and `InOut` versions of a curve from its `In` version
+        let out = |ease_in: fn(f32) -> f32| 1.0 - ease_in(1.0 - t);
+        let in_out = |ease_in: fn(f32) -> f32| {
+            if t < 0.5 {
+                ease_in(t * 2.0) * 0.5
+            } else {
+                1.0 - ease_in((1.0 - t) * 2.0) * 0.5
+            }
+        };
+        match self {
+            Easing::Linear => t,
+            Easing::QuadIn => quad_in(t),
+            Easing::QuadOut => out(quad_in),
+            Easing::QuadInOut => in_out(quad_in),
+            Easing::CubicIn => cubic_in(t),
+            Easing::CubicOut => out(cubic_in),
+            Easing::CubicInOut => in_out(cubic_in),
+            Easing::QuartIn => quart_in(t),
+            Easing::QuartOut => out(quart_in),
+            Easing::QuartInOut => in_out(quart_in),
+            Easing::SineIn => sine_in(t),
+            Easing::SineOut => out(sine_in),
+            Easing::SineInOut => in_out(sine_in),
+            Easing::ExpoIn => expo_in(t),
+            Easing::ExpoOut => out(expo_in),
+            Easing::ExpoInOut => in_out(expo_in),
+            Easing::CircIn => circ_in(t),
+            Easing::CircOut => out(circ_in),
+            Easing::CircInOut => in_out(circ_in),
+            Easing::BackIn => back_in(t),
+            Easing::BackOut => out(back_in),
+            Easing::BackInOut => in_out(back_in),
+            Easing::ElasticIn => elastic_in(t),
+            Easing::ElasticOut => out(elastic_in),
+            Easing::ElasticInOut => in_out(elastic_in),
+            Easing::BounceIn => bounce_in(t),
+            Easing::BounceOut => out(bounce_in),
+            Easing::BounceInOut => in_out(bounce_in),
+        }
+    }
+}
+
+fn quad_in(t: f32) -> f32 {
+    t * t
+}
+
+fn cubic_in(t: f32) -> f32 {
+    t * t * t
+}
+
+fn quart_in(t: f32) -> f32 {
+    t * t * t * t
+}
+
+fn sine_in(t: f32) -> f32 {
+    1.0 - (t * PI * 0.5).cos()
+}
+
+fn expo_in(t: f32) -> f32 {
+    if t <= 0.0 {
+        0.0
+    } else {
+        2.0f32.powf(10.0 * t - 10.0)
+    }
+}
+
+fn circ_in(t: f32) -> f32 {
+    1.0 - (1.0 - t * t).max(0.0).sqrt()
+}
+
+fn back_in(t: f32) -> f32 {
+    const OVERSHOOT: f32 = 1.70158;
+    t * t * ((OVERSHOOT + 1.0) * t - OVERSHOOT)
+}
+
+fn elastic_in(t: f32) -> f32 {
+    if t <= 0.0 || t >= 1.0 {
+        t
+    } else {
+        -(2.0f32.powf(10.0 * t - 10.0)) * ((t * 10.0 - 10.75) * (2.0 * PI / 3.0)).sin()
+    }
+}
+
+fn bounce_in(t: f32) -> f32 {
+    // The bounce curve is naturally described as an `Out` curve, so flip it around
+    let t = 1.0 - t;
+    let bounce_out = if t < 1.0 / 2.75 {
+        7.5625 * t * t
+    } else if t < 2.0 / 2.75 {
+        let t = t - 1.5 / 2.75;
+        7.5625 * t * t + 0.75
+    } else if t < 2.5 / 2.75 {
+        let t = t - 2.25 / 2.75;
+        7.5625 * t * t + 0.9375
+    } else {
+        let t = t - 2.625 / 2.75;
+        7.5625 * t * t + 0.984375
+    };
+    1.0 - bounce_out
+}
+
+/// What a [`Tween`] changes
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum TweenTarget {
+    /// The sprite with this label, or if there isn't one, the text with this label
+    Label(String),
+    /// The [`Camera`](crate::prelude::Camera). Only `Translation`, `Rotation`, and `Scale` (the
+    /// camera's `zoom`) can be tweened.
+    Camera,
+}
+
+impl From<&str> for TweenTarget {
+    fn from(label: &str) -> Self {
+        Self::Label(label.to_string())
+    }
+}
+
+impl From<String> for TweenTarget {
+    fn from(label: String) -> Self {
+        Self::Label(label)
+    }
+}
+
+/// Which property of the target a [`Tween`] changes. The value to tween to must be the matching
+/// type: a `Vec2` for `Translation`, a `Color` for `Color`, and an `f32` for everything else.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum TweenProperty {
+    Translation,
+    Rotation,
+    Scale,
+    Layer,
+    Color,
+    /// Just the alpha (opacity) of the color, from `0.0` (invisible) to `1.0` (opaque)
+    Alpha,
+}
+
+/// A value that a property can be tweened to
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TweenValue {
+    F32(f32),
+    Vec2(Vec2),
+    Color(Color),
+}
+
+impl From<f32> for TweenValue {
+    fn from(value: f32) -> Self {
+        Self::F32(value)
+    }
+}
+
+impl From<Vec2> for TweenValue {
+    fn from(value: Vec2) -> Self {
+        Self::Vec2(value)
+    }
+}
+
+impl From<Color> for TweenValue {
+    fn from(value: Color) -> Self {
+        Self::Color(value)
+    }
+}
+
+impl TweenValue {
+    /// The value `t` of the way from `self` to `to`. Returns `None` if the values are different
+    /// types.
+    fn lerp(&self, to: &TweenValue, t: f32) -> Option<TweenValue> {
+        match (self, to) {
+            (TweenValue::F32(from), TweenValue::F32(to)) => {
+                Some(TweenValue::F32(from + (to - from) * t))
+            }
+            (TweenValue::Vec2(from), TweenValue::Vec2(to)) => {
+                Some(TweenValue::Vec2(*from + (*to - *from) * t))
+            }
+            (TweenValue::Color(from), TweenValue::Color(to)) => {
+                let from = from.as_rgba_f32();
+                let to = to.as_rgba_f32();
+                let lerp = |i: usize| from[i] + (to[i] - from[i]) * t;
+                Some(TweenValue::Color(Color::rgba(
+                    lerp(0),
+                    lerp(1),
+                    lerp(2),
+                    lerp(3),
+                )))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Identifies a [`Tween`], so you can tell which tween a [`TweenEvent`] is for.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct TweenId(pub u64);
+
+/// How many times a [`Tween`] plays
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TweenRepeat {
+    /// Play once (the default)
+    Once,
+    /// Play this many times in total
+    Times(u32),
+    /// Play until the tween is removed from [`Engine::tweens`](crate::prelude::Engine::tweens)
+    Forever,
+}
+
+impl Default for TweenRepeat {
+    fn default() -> Self {
+        Self::Once
+    }
+}
+
+/// One step in a [`Tween`]'s sequence
+#[derive(Clone, Debug, PartialEq)]
+struct TweenStep {
+    // `None` for a pause
+    change: Option<(TweenProperty, TweenValue)>,
+    duration: f32,
+    easing: Easing,
+    // the value of the property when the step first started
+    from: Option<TweenValue>,
+}
+
+/// A [`Tween`] smoothly changes a property of a sprite, text, or the camera over time. Start one
+/// with [`Engine::tween`](crate::prelude::Engine::tween), and add more steps to play afterwards
+/// with [`then`](Tween::then) and [`wait`](Tween::wait). When a tween is done, it is removed from
+/// [`Engine::tweens`](crate::prelude::Engine::tweens) and a [`TweenEvent`] is added to
+/// [`Engine::tween_events`](crate::prelude::Engine::tween_events). Tweens whose target no longer
+/// exists are removed without an event.
+///
+/// ```rust,no_run
+/// # use rusty_engine::prelude::*;
+/// #
+/// # fn main() {
+/// # let mut game = Game::new();
+/// // Slide the title in, wait a second, and then fade it out
+/// let _ = game.add_text("title", "Hello!");
+/// game.tween(
+///     "title",
+///     TweenProperty::Translation,
+///     Vec2::new(0.0, 100.0),
+///     0.5,
+///     Easing::BackOut,
+/// )
+/// .wait(1.0)
+/// .then(TweenProperty::Alpha, 0.0, 0.5, Easing::Linear);
+/// # game.run(());
+/// # }
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct Tween {
+    /// READONLY: Identifies the tween in [`TweenEvent`]s
+    pub id: TweenId,
+    /// READONLY: What the tween changes
+    pub target: TweenTarget,
+    /// SYNCED: How many seconds to wait before starting. Defaults to `0.0`.
+    pub delay: f32,
+    /// SYNCED: How many times to play the tween. Defaults to [`TweenRepeat::Once`].
+    pub repeat: TweenRepeat,
+    /// SYNCED: If `true`, every other time the tween plays, it plays backwards, so that the target
+    /// goes back and forth. Defaults to `false`.
+    pub yoyo: bool,
+    /// SYNCED: If `true`, the tween is frozen where it is. Defaults to `false`.
+    pub paused: bool,
+    steps: Vec<TweenStep>,
+    // which step is playing, how long it has been playing, and how many times the whole sequence
+    // has played so far
+    step_index: usize,
+    step_elapsed: f32,
+    plays: u32,
+}
+
+impl Tween {
+    /// Create a tween which changes `property` of `target` to `to` over `duration` seconds.
+    /// Usually you will want to use [`Engine::tween`](crate::prelude::Engine::tween) instead.
+    pub fn new<T: Into<TweenTarget>, V: Into<TweenValue>>(
+        id: TweenId,
+        target: T,
+        property: TweenProperty,
+        to: V,
+        duration: f32,
+        easing: Easing,
+    ) -> Self {
+        let mut tween = Self {
+            id,
+            target: target.into(),
+            delay: 0.0,
+            repeat: TweenRepeat::Once,
+            yoyo: false,
+            paused: false,
+            steps: Vec::new(),
+            step_index: 0,
+            step_elapsed: 0.0,
+            plays: 0,
+        };
+        tween.then(property, to, duration, easing);
+        tween
+    }
+
+    /// Add a step which changes `property` of the same target to `to` over `duration` seconds,
+    /// once the previous steps are done.
+    pub fn then<V: Into<TweenValue>>(
+        &mut self,
+        property: TweenProperty,
+        to: V,
+        duration: f32,
+        easing: Easing,
+    ) -> &mut Self {
+        self.steps.push(TweenStep {
+            change: Some((property, to.into())),
+            duration: duration.max(0.0),
+            easing,
+            from: None,
+        });
+        self
+    }
+
+    /// Add a step which waits `duration` seconds without changing anything, once the previous
+    /// steps are done.
+    pub fn wait(&mut self, duration: f32) -> &mut Self {
+        self.steps.push(TweenStep {
+            change: None,
+            duration: duration.max(0.0),
+            easing: Easing::Linear,
+            from: None,
+        });
+        self
+    }
+
+    /// Whether the tween is currently playing backwards (see `yoyo`)
+    fn reversed(&self) -> bool {
+        self.yoyo && self.plays % 2 == 1
+    }
+
+    /// Whether the tween has played as many times as it is supposed to
+    fn finished(&self) -> bool {
+        match self.repeat {
+            TweenRepeat::Once => self.plays >= 1,
+            TweenRepeat::Times(times) => self.plays >= times.max(1),
+            TweenRepeat::Forever => false,
+        }
+    }
+
+    /// Advance the tween by `delta` seconds, changing the target in `engine`. Returns `false` if
+    /// the tween is done and should be removed.
+    fn update(&mut self, engine: &mut Engine, delta: f32) -> bool {
+        if self.paused {
+            return true;
+        }
+        let mut remaining = delta;
+        if self.delay > 0.0 {
+            self.delay -= remaining;
+            if self.delay > 0.0 {
+                return true;
+            }
+            remaining = -self.delay;
+            self.delay = 0.0;
+        }
+        let total_duration: f32 = self.steps.iter().map(|step| step.duration).sum();
+        loop {
+            let reversed = self.reversed();
+            let index = if reversed {
+                self.steps.len() - 1 - self.step_index
+            } else {
+                self.step_index
+            };
+            let step = &mut self.steps[index];
+            self.step_elapsed += remaining;
+            let t = if step.duration > 0.0 {
+                (self.step_elapsed / step.duration).min(1.0)
+            } else {
+                1.0
+            };
+            if let Some((property, to)) = step.change {
+                if step.from.is_none() {
+                    match get(engine, &self.target, property) {
+                        Some(from) => step.from = Some(from),
+                        None => return false,
+                    }
+                }
+                // Unwrap: Can't crash because we just set it
+                let from = step.from.unwrap();
+                let eased = if reversed {
+                    step.easing.ease(1.0 - t)
+                } else {
+                    step.easing.ease(t)
+                };
+                let value = match from.lerp(&to, eased) {
+                    Some(value) => value,
+                    None => {
+                        eprintln!(
+                            "warning: can't tween {:?} of {:?} to {:?} -- the value is the wrong type",
+                            property, self.target, to
+                        );
+                        return false;
+                    }
+                };
+                if !set(engine, &self.target, property, value) {
+                    return false;
+                }
+            }
+            if t < 1.0 {
+                return true;
+            }
+            // This step is done, carry any leftover time over to the next step
+            remaining = (self.step_elapsed - step.duration).max(0.0);
+            self.step_elapsed = 0.0;
+            self.step_index += 1;
+            if self.step_index < self.steps.len() {
+                continue;
+            }
+            self.step_index = 0;
+            self.plays += 1;
+            // Don't spin forever on a repeating tween that takes no time
+            if self.finished() || total_duration <= 0.0 {
+                return !self.finished();
+            }
+        }
+    }
+}
+
+/// A [`TweenEvent`] is added to [`Engine::tween_events`](crate::prelude::Engine::tween_events)
+/// when a [`Tween`] finishes.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TweenEvent {
+    pub id: TweenId,
+    pub target: TweenTarget,
+}
+
+/// Get the current value of a property, or `None` if the target doesn't exist or doesn't have that
+/// property.
+fn get(engine: &Engine, target: &TweenTarget, property: TweenProperty) -> Option<TweenValue> {
+    let value = match target {
+        TweenTarget::Label(label) => {
+            if let Some(sprite) = engine.sprites.get(label) {
+                match property {
+                    TweenProperty::Translation => sprite.translation.into(),
+                    TweenProperty::Rotation => sprite.rotation.into(),
+                    TweenProperty::Scale => sprite.scale.into(),
+                    TweenProperty::Layer => sprite.layer.into(),
+                    TweenProperty::Color => sprite.color.into(),
+                    TweenProperty::Alpha => sprite.color.a().into(),
+                }
+            } else if let Some(text) = engine.texts.get(label) {
+                match property {
+                    TweenProperty::Translation => text.translation.into(),
+                    TweenProperty::Rotation => text.rotation.into(),
+                    TweenProperty::Scale => text.scale.into(),
+                    TweenProperty::Layer => text.layer.into(),
+                    TweenProperty::Color => text.color.into(),
+                    TweenProperty::Alpha => text.color.a().into(),
+                }
+            } else {
+                return None;
+            }
+        }
+        TweenTarget::Camera => match property {
+            TweenProperty::Translation => engine.camera.translation.into(),
+            TweenProperty::Rotation => engine.camera.rotation.into(),
+            TweenProperty::Scale => engine.camera.zoom.into(),
+            TweenProperty::Layer | TweenProperty::Color | TweenProperty::Alpha => {
+                eprintln!("warning: the camera doesn't have a {:?} to tween", property);
+                return None;
+            }
+        },
+    };
+    Some(value)
+}
+
+/// Set a property to a value. Returns `false` if the target doesn't exist or the value is the wrong
+/// type.
+fn set(
+    engine: &mut Engine,
+    target: &TweenTarget,
+    property: TweenProperty,
+    value: TweenValue,
+) -> bool {
+    use TweenProperty as P;
+    use TweenValue as V;
+    match target {
+        TweenTarget::Label(label) => {
+            if let Some(sprite) = engine.sprites.get_mut(label) {
+                match (property, value) {
+                    (P::Translation, V::Vec2(value)) => sprite.translation = value,
+                    (P::Rotation, V::F32(value)) => sprite.rotation = value,
+                    (P::Scale, V::F32(value)) => sprite.scale = value,
+                    (P::Layer, V::F32(value)) => sprite.layer = value,
+                    (P::Color, V::Color(value)) => sprite.color = value,
+                    (P::Alpha, V::F32(value)) => {
+                        sprite.color.set_a(value.clamp(0.0, 1.0));
+                    }
+                    _ => return false,
+                }
+            } else if let Some(text) = engine.texts.get_mut(label) {
+                match (property, value) {
+                    (P::Translation, V::Vec2(value)) => text.translation = value,
+                    (P::Rotation, V::F32(value)) => text.rotation = value,
+                    (P::Scale, V::F32(value)) => text.scale = value,
+                    (P::Layer, V::F32(value)) => text.layer = value,
+                    (P::Color, V::Color(value)) => text.color = value,
+                    (P::Alpha, V::F32(value)) => {
+                        text.color.set_a(value.clamp(0.0, 1.0));
+                    }
+                    _ => return false,
+                }
+            } else {
+                return false;
+            }
+        }
+        TweenTarget::Camera => match (property, value) {
+            (P::Translation, V::Vec2(value)) => engine.camera.translation = value,
+            (P::Rotation, V::F32(value)) => engine.camera.rotation = value,
+            (P::Scale, V::F32(value)) => engine.camera.zoom = value,
+            _ => return false,
+        },
+    }
+    true
+}
+
+/// Advance all of the engine's tweens by `delta` seconds, removing the ones that are done and
+/// reporting them in `engine.tween_events`.
+pub(crate) fn update_tweens(engine: &mut Engine, delta: f32) {
+    let mut tweens = std::mem::take(&mut engine.tweens);
+    tweens.retain_mut(|tween| {
+        let keep = tween.update(engine, delta);
+        if !keep && tween.finished() {
+            engine.tween_events.push(TweenEvent {
+                id: tween.id,
+                target: tween.target.clone(),
+            });
+        }
+        keep
+    });
+    // Keep any tweens that were started while we were busy
+    tweens.append(&mut engine.tweens);
+    engine.tweens = tweens;
+}